@@ -3,10 +3,12 @@ extern crate alloc;
 
 mod filesystem;
 mod jvm;
+mod network;
 mod platform;
 
 pub use self::{
     filesystem::MemoryFilesystem,
     jvm::run_jvm_test,
-    platform::{TestPlatform, TestPlatformEvent},
+    network::{LoopbackHttp, LoopbackNetwork},
+    platform::{TestAudioSink, TestPlatform, TestPlatformEvent},
 };
@@ -0,0 +1,125 @@
+use alloc::{boxed::Box, collections::VecDeque, format, vec::Vec};
+
+use hashbrown::HashMap;
+use spin::Mutex;
+
+use wie_backend::{HttpRequest, HttpResponse, HttpTransport, Network, NetworkError, SocketId, SocketType};
+
+#[derive(Default)]
+struct LoopbackSocket {
+    connected: bool,
+    buffer: VecDeque<u8>,
+}
+
+/// In-memory `Network` for tests. Every host resolves to 127.0.0.1, connecting
+/// succeeds on any port except 0, and data sent on a socket is echoed back to it.
+#[derive(Default)]
+pub struct LoopbackNetwork {
+    sockets: Mutex<HashMap<SocketId, LoopbackSocket>>,
+    last_socket_id: Mutex<SocketId>,
+}
+
+impl LoopbackNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Network for LoopbackNetwork {
+    fn resolve(&self, host: &str) -> Result<[u8; 4], NetworkError> {
+        if host.is_empty() {
+            return Err(NetworkError::HostNotFound);
+        }
+
+        Ok([127, 0, 0, 1])
+    }
+
+    fn socket(&self, _socket_type: SocketType) -> Result<SocketId, NetworkError> {
+        let mut last_socket_id = self.last_socket_id.lock();
+        *last_socket_id += 1;
+
+        self.sockets.lock().insert(*last_socket_id, LoopbackSocket::default());
+
+        Ok(*last_socket_id)
+    }
+
+    fn connect(&self, socket: SocketId, _addr: [u8; 4], port: u16) -> Result<(), NetworkError> {
+        let mut sockets = self.sockets.lock();
+        let socket = sockets.get_mut(&socket).ok_or(NetworkError::InvalidSocket)?;
+        if port == 0 {
+            return Err(NetworkError::ConnectionRefused);
+        }
+
+        socket.connected = true;
+
+        Ok(())
+    }
+
+    fn send(&self, socket: SocketId, data: &[u8]) -> Result<usize, NetworkError> {
+        let mut sockets = self.sockets.lock();
+        let socket = sockets.get_mut(&socket).ok_or(NetworkError::InvalidSocket)?;
+        if !socket.connected {
+            return Err(NetworkError::NotConnected);
+        }
+
+        socket.buffer.extend(data);
+
+        Ok(data.len())
+    }
+
+    fn recv(&self, socket: SocketId, buf: &mut [u8]) -> Result<usize, NetworkError> {
+        let mut sockets = self.sockets.lock();
+        let socket = sockets.get_mut(&socket).ok_or(NetworkError::InvalidSocket)?;
+        if !socket.connected {
+            return Err(NetworkError::NotConnected);
+        }
+        if socket.buffer.is_empty() {
+            return Err(NetworkError::WouldBlock);
+        }
+
+        let length = buf.len().min(socket.buffer.len());
+        let data = socket.buffer.drain(..length).collect::<Vec<_>>();
+        buf[..length].copy_from_slice(&data);
+
+        Ok(length)
+    }
+
+    fn readable(&self, socket: SocketId) -> bool {
+        self.sockets.lock().get(&socket).is_some_and(|x| !x.buffer.is_empty())
+    }
+
+    fn close(&self, socket: SocketId) {
+        self.sockets.lock().remove(&socket);
+    }
+}
+
+/// In-memory `HttpTransport` for tests. `http://` urls get `200 OK` with the request
+/// body and headers echoed back and the method in `X-Method`; other urls fail to resolve.
+#[derive(Default)]
+pub struct LoopbackHttp;
+
+impl LoopbackHttp {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for LoopbackHttp {
+    async fn request(&self, request: HttpRequest) -> Result<HttpResponse, NetworkError> {
+        if !request.url.starts_with("http://") {
+            return Err(NetworkError::HostNotFound);
+        }
+
+        let mut headers = request.headers;
+        headers.push(("X-Method".into(), request.method));
+        headers.push(("Content-Length".into(), format!("{}", request.body.len())));
+
+        Ok(HttpResponse {
+            status: 200,
+            message: "OK".into(),
+            headers,
+            body: request.body,
+        })
+    }
+}
@@ -8,10 +8,16 @@ use core::sync::atomic::{AtomicU64, Ordering};
 
 use hashbrown::HashMap;
 use spin::Mutex;
-use wie_backend::{AudioSink, Database, DatabaseRepository, Filesystem, Instant, Platform, RecordId, Screen, canvas::Image};
+use wie_backend::{
+    AudioSink, CaptureAudioSink, Database, DatabaseRepository, Filesystem, HttpTransport, Instant, Network, Platform, RecordId, Screen, VirtualClock,
+    WaveVoice, canvas::Image,
+};
 use wie_util::Result;
 
-use crate::filesystem::MemoryFilesystem;
+use crate::{
+    filesystem::MemoryFilesystem,
+    network::{LoopbackHttp, LoopbackNetwork},
+};
 
 static TEST_EPOCH: AtomicU64 = AtomicU64::new(0);
 
@@ -25,6 +31,10 @@ pub struct TestPlatform {
     event_handler: Option<Box<dyn Fn(TestPlatformEvent) + Sync + Send>>,
     fs: Arc<MemoryFilesystem>,
     db: Arc<MemoryDatabaseRepository>,
+    network: LoopbackNetwork,
+    http: LoopbackHttp,
+    audio_sink: TestAudioSink,
+    clock: Option<VirtualClock>,
 }
 
 impl Default for TestPlatform {
@@ -40,6 +50,10 @@ impl TestPlatform {
             event_handler: None,
             fs: Arc::new(MemoryFilesystem::default()),
             db: Arc::new(MemoryDatabaseRepository::default()),
+            network: LoopbackNetwork::new(),
+            http: LoopbackHttp::new(),
+            audio_sink: TestAudioSink::new(),
+            clock: None,
         }
     }
 
@@ -52,8 +66,24 @@ impl TestPlatform {
             event_handler: Some(Box::new(event_handler)),
             fs: Arc::new(MemoryFilesystem::default()),
             db: Arc::new(MemoryDatabaseRepository::default()),
+            network: LoopbackNetwork::new(),
+            http: LoopbackHttp::new(),
+            audio_sink: TestAudioSink::new(),
+            clock: None,
         }
     }
+
+    /// Runs the app on `clock` instead of the shared test clock, e.g. for checkpoints.
+    pub fn with_virtual_clock(mut self, clock: VirtualClock) -> Self {
+        self.clock = Some(clock);
+
+        self
+    }
+
+    /// The sink handed to the app; clones share its recording, so keep one before boxing the platform.
+    pub fn audio(&self) -> TestAudioSink {
+        self.audio_sink.clone()
+    }
 }
 
 impl Platform for TestPlatform {
@@ -62,10 +92,18 @@ impl Platform for TestPlatform {
     }
 
     fn now(&self) -> Instant {
+        if let Some(clock) = &self.clock {
+            return clock.now();
+        }
+
         let epoch = TEST_EPOCH.fetch_add(8, Ordering::SeqCst);
         Instant::from_epoch_millis(epoch) // TODO
     }
 
+    fn virtual_clock(&self) -> Option<&VirtualClock> {
+        self.clock.as_ref()
+    }
+
     fn database_repository(&self) -> &dyn DatabaseRepository {
         self.db.as_ref()
     }
@@ -74,8 +112,16 @@ impl Platform for TestPlatform {
         self.fs.as_ref()
     }
 
+    fn network(&self) -> &dyn Network {
+        &self.network
+    }
+
+    fn http(&self) -> &dyn HttpTransport {
+        &self.http
+    }
+
     fn audio_sink(&self) -> Box<dyn AudioSink> {
-        Box::new(TestAudioSink)
+        Box::new(self.audio_sink.clone())
     }
 
     fn write_stdout(&self, buf: &[u8]) {
@@ -169,35 +215,68 @@ impl Database for MemoryDatabase {
     }
 }
 
-pub struct TestAudioSink;
+/// Records everything the app plays, for tests that check audio output; see [`CaptureAudioSink`].
+#[derive(Clone)]
+pub struct TestAudioSink {
+    capture: CaptureAudioSink,
+}
+
+impl Default for TestAudioSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestAudioSink {
+    pub fn new() -> Self {
+        // same clock as TestPlatform::now, without advancing it
+        let clock = Box::new(|| Instant::from_epoch_millis(TEST_EPOCH.load(Ordering::SeqCst)));
+
+        Self {
+            capture: CaptureAudioSink::new(clock),
+        }
+    }
+
+    pub fn capture(&self) -> &CaptureAudioSink {
+        &self.capture
+    }
+}
 
 impl AudioSink for TestAudioSink {
-    fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) {
-        todo!()
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) -> WaveVoice {
+        self.capture.play_wave(channel, sampling_rate, wave_data)
+    }
+
+    fn stop_wave(&self, voice: WaveVoice) {
+        self.capture.stop_wave(voice)
+    }
+
+    fn set_wave_volume(&self, voice: WaveVoice, volume: u8) {
+        self.capture.set_wave_volume(voice, volume)
     }
 
-    fn midi_note_on(&self, _channel_id: u8, _note: u8, _velocity: u8) {
-        todo!()
+    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.capture.midi_note_on(channel_id, note, velocity)
     }
 
-    fn midi_note_off(&self, _channel_id: u8, _note: u8, _velocity: u8) {
-        todo!()
+    fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.capture.midi_note_off(channel_id, note, velocity)
     }
 
-    fn midi_program_change(&self, _channel_id: u8, _program: u8) {
-        todo!()
+    fn midi_program_change(&self, channel_id: u8, program: u8) {
+        self.capture.midi_program_change(channel_id, program)
     }
 
-    fn midi_control_change(&self, _channel_id: u8, _control: u8, _value: u8) {
-        todo!()
+    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
+        self.capture.midi_control_change(channel_id, control, value)
     }
 
-    fn midi_pitch_bend(&self, _channel_id: u8, _value: u16) {
-        todo!()
+    fn midi_pitch_bend(&self, channel_id: u8, value: u16) {
+        self.capture.midi_pitch_bend(channel_id, value)
     }
 
-    fn midi_sysex(&self, _data: &[u8]) {
-        todo!()
+    fn midi_sysex(&self, data: &[u8]) {
+        self.capture.midi_sysex(data)
     }
 }
 
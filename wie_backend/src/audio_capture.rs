@@ -0,0 +1,385 @@
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+
+use spin::Mutex;
+
+use crate::{
+    audio_sink::{AudioSink, WaveVoice},
+    time::Instant,
+};
+
+// volumes are percentages, as the platform media apis use them
+const MAX_VOLUME: u8 = 100;
+// 500 ticks per quarter note at the default 120bpm makes one tick a millisecond
+const TICKS_PER_QUARTER: u16 = 500;
+const TEMPO: u32 = 500_000;
+
+pub type CaptureClock = Box<dyn Fn() -> Instant + Send + Sync>;
+
+struct CapturedWave {
+    time: u64,
+    sampling_rate: u32,
+    data: Vec<i16>,
+    // samples actually heard, less than the data if the wave was stopped early
+    length: usize,
+    // volume changes from the given sample on
+    volumes: Vec<(usize, u8)>,
+}
+
+impl CapturedWave {
+    fn volume_at(&self, index: usize) -> u8 {
+        self.volumes
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= index)
+            .map(|(_, x)| *x)
+            .unwrap_or(MAX_VOLUME)
+    }
+}
+
+// what a handle from `play_wave` refers to; waves that can't be heard aren't recorded but may still reach the output
+struct CapturedVoice {
+    wave: Option<usize>,
+    output: Option<WaveVoice>,
+}
+
+#[derive(Default)]
+struct Capture {
+    waves: Vec<CapturedWave>,
+    voices: Vec<CapturedVoice>,
+    midi: Vec<(u64, Vec<u8>)>,
+}
+
+/// [`AudioSink`] that records everything played through it, optionally passing it on to another sink, so a session's
+/// sound can be saved as a WAV file of the wave output and a standard midi file of the midi output.
+///
+/// Events are timed in milliseconds since the sink was created, read from `clock`, which should be the platform's `now`.
+/// Clones share the same recording.
+#[derive(Clone)]
+pub struct CaptureAudioSink {
+    clock: Arc<CaptureClock>,
+    start: Instant,
+    capture: Arc<Mutex<Capture>>,
+    output: Option<Arc<dyn AudioSink>>,
+}
+
+impl CaptureAudioSink {
+    pub fn new(clock: CaptureClock) -> Self {
+        let start = clock();
+
+        Self {
+            clock: Arc::new(clock),
+            start,
+            capture: Arc::new(Mutex::new(Capture::default())),
+            output: None,
+        }
+    }
+
+    /// Returns a sink sharing this recording that also plays everything on `output`.
+    pub fn with_output(&self, output: Box<dyn AudioSink>) -> Self {
+        Self {
+            output: Some(output.into()),
+            ..self.clone()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let capture = self.capture.lock();
+
+        capture.waves.is_empty() && capture.midi.is_empty()
+    }
+
+    /// Mixes the captured waves onto a mono 16-bit timeline at `sample_rate` and returns it as a RIFF WAVE file.
+    pub fn to_wav(&self, sample_rate: u32) -> Vec<u8> {
+        let capture = self.capture.lock();
+
+        let length = capture
+            .waves
+            .iter()
+            .map(|x| Self::wave_start(x.time, sample_rate) + Self::resampled_length(x, sample_rate))
+            .max()
+            .unwrap_or(0);
+
+        let mut mixed = vec![0i32; length];
+        for wave in &capture.waves {
+            let start = Self::wave_start(wave.time, sample_rate);
+            for i in 0..Self::resampled_length(wave, sample_rate) {
+                let source = (i as u64 * wave.sampling_rate as u64 / sample_rate as u64) as usize;
+                mixed[start + i] += wave.data[source] as i32 * wave.volume_at(source) as i32 / MAX_VOLUME as i32;
+            }
+        }
+
+        let data_length = (mixed.len() * 2) as u32;
+        let mut result = Vec::with_capacity(44 + data_length as usize);
+        result.extend_from_slice(b"RIFF");
+        result.extend_from_slice(&(36 + data_length).to_le_bytes());
+        result.extend_from_slice(b"WAVEfmt ");
+        result.extend_from_slice(&16u32.to_le_bytes());
+        result.extend_from_slice(&1u16.to_le_bytes()); // pcm
+        result.extend_from_slice(&1u16.to_le_bytes()); // mono
+        result.extend_from_slice(&sample_rate.to_le_bytes());
+        result.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        result.extend_from_slice(&2u16.to_le_bytes());
+        result.extend_from_slice(&16u16.to_le_bytes());
+        result.extend_from_slice(b"data");
+        result.extend_from_slice(&data_length.to_le_bytes());
+        for sample in mixed {
+            result.extend_from_slice(&(sample.clamp(i16::MIN as i32, i16::MAX as i32) as i16).to_le_bytes());
+        }
+
+        result
+    }
+
+    /// Returns the captured midi messages as a format 0 standard midi file, one tick per millisecond.
+    pub fn to_midi(&self) -> Vec<u8> {
+        let capture = self.capture.lock();
+
+        let mut track = Vec::new();
+        // tempo, so players don't have to assume the default
+        track.extend_from_slice(&[0x00, 0xff, 0x51, 0x03]);
+        track.extend_from_slice(&TEMPO.to_be_bytes()[1..]);
+
+        let mut last_time = 0;
+        for (time, message) in &capture.midi {
+            // a clock running backwards shouldn't produce a negative delta
+            Self::write_var_len(&mut track, time.saturating_sub(last_time) as u32);
+            last_time = last_time.max(*time);
+
+            if let Some(sysex) = message.strip_prefix(&[0xf0]) {
+                track.push(0xf0);
+                Self::write_var_len(&mut track, sysex.len() as u32);
+                track.extend_from_slice(sysex);
+            } else {
+                track.extend_from_slice(message);
+            }
+        }
+        track.extend_from_slice(&[0x00, 0xff, 0x2f, 0x00]);
+
+        let mut result = Vec::with_capacity(22 + track.len());
+        result.extend_from_slice(b"MThd");
+        result.extend_from_slice(&6u32.to_be_bytes());
+        result.extend_from_slice(&0u16.to_be_bytes()); // format 0
+        result.extend_from_slice(&1u16.to_be_bytes()); // one track
+        result.extend_from_slice(&TICKS_PER_QUARTER.to_be_bytes());
+        result.extend_from_slice(b"MTrk");
+        result.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        result.extend_from_slice(&track);
+
+        result
+    }
+
+    fn now(&self) -> u64 {
+        (self.clock)().raw().saturating_sub(self.start.raw())
+    }
+
+    fn wave_start(time: u64, sample_rate: u32) -> usize {
+        (time * sample_rate as u64 / 1000) as usize
+    }
+
+    fn resampled_length(wave: &CapturedWave, sample_rate: u32) -> usize {
+        (wave.length as u64 * sample_rate as u64 / wave.sampling_rate as u64) as usize
+    }
+
+    fn write_var_len(out: &mut Vec<u8>, value: u32) {
+        let mut bytes = vec![(value & 0x7f) as u8];
+        let mut value = value >> 7;
+        while value != 0 {
+            bytes.push((value & 0x7f) as u8 | 0x80);
+            value >>= 7;
+        }
+
+        out.extend(bytes.into_iter().rev());
+    }
+
+    // the recorded wave behind `voice` and how many of its samples have been heard by now
+    fn with_captured_wave<F>(&self, voice: WaveVoice, f: F) -> Option<WaveVoice>
+    where
+        F: FnOnce(&mut CapturedWave, usize),
+    {
+        let time = self.now();
+        let mut capture = self.capture.lock();
+        let captured = capture.voices.get(voice.checked_sub(1)? as usize)?;
+        let output = captured.output;
+
+        if let Some(index) = captured.wave {
+            let wave = &mut capture.waves[index];
+            let elapsed = (time.saturating_sub(wave.time) * wave.sampling_rate as u64 / 1000) as usize;
+            f(wave, elapsed.min(wave.length));
+        }
+
+        output
+    }
+
+    fn record_midi(&self, message: &[u8]) {
+        let time = self.now();
+        self.capture.lock().midi.push((time, message.to_vec()));
+    }
+}
+
+impl AudioSink for CaptureAudioSink {
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) -> WaveVoice {
+        let output = self.output.as_ref().map(|x| x.play_wave(channel, sampling_rate, wave_data));
+
+        let time = self.now();
+        let mut capture = self.capture.lock();
+
+        let wave = if sampling_rate != 0 && !wave_data.is_empty() {
+            capture.waves.push(CapturedWave {
+                time,
+                sampling_rate,
+                data: wave_data.to_vec(),
+                length: wave_data.len(),
+                volumes: Vec::new(),
+            });
+
+            Some(capture.waves.len() - 1)
+        } else {
+            None
+        };
+        capture.voices.push(CapturedVoice { wave, output });
+
+        capture.voices.len() as _
+    }
+
+    fn stop_wave(&self, voice: WaveVoice) {
+        let output = self.with_captured_wave(voice, |wave, elapsed| wave.length = elapsed);
+
+        if let (Some(output), Some(voice)) = (&self.output, output) {
+            output.stop_wave(voice);
+        }
+    }
+
+    fn set_wave_volume(&self, voice: WaveVoice, volume: u8) {
+        let output = self.with_captured_wave(voice, |wave, elapsed| wave.volumes.push((elapsed, volume.min(MAX_VOLUME))));
+
+        if let (Some(output), Some(voice)) = (&self.output, output) {
+            output.set_wave_volume(voice, volume);
+        }
+    }
+
+    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.record_midi(&[0x90 | channel_id, note, velocity]);
+
+        if let Some(output) = &self.output {
+            output.midi_note_on(channel_id, note, velocity);
+        }
+    }
+
+    fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.record_midi(&[0x80 | channel_id, note, velocity]);
+
+        if let Some(output) = &self.output {
+            output.midi_note_off(channel_id, note, velocity);
+        }
+    }
+
+    fn midi_program_change(&self, channel_id: u8, program: u8) {
+        self.record_midi(&[0xc0 | channel_id, program]);
+
+        if let Some(output) = &self.output {
+            output.midi_program_change(channel_id, program);
+        }
+    }
+
+    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
+        self.record_midi(&[0xb0 | channel_id, control, value]);
+
+        if let Some(output) = &self.output {
+            output.midi_control_change(channel_id, control, value);
+        }
+    }
+
+    fn midi_pitch_bend(&self, channel_id: u8, value: u16) {
+        self.record_midi(&[0xe0 | channel_id, (value & 0x7f) as u8, ((value >> 7) & 0x7f) as u8]);
+
+        if let Some(output) = &self.output {
+            output.midi_pitch_bend(channel_id, value);
+        }
+    }
+
+    fn midi_sysex(&self, data: &[u8]) {
+        self.record_midi(data);
+
+        if let Some(output) = &self.output {
+            output.midi_sysex(data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, sync::Arc, vec::Vec};
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::{audio_sink::AudioSink, time::Instant};
+
+    use super::CaptureAudioSink;
+
+    fn sink_with_clock() -> (CaptureAudioSink, Arc<AtomicU64>) {
+        let now = Arc::new(AtomicU64::new(1000));
+        let now_clone = now.clone();
+
+        (
+            CaptureAudioSink::new(Box::new(move || Instant::from_epoch_millis(now_clone.load(Ordering::SeqCst)))),
+            now,
+        )
+    }
+
+    #[test]
+    fn test_capture_midi() {
+        let (sink, now) = sink_with_clock();
+
+        sink.midi_program_change(1, 5);
+        now.fetch_add(300, Ordering::SeqCst);
+        sink.midi_note_on(1, 60, 100);
+        sink.midi_sysex(&[0xf0, 0x7e, 0x7f, 0x09, 0x01, 0xf7]);
+
+        let midi = sink.to_midi();
+        assert_eq!(&midi[..14], b"MThd\0\0\0\x06\0\0\0\x01\x01\xf4");
+        assert_eq!(
+            &midi[22..],
+            &[
+                0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20, // tempo
+                0x00, 0xc1, 0x05, // program change
+                0x82, 0x2c, 0x91, 0x3c, 0x64, // note on 300 ticks later
+                0x00, 0xf0, 0x05, 0x7e, 0x7f, 0x09, 0x01, 0xf7, // sysex
+                0x00, 0xff, 0x2f, 0x00,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_wave() {
+        let (sink, now) = sink_with_clock();
+        assert!(sink.is_empty());
+
+        sink.play_wave(0, 4000, &[100, 200]);
+        now.fetch_add(1, Ordering::SeqCst);
+        sink.play_wave(1, 8000, &[1000, 1000]);
+        assert!(!sink.is_empty());
+
+        let wav = sink.to_wav(8000);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[36..40], b"data");
+
+        // the second wave starts 1ms, 8 samples, in
+        let samples = wav[44..].chunks_exact(2).map(|x| i16::from_le_bytes([x[0], x[1]])).collect::<Vec<_>>();
+        assert_eq!(samples.len(), 10);
+        assert_eq!(&samples[..4], &[100, 100, 200, 200]);
+        assert_eq!(&samples[8..], &[1000, 1000]);
+    }
+
+    #[test]
+    fn test_capture_wave_volume_and_stop() {
+        let (sink, now) = sink_with_clock();
+
+        let voice = sink.play_wave(0, 1000, &[1000; 10]);
+        now.fetch_add(2, Ordering::SeqCst);
+        sink.set_wave_volume(voice, 50);
+        now.fetch_add(2, Ordering::SeqCst);
+        sink.stop_wave(voice);
+
+        let wav = sink.to_wav(1000);
+        let samples = wav[44..].chunks_exact(2).map(|x| i16::from_le_bytes([x[0], x[1]])).collect::<Vec<_>>();
+        assert_eq!(samples, [1000, 1000, 500, 500]);
+    }
+}
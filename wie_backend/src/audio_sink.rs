@@ -1,5 +1,13 @@
+/// Handle to a wave started with [`AudioSink::play_wave`]. Sinks that drop a wave hand out 0, which is never a playing wave.
+pub type WaveVoice = u32;
+
 pub trait AudioSink: Sync + Send {
-    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]);
+    /// Starts playing `wave_data` alongside whatever is already playing.
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) -> WaveVoice;
+    /// Cuts a wave short; waves that already finished are ignored.
+    fn stop_wave(&self, voice: WaveVoice);
+    /// Sets the volume of a playing wave, in percent.
+    fn set_wave_volume(&self, voice: WaveVoice, volume: u8);
     fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8);
     fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8);
     fn midi_program_change(&self, channel_id: u8, program: u8);
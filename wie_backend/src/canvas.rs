@@ -1,9 +1,10 @@
+mod bdf;
+mod font;
 mod lbmp;
 
 use alloc::{borrow::Cow, boxed::Box, string::ToString, vec, vec::Vec};
 use core::mem::size_of;
 
-use ab_glyph::{Font, FontRef, ScaleFont};
 use bytemuck::{Pod, cast_slice, pod_collect_to_vec};
 use image::ImageReader;
 use num_traits::{Num, Zero};
@@ -12,9 +13,7 @@ use wie_util::{Result, WieError};
 
 use self::lbmp::decode_lbmp;
 
-lazy_static::lazy_static! {
-    static ref FONT: FontRef<'static> = FontRef::try_from_slice(include_bytes!("../../fonts/neodgm.ttf")).unwrap();
-}
+pub use self::font::{Font, FontPack, FontSize, FontStyle, LoadedFont};
 
 pub enum TextAlignment {
     Left,
@@ -49,7 +48,7 @@ pub trait Canvas: Send {
     fn image(&self) -> &dyn Image;
     fn draw(&mut self, dx: i32, dy: i32, w: u32, h: u32, src: &dyn Image, sx: i32, sy: i32, clip: Clip);
     fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, color: Color);
-    fn draw_text(&mut self, string: &str, x: i32, y: i32, text_alignment: TextAlignment, color: Color);
+    fn draw_text(&mut self, string: &str, x: i32, y: i32, font: LoadedFont<'_>, text_alignment: TextAlignment, color: Color);
     fn draw_rect(&mut self, x: i32, y: i32, w: u32, h: u32, color: Color, clip: Clip);
     fn draw_arc(&mut self, x: i32, y: i32, w: u32, h: u32, start_angle: i32, arc_angle: i32, color: Color, clip: Clip);
     fn draw_round_rect(&mut self, x: i32, y: i32, w: u32, h: u32, arc_width: u32, arc_height: u32, color: Color, clip: Clip);
@@ -410,44 +409,26 @@ where
         }
     }
 
-    fn draw_text(&mut self, string: &str, x: i32, y: i32, text_alignment: TextAlignment, color: Color) {
-        let size = 10.0; // TODO
-        let font = FONT.as_scaled(FONT.pt_to_px_scale(size).unwrap());
-
-        let total_width = string.chars().map(|c| font.h_advance(font.scaled_glyph(c).id)).sum::<f32>();
+    fn draw_text(&mut self, string: &str, x: i32, y: i32, font: LoadedFont<'_>, text_alignment: TextAlignment, color: Color) {
+        let total_width = font.string_width(string) as i32;
         let x = match text_alignment {
             TextAlignment::Left => x,
-            TextAlignment::Center => x - (total_width / 2.0) as i32,
-            TextAlignment::Right => x - total_width as i32,
+            TextAlignment::Center => x - total_width / 2,
+            TextAlignment::Right => x - total_width,
         };
 
-        let mut position = 0.0;
-        for c in string.chars() {
-            if c.is_control() {
-                continue;
-            }
-
-            let glyph = font.scaled_glyph(c);
-            let h_advance = font.h_advance(glyph.id);
-
-            if let Some(outlined_glyph) = font.outline_glyph(glyph) {
-                outlined_glyph.draw(|glyph_x: u32, glyph_y, c| {
-                    let bounds = outlined_glyph.px_bounds();
-                    self.blend_pixel(
-                        x + (glyph_x as f32 + bounds.min.x + position) as i32,
-                        y + (glyph_y as f32 + bounds.min.y + size) as i32,
-                        Color {
-                            a: (c * 255.0) as u8,
-                            r: color.r,
-                            g: color.g,
-                            b: color.b,
-                        },
-                    )
-                });
-            }
-
-            position += h_advance;
-        }
+        font.rasterize(string, |glyph_x, glyph_y, coverage| {
+            self.blend_pixel(
+                x + glyph_x,
+                y + glyph_y,
+                Color {
+                    a: (coverage * 255.0) as u8,
+                    r: color.r,
+                    g: color.g,
+                    b: color.b,
+                },
+            )
+        });
     }
 
     fn draw_rect(&mut self, x: i32, y: i32, w: u32, h: u32, color: Color, clip: Clip) {
@@ -673,12 +654,6 @@ pub fn decode_image(data: &[u8]) -> Result<Box<dyn Image>> {
     )) as Box<_>)
 }
 
-pub fn string_width(string: &str, pt_size: f32) -> f32 {
-    let font = FONT.as_scaled(FONT.pt_to_px_scale(pt_size).unwrap());
-
-    string.chars().map(|c| font.h_advance(font.scaled_glyph(c).id)).sum::<f32>()
-}
-
 #[cfg(test)]
 mod tests {
     use wie_util::Result;
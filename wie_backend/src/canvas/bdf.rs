@@ -0,0 +1,183 @@
+use alloc::{format, vec::Vec};
+
+use hashbrown::HashMap;
+
+use wie_util::{Result, WieError};
+
+// glyph bitmap font in the adobe bitmap distribution format, with unicode encodings
+
+pub struct BitmapGlyph {
+    advance: i32,
+    width: u32,
+    height: u32,
+    x_offset: i32,
+    // offset of the bottom row from the baseline, up is positive
+    y_offset: i32,
+    // rows padded to whole bytes, most significant bit leftmost
+    bitmap: Vec<u8>,
+}
+
+impl BitmapGlyph {
+    pub fn advance(&self) -> i32 {
+        self.advance
+    }
+
+    /// Calls `f` with every set pixel, relative to the glyph origin on the baseline.
+    pub fn draw(&self, mut f: impl FnMut(i32, i32)) {
+        let stride = self.width.div_ceil(8) as usize;
+
+        for row in 0..self.height as usize {
+            for column in 0..self.width as usize {
+                if self.bitmap[row * stride + column / 8] & (0x80 >> (column % 8)) != 0 {
+                    f(self.x_offset + column as i32, row as i32 - (self.height as i32 + self.y_offset));
+                }
+            }
+        }
+    }
+}
+
+pub struct BitmapFont {
+    ascent: u32,
+    descent: u32,
+    glyphs: HashMap<char, BitmapGlyph>,
+}
+
+impl BitmapFont {
+    pub fn ascent(&self) -> u32 {
+        self.ascent
+    }
+
+    pub fn height(&self) -> u32 {
+        self.ascent + self.descent
+    }
+
+    pub fn glyph(&self, c: char) -> Option<&BitmapGlyph> {
+        self.glyphs.get(&c)
+    }
+}
+
+fn invalid(message: &str) -> WieError {
+    WieError::FatalError(format!("Invalid BDF font: {message}"))
+}
+
+fn numbers<const N: usize>(line: &str) -> Result<[i32; N]> {
+    let values = line
+        .split_ascii_whitespace()
+        .map(|x| x.parse::<i32>().map_err(|_| invalid(line)))
+        .collect::<Result<Vec<_>>>()?;
+
+    values.get(..N).and_then(|x| x.try_into().ok()).ok_or_else(|| invalid(line))
+}
+
+pub fn decode_bdf(data: &[u8]) -> Result<BitmapFont> {
+    let text = core::str::from_utf8(data).map_err(|_| invalid("not a text file"))?;
+    let mut lines = text.lines().map(|x| x.trim());
+
+    if !lines.next().is_some_and(|x| x.starts_with("STARTFONT")) {
+        return Err(invalid("missing STARTFONT"));
+    }
+
+    let mut bounding_box = None;
+    let mut ascent = None;
+    let mut descent = None;
+    let mut glyphs = HashMap::new();
+
+    let mut encoding = None;
+    let mut advance = 0;
+    let mut bbx = [0; 4];
+    while let Some(line) = lines.next() {
+        let (keyword, values) = line.split_once(' ').unwrap_or((line, ""));
+
+        match keyword {
+            "FONTBOUNDINGBOX" => bounding_box = Some(numbers::<4>(values)?),
+            "FONT_ASCENT" => ascent = Some(numbers::<1>(values)?[0]),
+            "FONT_DESCENT" => descent = Some(numbers::<1>(values)?[0]),
+            "STARTCHAR" => {
+                encoding = None;
+                advance = 0;
+                bbx = bounding_box.unwrap_or_default();
+            }
+            // -1 is a glyph without a standard encoding
+            "ENCODING" => encoding = char::from_u32(numbers::<1>(values)?[0] as u32),
+            "DWIDTH" => advance = numbers::<2>(values)?[0],
+            "BBX" => bbx = numbers::<4>(values)?,
+            "BITMAP" => {
+                let [width, height, x_offset, y_offset] = bbx;
+                let stride = (width.max(0) as usize).div_ceil(8);
+
+                let mut bitmap = Vec::with_capacity(stride * height.max(0) as usize);
+                for _ in 0..height {
+                    let row = lines.next().ok_or_else(|| invalid("truncated bitmap"))?;
+                    for i in 0..stride {
+                        let byte = row.get(i * 2..i * 2 + 2).unwrap_or("00");
+                        bitmap.push(u8::from_str_radix(byte, 16).map_err(|_| invalid(row))?);
+                    }
+                }
+
+                if let Some(c) = encoding {
+                    glyphs.insert(
+                        c,
+                        BitmapGlyph {
+                            advance,
+                            width: width.max(0) as _,
+                            height: height.max(0) as _,
+                            x_offset,
+                            y_offset,
+                            bitmap,
+                        },
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // the bounding box stands in for the optional ascent and descent properties
+    let [_, box_height, _, box_y_offset] = bounding_box.ok_or_else(|| invalid("missing FONTBOUNDINGBOX"))?;
+    let ascent = ascent.unwrap_or(box_height + box_y_offset).max(0) as u32;
+    let descent = descent.unwrap_or(-box_y_offset).max(0) as u32;
+
+    Ok(BitmapFont { ascent, descent, glyphs })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::decode_bdf;
+
+    #[test]
+    fn test_decode_bdf() {
+        let bdf = b"STARTFONT 2.1
+FONT -test-fixed-medium-r-normal--4-40-75-75-c-40-iso10646-1
+SIZE 4 75 75
+FONTBOUNDINGBOX 4 4 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 3
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 1
+STARTCHAR uni AC00
+ENCODING 44032
+SWIDTH 1000 0
+DWIDTH 5 0
+BBX 3 2 1 0
+BITMAP
+A0
+40
+ENDCHAR
+ENDFONT
+";
+        let font = decode_bdf(bdf).unwrap();
+        assert_eq!(font.ascent(), 3);
+        assert_eq!(font.height(), 4);
+        assert!(font.glyph('a').is_none());
+
+        let glyph = font.glyph('가').unwrap();
+        assert_eq!(glyph.advance(), 5);
+
+        let mut pixels = Vec::new();
+        glyph.draw(|x, y| pixels.push((x, y)));
+        assert_eq!(pixels, [(1, -2), (3, -2), (2, -1)]);
+    }
+}
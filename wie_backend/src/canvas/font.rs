@@ -0,0 +1,384 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use ab_glyph::{Font as _, FontArc, PxScale, PxScaleFont, ScaleFont, point};
+
+use wie_util::{Result, WieError};
+
+use super::bdf::{BitmapFont, decode_bdf};
+
+lazy_static::lazy_static! {
+    // fills in whatever the installed font pack lacks
+    static ref BUILTIN_FACE: FontFace = FontFace::Outline(FontArc::try_from_slice(include_bytes!("../../../fonts/neodgm.ttf")).unwrap());
+}
+
+// faux italic leans glyphs this many pixels right per pixel above the baseline
+const ITALIC_SLANT: f32 = 0.2;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FontSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl FontSize {
+    fn index(self) -> usize {
+        match self {
+            Self::Small => 0,
+            Self::Medium => 1,
+            Self::Large => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FontStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+/// A size and style of the system typeface, as chosen through the platform font apis.
+///
+/// Look it up in the system's [`FontPack`] with [`FontPack::get`] to measure or draw text with it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Font {
+    pub size: FontSize,
+    pub style: FontStyle,
+}
+
+impl Font {
+    pub fn new(size: FontSize, style: FontStyle) -> Self {
+        Self { size, style }
+    }
+}
+
+/// A [`Font`] in the faces of a [`FontPack`].
+///
+/// Metrics are in whole pixels, which is what games lay their text out with.
+#[derive(Clone, Copy)]
+pub struct LoadedFont<'a> {
+    font: Font,
+    pack: &'a FontPack,
+}
+
+impl LoadedFont<'_> {
+    /// Line height, which is ascent plus descent.
+    pub fn height(&self) -> u32 {
+        self.pack.primary_face(self.font.size).height(self.pixel_size())
+    }
+
+    /// Distance from the top of the line to the baseline.
+    pub fn ascent(&self) -> u32 {
+        self.pack.primary_face(self.font.size).ascent(self.pixel_size())
+    }
+
+    pub fn descent(&self) -> u32 {
+        self.height() - self.ascent()
+    }
+
+    pub fn char_width(&self, c: char) -> u32 {
+        let mut buf = [0; 4];
+
+        self.string_width(c.encode_utf8(&mut buf))
+    }
+
+    pub fn string_width(&self, string: &str) -> u32 {
+        let pixel_size = self.pixel_size();
+
+        string
+            .chars()
+            .filter(|c| !c.is_control())
+            .map(|c| self.pack.glyph_face(self.font.size, c).advance(pixel_size, c) + self.bold_offset() as f32)
+            .sum::<f32>()
+            .round() as u32
+    }
+
+    /// Rasterizes `string` with the top left of its line at the origin, calling `f` with each covered pixel and its coverage.
+    pub fn rasterize(&self, string: &str, mut f: impl FnMut(i32, i32, f32)) {
+        let pixel_size = self.pixel_size();
+        let primary_face = self.pack.primary_face(self.font.size);
+        let ascent = primary_face.ascent(pixel_size) as i32;
+
+        let mut position = 0.0;
+        for c in string.chars() {
+            if c.is_control() {
+                continue;
+            }
+
+            let face = self.pack.glyph_face(self.font.size, c);
+            face.draw(pixel_size, c, position, ascent, &mut |x, y, coverage| {
+                let x = if self.font.style.italic {
+                    x + ((ascent - y) as f32 * ITALIC_SLANT).round() as i32
+                } else {
+                    x
+                };
+
+                // bold is the glyph drawn again a pixel to the right
+                for offset in 0..=self.bold_offset() {
+                    f(x + offset, y, coverage);
+                }
+            });
+            position += face.advance(pixel_size, c) + self.bold_offset() as f32;
+        }
+
+        if self.font.style.underline {
+            let y = (ascent + 1).min(primary_face.height(pixel_size) as i32 - 1);
+            for x in 0..position.round() as i32 {
+                f(x, y, 1.0);
+            }
+        }
+    }
+
+    fn pixel_size(&self) -> u32 {
+        self.pack.pixel_size(self.font.size)
+    }
+
+    fn bold_offset(&self) -> i32 {
+        self.font.style.bold as _
+    }
+}
+
+#[derive(Clone)]
+enum FontFace {
+    // scalable, drawn at whatever size is asked for
+    Outline(FontArc),
+    // drawn at its own size only
+    Bitmap(Arc<BitmapFont>),
+}
+
+impl FontFace {
+    fn scaled(font: &FontArc, pixel_size: u32) -> PxScaleFont<&FontArc> {
+        font.as_scaled(PxScale::from(pixel_size as f32))
+    }
+
+    fn has_glyph(&self, c: char) -> bool {
+        match self {
+            Self::Outline(font) => font.glyph_id(c).0 != 0,
+            Self::Bitmap(font) => font.glyph(c).is_some(),
+        }
+    }
+
+    fn height(&self, pixel_size: u32) -> u32 {
+        match self {
+            Self::Outline(_) => pixel_size,
+            Self::Bitmap(font) => font.height(),
+        }
+    }
+
+    fn ascent(&self, pixel_size: u32) -> u32 {
+        match self {
+            Self::Outline(font) => (Self::scaled(font, pixel_size).ascent().round().max(0.0) as u32).min(pixel_size),
+            Self::Bitmap(font) => font.ascent(),
+        }
+    }
+
+    fn advance(&self, pixel_size: u32, c: char) -> f32 {
+        match self {
+            Self::Outline(font) => {
+                let font = Self::scaled(font, pixel_size);
+
+                font.h_advance(font.glyph_id(c))
+            }
+            Self::Bitmap(font) => font.glyph(c).map(|x| x.advance()).unwrap_or(0) as f32,
+        }
+    }
+
+    fn draw(&self, pixel_size: u32, c: char, x: f32, baseline: i32, f: &mut dyn FnMut(i32, i32, f32)) {
+        match self {
+            Self::Outline(font) => {
+                let font = Self::scaled(font, pixel_size);
+
+                let mut glyph = font.scaled_glyph(c);
+                glyph.position = point(x, baseline as f32);
+
+                if let Some(outlined_glyph) = font.outline_glyph(glyph) {
+                    let bounds = outlined_glyph.px_bounds();
+                    outlined_glyph
+                        .draw(|glyph_x, glyph_y, coverage| f(bounds.min.x as i32 + glyph_x as i32, bounds.min.y as i32 + glyph_y as i32, coverage));
+                }
+            }
+            Self::Bitmap(font) => {
+                if let Some(glyph) = font.glyph(c) {
+                    glyph.draw(|glyph_x, glyph_y| f(x.round() as i32 + glyph_x, baseline + glyph_y, 1.0));
+                }
+            }
+        }
+    }
+}
+
+/// Typefaces to draw text with in place of the built-in font, and the line height of each font size.
+///
+/// Glyphs come from the first face that has them, preferring bitmap faces made for the requested line height, then
+/// scalable faces, then the bitmap face closest in height. The built-in font fills in anything the pack lacks.
+#[derive(Clone)]
+pub struct FontPack {
+    faces: Vec<FontFace>,
+    pixel_sizes: [u32; 3],
+}
+
+impl FontPack {
+    pub fn new() -> Self {
+        Self {
+            faces: Vec::new(),
+            pixel_sizes: [12, 16, 20],
+        }
+    }
+
+    /// Adds a TrueType or OpenType font.
+    pub fn add_truetype(&mut self, data: Vec<u8>) -> Result<()> {
+        let font = FontArc::try_from_vec(data).map_err(|_| WieError::FatalError("Invalid TrueType font".into()))?;
+        self.faces.push(FontFace::Outline(font));
+
+        Ok(())
+    }
+
+    /// Adds a BDF bitmap font with unicode encodings.
+    pub fn add_bdf(&mut self, data: &[u8]) -> Result<()> {
+        self.faces.push(FontFace::Bitmap(Arc::new(decode_bdf(data)?)));
+
+        Ok(())
+    }
+
+    /// Sets the line height in pixels of the small, medium and large font sizes.
+    pub fn set_pixel_sizes(&mut self, small: u32, medium: u32, large: u32) {
+        self.pixel_sizes = [small, medium, large];
+    }
+
+    pub fn get(&self, font: Font) -> LoadedFont<'_> {
+        LoadedFont { font, pack: self }
+    }
+
+    fn pixel_size(&self, size: FontSize) -> u32 {
+        self.pixel_sizes[size.index()]
+    }
+
+    // faces in the order glyphs are looked up in for `size`
+    fn faces(&self, size: FontSize) -> impl Iterator<Item = &FontFace> {
+        let pixel_size = self.pixel_size(size);
+
+        let exact_bitmaps = self
+            .faces
+            .iter()
+            .filter(move |x| matches!(x, FontFace::Bitmap(font) if font.height() == pixel_size));
+        let outlines = self.faces.iter().filter(|x| matches!(x, FontFace::Outline(_)));
+
+        let mut other_bitmaps = self
+            .faces
+            .iter()
+            .filter(|x| matches!(x, FontFace::Bitmap(font) if font.height() != pixel_size))
+            .collect::<Vec<_>>();
+        other_bitmaps.sort_by_key(|x| x.height(pixel_size).abs_diff(pixel_size));
+
+        exact_bitmaps.chain(outlines).chain(other_bitmaps)
+    }
+
+    fn primary_face(&self, size: FontSize) -> &FontFace {
+        self.faces(size).next().unwrap_or(&BUILTIN_FACE)
+    }
+
+    fn glyph_face(&self, size: FontSize, c: char) -> &FontFace {
+        self.faces(size).find(|x| x.has_glyph(c)).unwrap_or(&BUILTIN_FACE)
+    }
+}
+
+impl Default for FontPack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{Font, FontFace, FontPack, FontSize, FontStyle};
+
+    #[test]
+    fn test_metrics() {
+        let pack = FontPack::new();
+        let small = pack.get(Font::new(FontSize::Small, FontStyle::default()));
+        let large = pack.get(Font::new(FontSize::Large, FontStyle::default()));
+
+        assert_eq!(small.height(), 12);
+        assert_eq!(small.ascent() + small.descent(), small.height());
+        assert!(large.height() > small.height());
+        assert!(large.string_width("abc") > small.string_width("abc"));
+        assert_eq!(small.string_width("\n"), 0);
+    }
+
+    #[test]
+    fn test_styles() {
+        let pack = FontPack::new();
+        let plain = pack.get(Font::default());
+        let bold = pack.get(Font::new(
+            FontSize::Medium,
+            FontStyle {
+                bold: true,
+                ..Default::default()
+            },
+        ));
+        let underline = pack.get(Font::new(
+            FontSize::Medium,
+            FontStyle {
+                underline: true,
+                ..Default::default()
+            },
+        ));
+
+        assert_eq!(bold.string_width("ab"), plain.string_width("ab") + 2);
+
+        let mut pixels = Vec::new();
+        plain.rasterize("a", |x, y, _| pixels.push((x, y)));
+        assert!(!pixels.is_empty());
+        assert!(pixels.iter().all(|&(x, y)| x >= 0 && y >= 0 && y < plain.height() as i32));
+
+        let mut underline_pixels = Vec::new();
+        underline.rasterize(" ", |x, y, _| underline_pixels.push((x, y)));
+        assert_eq!(underline_pixels.len(), plain.string_width(" ") as usize);
+    }
+
+    #[test]
+    fn test_font_pack() {
+        // 8px bitmap font with only 'a', a filled 4x6 box standing on the baseline
+        let bdf = b"STARTFONT 2.1
+FONTBOUNDINGBOX 4 8 0 -2
+FONT_ASCENT 6
+FONT_DESCENT 2
+STARTCHAR a
+ENCODING 97
+DWIDTH 5 0
+BBX 4 6 0 0
+BITMAP
+F0
+F0
+F0
+F0
+F0
+F0
+ENDCHAR
+ENDFONT
+";
+        let mut pack = FontPack::new();
+        pack.add_bdf(bdf).unwrap();
+        pack.set_pixel_sizes(8, 16, 20);
+
+        let bitmap = pack.primary_face(FontSize::Small);
+        assert!(matches!(bitmap, FontFace::Bitmap(_)));
+        assert_eq!(bitmap.height(8), 8);
+        assert_eq!(bitmap.ascent(8), 6);
+        assert!(matches!(pack.glyph_face(FontSize::Small, 'a'), FontFace::Bitmap(_)));
+        assert!(matches!(pack.glyph_face(FontSize::Small, 'b'), FontFace::Outline(_)));
+
+        let mut pixels = Vec::new();
+        bitmap.draw(8, 'a', 0.0, 6, &mut |x, y, _| pixels.push((x, y)));
+        assert_eq!(pixels.len(), 24);
+        assert!(pixels.iter().all(|&(x, y)| (0..4).contains(&x) && (0..6).contains(&y)));
+
+        // bitmap faces of another height are only used for glyphs no scalable face has
+        pack.add_truetype(include_bytes!("../../../fonts/neodgm.ttf").to_vec()).unwrap();
+        assert!(matches!(pack.primary_face(FontSize::Medium), FontFace::Outline(_)));
+        assert!(matches!(pack.primary_face(FontSize::Small), FontFace::Bitmap(_)));
+    }
+}
@@ -1,23 +1,24 @@
-use alloc::{boxed::Box, sync::Arc};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc};
 use core::{
     future::Future,
+    mem,
     pin::Pin,
     task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
 };
 
-use hashbrown::HashMap;
 use spin::Mutex;
 
 use wie_util::{Result, WieError};
 
-use crate::time::Instant;
+use crate::{snapshot::SnapshotWriter, time::Instant};
 
 type Task = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
 
 pub struct ExecutorInner {
     current_task_id: Option<usize>,
-    tasks: HashMap<usize, Task>,
-    sleeping_tasks: HashMap<usize, Instant>,
+    // ordered by task id so polling order doesn't depend on hasher seeds
+    tasks: BTreeMap<usize, Task>,
+    sleeping_tasks: BTreeMap<usize, Instant>,
     last_task_id: usize,
     last_now: Instant,
 }
@@ -69,8 +70,8 @@ impl Executor {
     pub fn new() -> Self {
         let inner = Arc::new(Mutex::new(ExecutorInner {
             current_task_id: None,
-            tasks: HashMap::new(),
-            sleeping_tasks: HashMap::new(),
+            tasks: BTreeMap::new(),
+            sleeping_tasks: BTreeMap::new(),
             last_task_id: 0,
             last_now: Instant::from_epoch_millis(0),
         }));
@@ -112,19 +113,22 @@ impl Executor {
         loop {
             let now = now();
 
-            if now > end {
+            if now > end || self.all_sleeping(now) {
                 break;
             }
 
-            {
-                let inner = self.inner.lock();
-                let running_task_count = inner.tasks.len() - inner.sleeping_tasks.len();
-                if running_task_count == 0 && !inner.sleeping_tasks.is_empty() {
-                    let next_wakeup = *inner.sleeping_tasks.values().min().unwrap();
-                    if now < next_wakeup {
-                        break;
-                    }
-                }
+            self.step(now)?;
+        }
+
+        Ok(())
+    }
+
+    /// Tick against a virtual clock: `now` stays fixed, so instead of a wall-clock budget
+    /// we step until every task sleeps past `now`, no task is left, or `max_steps` is reached.
+    pub fn tick_virtual(&mut self, now: Instant, max_steps: usize) -> Result<()> {
+        for _ in 0..max_steps {
+            if self.inner.lock().tasks.is_empty() || self.all_sleeping(now) {
+                break;
             }
 
             self.step(now)?;
@@ -133,6 +137,19 @@ impl Executor {
         Ok(())
     }
 
+    fn all_sleeping(&self, now: Instant) -> bool {
+        let inner = self.inner.lock();
+        let running_task_count = inner.tasks.len() - inner.sleeping_tasks.len();
+        if running_task_count == 0 && !inner.sleeping_tasks.is_empty() {
+            let next_wakeup = *inner.sleeping_tasks.values().min().unwrap();
+            if now < next_wakeup {
+                return true;
+            }
+        }
+
+        false
+    }
+
     pub fn current_task_id(&self) -> u64 {
         self.inner.lock().current_task_id.unwrap() as _
     }
@@ -140,9 +157,9 @@ impl Executor {
     fn step(&mut self, now: Instant) -> Result<()> {
         self.inner.lock().last_now = now;
 
-        let mut next_tasks = HashMap::new();
-        let tasks = self.inner.lock().tasks.drain().collect::<HashMap<_, _>>();
-        let mut sleeping_tasks = self.inner.lock().sleeping_tasks.drain().collect::<HashMap<_, _>>();
+        let mut next_tasks = BTreeMap::new();
+        let tasks = mem::take(&mut self.inner.lock().tasks);
+        let mut sleeping_tasks = mem::take(&mut self.inner.lock().sleeping_tasks);
 
         for (task_id, mut task) in tasks.into_iter() {
             let item = sleeping_tasks.get(&task_id);
@@ -177,6 +194,23 @@ impl Executor {
         Ok(())
     }
 
+    /// Writes which tasks exist and when the sleeping ones wake up. The tasks themselves are rust futures, so this is
+    /// only for checking that a checkpoint replay rebuilt the same ones.
+    pub(crate) fn write_state(&self, writer: &mut SnapshotWriter) {
+        let inner = self.inner.lock();
+
+        writer.write_u64(inner.last_task_id as _);
+        writer.write_u32(inner.tasks.len() as _);
+        for task_id in inner.tasks.keys() {
+            writer.write_u64(*task_id as _);
+        }
+        writer.write_u32(inner.sleeping_tasks.len() as _);
+        for (task_id, until) in &inner.sleeping_tasks {
+            writer.write_u64(*task_id as _);
+            writer.write_u64(until.raw());
+        }
+    }
+
     pub(crate) fn sleep(&self, timeout: u64) {
         let task_id = self.inner.lock().current_task_id.unwrap();
 
@@ -200,3 +234,45 @@ impl Executor {
         unsafe { Waker::from_raw(noop_raw_waker()) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use wie_util::Result;
+
+    use crate::{task::SleepFuture, time::Instant};
+
+    use super::Executor;
+
+    #[test]
+    fn test_tick_virtual() -> Result<()> {
+        let mut executor = Executor::new();
+        let wakeups = Arc::new(AtomicU32::new(0));
+
+        let executor_clone = executor.clone();
+        let wakeups_clone = wakeups.clone();
+        executor.spawn(async move || {
+            for _ in 0..3 {
+                SleepFuture::new(10, &executor_clone).await;
+                wakeups_clone.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        // the clock doesn't move during a tick, so the task sleeps at most once per tick
+        executor.tick_virtual(Instant::from_epoch_millis(0), 100)?;
+        assert_eq!(wakeups.load(Ordering::SeqCst), 0);
+
+        executor.tick_virtual(Instant::from_epoch_millis(5), 100)?;
+        assert_eq!(wakeups.load(Ordering::SeqCst), 0);
+
+        executor.tick_virtual(Instant::from_epoch_millis(10), 100)?;
+        assert_eq!(wakeups.load(Ordering::SeqCst), 1);
+
+        executor.tick_virtual(Instant::from_epoch_millis(30), 100)?;
+        assert_eq!(wakeups.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+
+use serde::Deserialize;
+
+use wie_util::{Result, WieError};
+
+use crate::{HandsetProfile, KeyCode};
+
+const GAME_CONFIGS_TOML: &str = include_str!("../../data/game_configs.toml");
+
+lazy_static::lazy_static! {
+    static ref GAME_CONFIGS: core::result::Result<Vec<GameConfig>, String> = parse_game_configs(GAME_CONFIGS_TOML);
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Quirk {
+    /// Don't install `data/binary_patches.toml` patches. Only the ARM platforms (KTF, LGT) install them.
+    NoBinaryPatches,
+}
+
+/// Settings for a title from `data/game_configs.toml`.
+#[derive(Clone, Debug, Default)]
+pub struct GameConfig {
+    pub name: String,
+    pub main_class: Option<String>,
+    /// `(from, to)` pairs, the app sees `to` when `from` is pressed.
+    pub key_map: Vec<(KeyCode, KeyCode)>,
+    pub quirks: Vec<Quirk>,
+    /// Applied by the frontend when it builds the [`HandsetProfile`], before its own options.
+    pub handset: HandsetOverrides,
+    hash: String,
+}
+
+impl GameConfig {
+    /// Looks up the config of the app made of `files`, matching any of them by MD5.
+    pub fn find<'a>(files: impl IntoIterator<Item = &'a [u8]>) -> Result<Option<Self>> {
+        let configs = GAME_CONFIGS.as_ref().map_err(|x| WieError::FatalError(x.clone()))?;
+
+        Ok(Self::find_in(configs, files))
+    }
+
+    fn find_in<'a>(configs: &[Self], files: impl IntoIterator<Item = &'a [u8]>) -> Option<Self> {
+        let hashes = files.into_iter().map(|x| format!("{:x}", md5::compute(x))).collect::<Vec<_>>();
+
+        let config = configs.iter().find(|x| hashes.iter().any(|hash| hash.eq_ignore_ascii_case(&x.hash)))?;
+        tracing::info!("Using game config for {}", config.name);
+
+        Some(config.clone())
+    }
+
+    pub fn has_quirk(&self, quirk: Quirk) -> bool {
+        self.quirks.contains(&quirk)
+    }
+}
+
+/// [`HandsetProfile`] fields a title overrides, `None` where it keeps the frontend's.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HandsetOverrides {
+    pub screen_size: Option<[u32; 2]>,
+    pub color_depth: Option<u32>,
+    pub total_memory: Option<u32>,
+    pub free_memory: Option<u32>,
+    pub model: Option<String>,
+    pub carrier: Option<String>,
+    pub font_sizes: Option<[u32; 3]>,
+    pub touch_screen: Option<bool>,
+}
+
+impl HandsetOverrides {
+    pub fn apply(&self, handset: &mut HandsetProfile) {
+        if let Some([width, height]) = self.screen_size {
+            handset.screen_width = width;
+            handset.screen_height = height;
+        }
+        if let Some(x) = self.color_depth {
+            handset.color_depth = x;
+        }
+        if let Some(x) = self.total_memory {
+            handset.total_memory = x;
+        }
+        if let Some(x) = self.free_memory {
+            handset.free_memory = x;
+        }
+        if let Some(x) = &self.model {
+            handset.model = x.clone();
+        }
+        if let Some(x) = &self.carrier {
+            handset.carrier = Some(x.clone());
+        }
+        if let Some(x) = self.font_sizes {
+            handset.font_sizes = x;
+        }
+        if let Some(x) = self.touch_screen {
+            handset.touch_screen = x;
+        }
+    }
+}
+
+fn parse_game_configs(toml: &str) -> core::result::Result<Vec<GameConfig>, String> {
+    let doc: RawDoc = toml::from_str(toml).map_err(|x| format!("Failed to parse data/game_configs.toml: {x}"))?;
+
+    doc.game.into_iter().map(RawGame::into_config).collect()
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawDoc {
+    #[serde(default)]
+    game: Vec<RawGame>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawGame {
+    hash: String,
+    name: String,
+    main_class: Option<String>,
+    #[serde(default)]
+    key_map: BTreeMap<String, String>,
+    #[serde(default)]
+    quirks: Vec<Quirk>,
+    #[serde(default)]
+    handset: HandsetOverrides,
+}
+
+impl RawGame {
+    fn into_config(self) -> core::result::Result<GameConfig, String> {
+        let name = self.name;
+        if self.hash.len() != 32 || !self.hash.chars().all(|x| x.is_ascii_hexdigit()) {
+            return Err(format!("game {name}: hash must be 32 hex chars"));
+        }
+
+        let key = |x: &str| KeyCode::from_name(x).ok_or_else(|| format!("game {name}: unknown key `{x}`"));
+        let key_map = self
+            .key_map
+            .iter()
+            .map(|(from, to)| Ok((key(from)?, key(to)?)))
+            .collect::<core::result::Result<_, String>>()?;
+
+        Ok(GameConfig {
+            main_class: self.main_class,
+            key_map,
+            quirks: self.quirks,
+            handset: self.handset,
+            hash: self.hash,
+            name,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HandsetProfile, KeyCode};
+
+    use super::{GameConfig, Quirk};
+
+    #[test]
+    fn test_game_configs_parse() {
+        super::GAME_CONFIGS.as_ref().unwrap();
+    }
+
+    #[test]
+    fn test_find() {
+        let configs = super::parse_game_configs(
+            r#"
+[[game]]
+hash = "0123456789abcdef0123456789abcdef"
+name = "other"
+
+[[game]]
+# md5 of "game.jar", in upper case
+hash = "B52EA42D75CB16A97DF1AB62D6593822"
+name = "test"
+main_class = "Clet"
+"#,
+        )
+        .unwrap();
+
+        let config = GameConfig::find_in(&configs, [b"descriptor".as_slice(), b"game.jar".as_slice()]).unwrap();
+        assert_eq!(config.name, "test");
+        assert_eq!(config.main_class.as_deref(), Some("Clet"));
+
+        assert!(GameConfig::find_in(&configs, [b"not a game".as_slice()]).is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        let configs = super::parse_game_configs(
+            r#"
+[[game]]
+hash = "0123456789abcdef0123456789abcdef"
+name = "test"
+key_map = { OK = "5" }
+quirks = ["no_binary_patches"]
+
+[game.handset]
+screen_size = [176, 208]
+carrier = "KTF"
+"#,
+        )
+        .unwrap();
+        let config = &configs[0];
+
+        let mut handset = HandsetProfile::default();
+        config.handset.apply(&mut handset);
+
+        assert_eq!((handset.screen_width, handset.screen_height), (176, 208));
+        assert_eq!(handset.carrier.as_deref(), Some("KTF"));
+        assert_eq!(handset.color_depth, HandsetProfile::default().color_depth);
+        assert_eq!(config.key_map, [(KeyCode::OK, KeyCode::NUM5)]);
+        assert!(config.has_quirk(Quirk::NoBinaryPatches));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let unknown_key = "[[game]]\nhash = \"0123456789abcdef0123456789abcdef\"\nname = \"test\"\nkey_map = { OK = \"NOPE\" }\n";
+        assert!(super::parse_game_configs(unknown_key).unwrap_err().contains("unknown key"));
+
+        let short_hash = "[[game]]\nhash = \"0123\"\nname = \"test\"\n";
+        assert!(super::parse_game_configs(short_hash).unwrap_err().contains("hash"));
+
+        assert!(super::parse_game_configs("[[game]]\nname = 1\n").is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use alloc::{
+    borrow::ToOwned,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::KeyCode;
+
+/// Hardware and identity of the emulated handset, as reported to apps through the platform apis.
+///
+/// Titles were often built per lcd size and check what the handset reports, so this should match the build being run.
+#[derive(Clone, Debug)]
+pub struct HandsetProfile {
+    pub screen_width: u32,
+    pub screen_height: u32,
+    /// Bits per pixel of the lcd.
+    pub color_depth: u32,
+    pub total_memory: u32,
+    pub free_memory: u32,
+    pub model: String,
+    /// Carrier name, or the carrier the app's platform belongs to if `None`.
+    pub carrier: Option<String>,
+    /// Subscriber number, called `MIN` by the korean platform apis.
+    pub phone_number: String,
+    /// Keys on the handset keypad. Frontends don't send the others.
+    pub keys: Vec<KeyCode>,
+    /// Whether the handset has a touch screen. Frontends only send pointer events if it does.
+    pub touch_screen: bool,
+    /// Media content types the handset plays.
+    pub content_types: Vec<String>,
+    /// Line height in pixels of the small, medium and large system fonts.
+    pub font_sizes: [u32; 3],
+}
+
+impl HandsetProfile {
+    /// Value of a handset property queried by id, e.g. through `MC_knlGetSystemProperty`.
+    pub fn system_property(&self, id: &str) -> Option<String> {
+        Some(match id {
+            "PHONEMODEL" => self.model.clone(),
+            "CARRIER" => self.carrier.clone()?,
+            "PHONENUMBER" => "".to_owned(), // putting this cause some game to fail authentication
+            "MIN" => self.phone_number.clone(),
+            "LCDWIDTH" => self.screen_width.to_string(),
+            "LCDHEIGHT" => self.screen_height.to_string(),
+            "COLORDEPTH" => self.color_depth.to_string(),
+            "RSSILEVEL" => "30".to_owned(),
+            "BATTERYLEVEL" => "100".to_owned(),
+            "VIBRATORLEVEL" | "ANNUN_CALL" | "ANNUN_SMS" | "ANNUN_SILENT" | "ANNUN_ALARM" | "ANNUN_SECURITY" | "CURRENTCH" | "AIRPLANE_MODE"
+            | "ROAMING_AREA" | "DS_LOCK" => "0".to_owned(),
+            _ => return None,
+        })
+    }
+}
+
+impl Default for HandsetProfile {
+    fn default() -> Self {
+        Self {
+            screen_width: 240,
+            screen_height: 320,
+            color_depth: 16,
+            total_memory: 0x100000,
+            free_memory: 0x100000,
+            model: "Emulator".into(),
+            carrier: None,
+            phone_number: "01000000000".into(),
+            keys: KeyCode::ALL.to_vec(),
+            touch_screen: false,
+            content_types: ["application/vnd.smaf", "audio/midi", "audio/x-wav"].map(ToOwned::to_owned).to_vec(),
+            font_sizes: [12, 16, 20],
+        }
+    }
+}
@@ -1,26 +1,46 @@
 #![no_std]
 extern crate alloc;
 
+mod audio_capture;
 mod audio_sink;
 pub mod canvas;
 mod database;
 mod executor;
+mod game_config;
+mod handset;
+mod mixer;
+mod network;
 mod platform;
+mod replay;
 mod screen;
+mod snapshot;
+mod svc_trace;
+mod symbols;
+mod synth;
 mod system;
 mod task;
 mod task_runner;
 mod time;
 
 pub use self::{
-    audio_sink::AudioSink,
+    audio_capture::{CaptureAudioSink, CaptureClock},
+    audio_sink::{AudioSink, WaveVoice},
     database::{Database, DatabaseRepository, RecordId},
     executor::{AsyncCallable, AsyncCallableResult},
+    game_config::{GameConfig, HandsetOverrides, Quirk},
+    handset::HandsetProfile,
+    mixer::{Mixer, VoiceHandle},
+    network::{HttpRequest, HttpResponse, HttpTransport, Network, NetworkError, SocketId, SocketType},
     platform::{Filesystem, Platform},
+    replay::{InputEvent, InputLog, InputPlayer, InputRecorder, REPLAY_VERSION, SharedInputLog},
     screen::Screen,
+    snapshot::{SNAPSHOT_VERSION, Snapshot, SnapshotReader, SnapshotWriter},
+    svc_trace::{SvcTrace, SvcTraceCallback},
+    symbols::SymbolResolver,
+    synth::SynthAudioSink,
     system::{Event, FilesystemOverlay, KeyCode, System},
     task_runner::{DefaultTaskRunner, TaskRunner},
-    time::Instant,
+    time::{Instant, VirtualClock},
 };
 
 use alloc::{
@@ -36,6 +56,18 @@ use wie_util::{Result, WieError};
 pub trait Emulator {
     fn handle_event(&mut self, event: Event);
     fn tick(&mut self) -> Result<()>;
+
+    /// Writes a checkpoint: a deterministic replay of the ticks and input since load, with a digest of the state they
+    /// led to. Call between ticks; needs a virtual clock.
+    fn save_checkpoint(&self) -> Result<Vec<u8>> {
+        Err(WieError::Unimplemented("checkpoints are not supported on this platform".into()))
+    }
+
+    /// Reaches a checkpoint from [`Emulator::save_checkpoint`] on a freshly loaded emulator running the same app by
+    /// running its ticks and input again. Host side effects like storage writes and network traffic are repeated.
+    fn replay_checkpoint(&mut self, _data: &[u8]) -> Result<()> {
+        Err(WieError::Unimplemented("checkpoints are not supported on this platform".into()))
+    }
 }
 
 pub struct ProfileSample {
@@ -51,7 +83,14 @@ pub type ProfileCallback = Box<dyn FnMut(Vec<ProfileSample>) + Send + Sync>;
 
 pub struct Options {
     pub enable_gdbserver: bool,
+    /// Runs the app's code from cached decoded blocks, which is faster than stepping each instruction. Not used with
+    /// the gdb server.
+    pub enable_block_cache: bool,
     pub profile: Option<ProfileCallback>,
+    /// Filled with the names of the app's code and of the emulated functions it calls, for resolving profile samples.
+    pub symbols: Option<SymbolResolver>,
+    /// Receives every call the app makes to emulated functions.
+    pub svc_trace: Option<SvcTraceCallback>,
 }
 
 pub fn extract_zip(zip: &[u8]) -> Result<BTreeMap<String, Vec<u8>>> {
@@ -0,0 +1,205 @@
+use alloc::{collections::BTreeMap, sync::Arc, vec, vec::Vec};
+
+pub type VoiceHandle = u32;
+
+// volumes are percentages, like the platform media apis use
+const MAX_VOLUME: u8 = 100;
+
+struct Voice {
+    channel: u8,
+    data: Arc<[i16]>,
+    // 16.16 fixed point position into data, and its increment per output sample
+    position: u64,
+    step: u64,
+    volume: u8,
+    looping: bool,
+}
+
+impl Voice {
+    fn next_sample(&mut self) -> Option<i16> {
+        let mut index = (self.position >> 16) as usize;
+        if index >= self.data.len() {
+            if !self.looping {
+                return None;
+            }
+
+            self.position &= 0xffff;
+            index = 0;
+        }
+
+        self.position += self.step;
+
+        Some(self.data[index])
+    }
+}
+
+/// Mixes any number of concurrently playing PCM voices into one mono stream at a fixed output rate.
+///
+/// Voices are grouped by the channel they were started on, so a player can stop or turn down everything it started at once.
+pub struct Mixer {
+    sample_rate: u32,
+    voices: BTreeMap<VoiceHandle, Voice>,
+    channel_volumes: [u8; 256],
+    last_handle: VoiceHandle,
+}
+
+impl Mixer {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            voices: BTreeMap::new(),
+            channel_volumes: [MAX_VOLUME; 256],
+            last_handle: 0,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Starts playing `data`, resampled from `sampling_rate`, alongside whatever is already playing.
+    pub fn play(&mut self, channel: u8, sampling_rate: u32, data: &[i16], looping: bool) -> VoiceHandle {
+        self.last_handle += 1;
+
+        // a voice that can never produce a sample is done right away, but still gets a handle
+        if sampling_rate != 0 && !data.is_empty() {
+            self.voices.insert(
+                self.last_handle,
+                Voice {
+                    channel,
+                    data: data.into(),
+                    position: 0,
+                    step: ((sampling_rate as u64) << 16) / self.sample_rate as u64,
+                    volume: MAX_VOLUME,
+                    looping,
+                },
+            );
+        }
+
+        self.last_handle
+    }
+
+    pub fn is_playing(&self, handle: VoiceHandle) -> bool {
+        self.voices.contains_key(&handle)
+    }
+
+    pub fn stop(&mut self, handle: VoiceHandle) {
+        self.voices.remove(&handle);
+    }
+
+    pub fn stop_channel(&mut self, channel: u8) {
+        self.voices.retain(|_, x| x.channel != channel);
+    }
+
+    pub fn stop_all(&mut self) {
+        self.voices.clear();
+    }
+
+    pub fn set_volume(&mut self, handle: VoiceHandle, volume: u8) {
+        if let Some(voice) = self.voices.get_mut(&handle) {
+            voice.volume = volume.min(MAX_VOLUME);
+        }
+    }
+
+    pub fn set_looping(&mut self, handle: VoiceHandle, looping: bool) {
+        if let Some(voice) = self.voices.get_mut(&handle) {
+            voice.looping = looping;
+        }
+    }
+
+    pub fn set_channel_volume(&mut self, channel: u8, volume: u8) {
+        self.channel_volumes[channel as usize] = volume.min(MAX_VOLUME);
+    }
+
+    /// Adds the next `out.len()` samples of every playing voice onto `out`, dropping voices that ran out.
+    pub fn mix_into(&mut self, out: &mut [i32]) {
+        let mut finished = Vec::new();
+
+        for (handle, voice) in &mut self.voices {
+            let gain = voice.volume as i32 * self.channel_volumes[voice.channel as usize] as i32;
+
+            for sample in out.iter_mut() {
+                match voice.next_sample() {
+                    Some(x) => *sample += x as i32 * gain / (MAX_VOLUME as i32 * MAX_VOLUME as i32),
+                    None => {
+                        finished.push(*handle);
+                        break;
+                    }
+                }
+            }
+        }
+
+        for handle in finished {
+            self.voices.remove(&handle);
+        }
+    }
+
+    /// Renders the next `out.len()` mixed samples, clipping where voices add up past the sample range.
+    pub fn render(&mut self, out: &mut [i16]) {
+        let mut mixed = vec![0; out.len()];
+        self.mix_into(&mut mixed);
+
+        for (out, mixed) in out.iter_mut().zip(mixed) {
+            *out = mixed.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::Mixer;
+
+    #[test]
+    fn test_voices_play_concurrently() {
+        let mut mixer = Mixer::new(8000);
+
+        let music = mixer.play(0, 8000, &[100; 8], false);
+        let effect = mixer.play(1, 8000, &[10; 2], false);
+
+        let mut out = vec![0; 4];
+        mixer.render(&mut out);
+        assert_eq!(out, [110, 110, 100, 100]);
+        assert!(mixer.is_playing(music));
+        assert!(!mixer.is_playing(effect));
+
+        mixer.stop_channel(0);
+        mixer.render(&mut out);
+        assert_eq!(out, [0; 4]);
+    }
+
+    #[test]
+    fn test_volume_loop_and_resample() {
+        let mut mixer = Mixer::new(8000);
+
+        // 4khz source at 8khz output plays every sample twice
+        let handle = mixer.play(2, 4000, &[1000, 2000], true);
+        mixer.set_volume(handle, 50);
+
+        let mut out = vec![0; 6];
+        mixer.render(&mut out);
+        assert_eq!(out, [500, 500, 1000, 1000, 500, 500]);
+
+        mixer.set_channel_volume(2, 10);
+        mixer.render(&mut out);
+        assert_eq!(out, [100, 100, 50, 50, 100, 100]);
+
+        mixer.set_looping(handle, false);
+        mixer.render(&mut out);
+        assert_eq!(out, [0; 6]);
+        assert!(!mixer.is_playing(handle));
+    }
+
+    #[test]
+    fn test_mix_clips() {
+        let mut mixer = Mixer::new(8000);
+
+        mixer.play(0, 8000, &[i16::MAX], false);
+        mixer.play(1, 8000, &[i16::MAX], false);
+
+        let mut out = vec![0; 1];
+        mixer.render(&mut out);
+        assert_eq!(out, [i16::MAX]);
+    }
+}
@@ -0,0 +1,68 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+pub type SocketId = u32;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SocketType {
+    Stream,
+    Datagram,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NetworkError {
+    /// No data is available or the connection isn't made yet; try again later.
+    WouldBlock,
+    NotConnected,
+    InvalidSocket,
+    HostNotFound,
+    ConnectionRefused,
+    Other,
+}
+
+/// Host network stack backing the guest socket APIs. Addresses are IPv4 octets.
+///
+/// `resolve` may block the emulator for as long as the host lookup takes;
+/// every other call must return immediately, reporting
+/// [`NetworkError::WouldBlock`] when it can't make progress yet.
+pub trait Network: Send + Sync {
+    fn resolve(&self, host: &str) -> Result<[u8; 4], NetworkError>;
+    fn socket(&self, socket_type: SocketType) -> Result<SocketId, NetworkError>;
+    /// May return [`NetworkError::WouldBlock`] while the connection is being made; call again to get the outcome.
+    fn connect(&self, socket: SocketId, addr: [u8; 4], port: u16) -> Result<(), NetworkError>;
+    fn send(&self, socket: SocketId, data: &[u8]) -> Result<usize, NetworkError>;
+    /// `Ok(0)` means the peer closed the connection.
+    fn recv(&self, socket: SocketId, buf: &mut [u8]) -> Result<usize, NetworkError>;
+    /// Whether `recv` would return without [`NetworkError::WouldBlock`].
+    fn readable(&self, socket: SocketId) -> bool;
+    fn close(&self, socket: SocketId);
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub message: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Looks up a header by case-insensitive name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(x, _)| x.eq_ignore_ascii_case(name)).map(|(_, x)| x.as_str())
+    }
+}
+
+/// Host HTTP client backing the guest HTTP APIs. One call performs a whole exchange,
+/// any non-transport failure (404, 500, ...) is still an `Ok` response.
+#[async_trait::async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn request(&self, request: HttpRequest) -> Result<HttpResponse, NetworkError>;
+}
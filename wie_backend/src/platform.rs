@@ -1,17 +1,41 @@
 use alloc::boxed::Box;
 
-use crate::{audio_sink::AudioSink, database::DatabaseRepository, screen::Screen, time::Instant};
+use crate::{
+    audio_sink::AudioSink,
+    canvas::FontPack,
+    database::DatabaseRepository,
+    network::{HttpTransport, Network},
+    screen::Screen,
+    time::{Instant, VirtualClock},
+};
 
 pub trait Platform: Send + Sync {
     fn screen(&self) -> &dyn Screen;
     fn now(&self) -> Instant;
     fn database_repository(&self) -> &dyn DatabaseRepository;
     fn filesystem(&self) -> &dyn Filesystem;
+    fn network(&self) -> &dyn Network;
+    fn http(&self) -> &dyn HttpTransport;
     fn audio_sink(&self) -> Box<dyn AudioSink>;
     fn write_stdout(&self, buf: &[u8]);
     fn write_stderr(&self, buf: &[u8]);
     fn exit(&self);
     fn vibrate(&self, duration_ms: u64, intensity: u8);
+
+    /// Deterministic time source. When set, `now` must return this clock's time,
+    /// and `System::tick` runs a fixed step budget instead of 8ms of wall-clock time.
+    fn virtual_clock(&self) -> Option<&VirtualClock> {
+        None
+    }
+
+    /// Fonts to draw text with in place of the built-in font, e.g. the bitmap fonts of the emulated handset.
+    fn font_pack(&self) -> Option<FontPack> {
+        None
+    }
+
+    /// Key repeat timing requested by the app: the first `Keyrepeat` `delay_ms` after a key goes down, then one every `interval_ms`.
+    /// An interval of 0 turns repeating off.
+    fn set_key_repeat(&self, _delay_ms: u64, _interval_ms: u64) {}
 }
 
 /// Platform filesystem abstraction. Every method is scoped by `aid`;
@@ -0,0 +1,344 @@
+use alloc::{boxed::Box, format, sync::Arc, vec::Vec};
+
+use spin::Mutex;
+
+use wie_util::{Result, WieError};
+
+use crate::{
+    Emulator,
+    snapshot::{SnapshotReader, SnapshotWriter},
+    system::{Event, KeyCode},
+};
+
+/// Log handle shared between an [`InputRecorder`] and the host.
+pub type SharedInputLog = Arc<Mutex<InputLog>>;
+
+const REPLAY_MAGIC: [u8; 4] = *b"WIER";
+pub const REPLAY_VERSION: u32 = 1;
+
+/// Host input that is recorded in an [`InputLog`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InputEvent {
+    Redraw,
+    Keydown(KeyCode),
+    Keyup(KeyCode),
+    Keyrepeat(KeyCode),
+    Pointerdown { x: i32, y: i32 },
+    Pointerdrag { x: i32, y: i32 },
+    Pointerup { x: i32, y: i32 },
+}
+
+impl InputEvent {
+    pub fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::Redraw => Some(Self::Redraw),
+            Event::Keydown(x) => Some(Self::Keydown(*x)),
+            Event::Keyup(x) => Some(Self::Keyup(*x)),
+            Event::Keyrepeat(x) => Some(Self::Keyrepeat(*x)),
+            Event::Pointerdown { x, y } => Some(Self::Pointerdown { x: *x, y: *y }),
+            Event::Pointerdrag { x, y } => Some(Self::Pointerdrag { x: *x, y: *y }),
+            Event::Pointerup { x, y } => Some(Self::Pointerup { x: *x, y: *y }),
+            _ => None,
+        }
+    }
+
+    pub fn into_event(self) -> Event {
+        match self {
+            Self::Redraw => Event::Redraw,
+            Self::Keydown(x) => Event::Keydown(x),
+            Self::Keyup(x) => Event::Keyup(x),
+            Self::Keyrepeat(x) => Event::Keyrepeat(x),
+            Self::Pointerdown { x, y } => Event::Pointerdown { x, y },
+            Self::Pointerdrag { x, y } => Event::Pointerdrag { x, y },
+            Self::Pointerup { x, y } => Event::Pointerup { x, y },
+        }
+    }
+}
+
+/// Versioned list of input events, each tagged with the number of ticks that ran before it arrived.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct InputLog {
+    entries: Vec<(u64, InputEvent)>,
+}
+
+impl InputLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, tick: u64, event: InputEvent) {
+        self.entries.push((tick, event));
+    }
+
+    pub fn entries(&self) -> &[(u64, InputEvent)] {
+        &self.entries
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = SnapshotWriter::new();
+        writer.write_raw(&REPLAY_MAGIC);
+        writer.write_u32(REPLAY_VERSION);
+        writer.write_u32(self.entries.len() as _);
+        for (tick, event) in &self.entries {
+            writer.write_u64(*tick);
+            match event {
+                InputEvent::Redraw => writer.write_u8(0),
+                InputEvent::Keydown(x) => {
+                    writer.write_u8(1);
+                    writer.write_u8(x.raw());
+                }
+                InputEvent::Keyup(x) => {
+                    writer.write_u8(2);
+                    writer.write_u8(x.raw());
+                }
+                InputEvent::Keyrepeat(x) => {
+                    writer.write_u8(3);
+                    writer.write_u8(x.raw());
+                }
+                InputEvent::Pointerdown { x, y } => {
+                    writer.write_u8(4);
+                    writer.write_i32(*x);
+                    writer.write_i32(*y);
+                }
+                InputEvent::Pointerdrag { x, y } => {
+                    writer.write_u8(5);
+                    writer.write_i32(*x);
+                    writer.write_i32(*y);
+                }
+                InputEvent::Pointerup { x, y } => {
+                    writer.write_u8(6);
+                    writer.write_i32(*x);
+                    writer.write_i32(*y);
+                }
+            }
+        }
+
+        writer.into_inner()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut reader = SnapshotReader::new(data);
+        if reader.read_raw(REPLAY_MAGIC.len())? != REPLAY_MAGIC {
+            return Err(WieError::FatalError("Invalid replay magic".into()));
+        }
+
+        let version = reader.read_u32()?;
+        if version != REPLAY_VERSION {
+            return Err(WieError::FatalError(format!("Unsupported replay version {version}")));
+        }
+
+        let count = reader.read_u32()?;
+        let entries = (0..count)
+            .map(|_| {
+                let tick = reader.read_u64()?;
+                let tag = reader.read_u8()?;
+
+                let mut read_key = || {
+                    let raw = reader.read_u8()?;
+                    KeyCode::from_raw(raw).ok_or_else(|| WieError::FatalError(format!("Invalid key code {raw} in replay")))
+                };
+                let event = match tag {
+                    0 => InputEvent::Redraw,
+                    1 => InputEvent::Keydown(read_key()?),
+                    2 => InputEvent::Keyup(read_key()?),
+                    3 => InputEvent::Keyrepeat(read_key()?),
+                    4 => InputEvent::Pointerdown {
+                        x: reader.read_i32()?,
+                        y: reader.read_i32()?,
+                    },
+                    5 => InputEvent::Pointerdrag {
+                        x: reader.read_i32()?,
+                        y: reader.read_i32()?,
+                    },
+                    6 => InputEvent::Pointerup {
+                        x: reader.read_i32()?,
+                        y: reader.read_i32()?,
+                    },
+                    _ => return Err(WieError::FatalError(format!("Invalid replay event tag {tag}"))),
+                };
+
+                Ok((tick, event))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { entries })
+    }
+}
+
+/// Wraps an emulator and records every [`InputEvent`] passed to `handle_event`.
+pub struct InputRecorder {
+    emulator: Box<dyn Emulator>,
+    tick: u64,
+    log: SharedInputLog,
+}
+
+impl InputRecorder {
+    pub fn new(emulator: Box<dyn Emulator>) -> Self {
+        Self {
+            emulator,
+            tick: 0,
+            log: Arc::new(Mutex::new(InputLog::new())),
+        }
+    }
+
+    /// Shared handle to the log, so the host can save it after the recorder has been moved or dropped.
+    pub fn log(&self) -> SharedInputLog {
+        self.log.clone()
+    }
+}
+
+impl Emulator for InputRecorder {
+    fn handle_event(&mut self, event: Event) {
+        if let Some(x) = InputEvent::from_event(&event) {
+            self.log.lock().push(self.tick, x);
+        }
+
+        self.emulator.handle_event(event)
+    }
+
+    fn tick(&mut self) -> Result<()> {
+        self.emulator.tick()?;
+        self.tick += 1;
+
+        Ok(())
+    }
+
+    fn save_checkpoint(&self) -> Result<Vec<u8>> {
+        self.emulator.save_checkpoint()
+    }
+
+    fn replay_checkpoint(&mut self, data: &[u8]) -> Result<()> {
+        self.emulator.replay_checkpoint(data)
+    }
+}
+
+/// Wraps an emulator and feeds it a recorded [`InputLog`] on the ticks the events arrived on.
+/// Input from the host is ignored so the replay isn't disturbed.
+pub struct InputPlayer {
+    emulator: Box<dyn Emulator>,
+    tick: u64,
+    log: InputLog,
+    cursor: usize,
+}
+
+impl InputPlayer {
+    pub fn new(emulator: Box<dyn Emulator>, log: InputLog) -> Self {
+        Self {
+            emulator,
+            tick: 0,
+            log,
+            cursor: 0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.log.entries.len()
+    }
+}
+
+impl Emulator for InputPlayer {
+    fn handle_event(&mut self, _event: Event) {}
+
+    fn tick(&mut self) -> Result<()> {
+        while let Some((tick, event)) = self.log.entries.get(self.cursor) {
+            if *tick > self.tick {
+                break;
+            }
+
+            self.emulator.handle_event(event.into_event());
+            self.cursor += 1;
+        }
+
+        self.emulator.tick()?;
+        self.tick += 1;
+
+        Ok(())
+    }
+
+    fn save_checkpoint(&self) -> Result<Vec<u8>> {
+        self.emulator.save_checkpoint()
+    }
+
+    fn replay_checkpoint(&mut self, data: &[u8]) -> Result<()> {
+        self.emulator.replay_checkpoint(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, sync::Arc, vec::Vec};
+
+    use spin::Mutex;
+
+    use wie_util::Result;
+
+    use crate::{Emulator, Event, KeyCode};
+
+    use super::{InputEvent, InputLog, InputPlayer, InputRecorder};
+
+    #[derive(Default)]
+    struct FakeEmulator {
+        tick: u64,
+        events: Arc<Mutex<Vec<(u64, InputEvent)>>>,
+    }
+
+    impl Emulator for FakeEmulator {
+        fn handle_event(&mut self, event: Event) {
+            self.events.lock().push((self.tick, InputEvent::from_event(&event).unwrap()));
+        }
+
+        fn tick(&mut self) -> Result<()> {
+            self.tick += 1;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() -> Result<()> {
+        let mut recorder = InputRecorder::new(Box::new(FakeEmulator::default()));
+        let log = recorder.log();
+
+        recorder.handle_event(Event::Redraw);
+        recorder.tick()?;
+        recorder.tick()?;
+        recorder.handle_event(Event::Keydown(KeyCode::NUM5));
+        recorder.handle_event(Event::Keyrepeat(KeyCode::NUM5));
+        recorder.tick()?;
+        recorder.handle_event(Event::Keyup(KeyCode::NUM5));
+        recorder.handle_event(Event::Pointerdown { x: 10, y: -2 });
+        drop(recorder);
+
+        let log = InputLog::from_bytes(&log.lock().to_bytes())?;
+        assert_eq!(
+            log.entries(),
+            &[
+                (0, InputEvent::Redraw),
+                (2, InputEvent::Keydown(KeyCode::NUM5)),
+                (2, InputEvent::Keyrepeat(KeyCode::NUM5)),
+                (3, InputEvent::Keyup(KeyCode::NUM5)),
+                (3, InputEvent::Pointerdown { x: 10, y: -2 }),
+            ]
+        );
+
+        let emulator = FakeEmulator::default();
+        let events = emulator.events.clone();
+        let mut player = InputPlayer::new(Box::new(emulator), log.clone());
+
+        player.handle_event(Event::Keydown(KeyCode::OK)); // ignored
+        for _ in 0..4 {
+            player.tick()?;
+        }
+        assert!(player.is_finished());
+        assert_eq!(events.lock().as_slice(), log.entries());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_input_log_rejects_bad_data() {
+        assert!(InputLog::from_bytes(b"WIES\x01\x00\x00\x00\x00\x00\x00\x00").is_err());
+        assert!(InputLog::from_bytes(b"WIER\x02\x00\x00\x00\x00\x00\x00\x00").is_err());
+        assert!(InputLog::from_bytes(b"WIER\x01\x00\x00\x00\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01\xff").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use wie_util::{Result, WieError};
+
+const SNAPSHOT_MAGIC: [u8; 4] = *b"WIES";
+pub const SNAPSHOT_VERSION: u32 = 4;
+
+/// Versioned blob made of named sections, used for checkpoints.
+///
+/// Each layer (`System`, `ArmCore`, ...) writes its own section, so the
+/// top-level emulator only decides which sections exist.
+#[derive(Default)]
+pub struct Snapshot {
+    sections: BTreeMap<String, Vec<u8>>,
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: &str, data: Vec<u8>) {
+        self.sections.insert(name.to_string(), data);
+    }
+
+    pub fn get(&self, name: &str) -> Result<&[u8]> {
+        self.sections
+            .get(name)
+            .map(|x| x.as_slice())
+            .ok_or_else(|| WieError::FatalError(format!("Missing snapshot section {name}")))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = SnapshotWriter::new();
+        writer.write_raw(&SNAPSHOT_MAGIC);
+        writer.write_u32(SNAPSHOT_VERSION);
+        writer.write_u32(self.sections.len() as _);
+        for (name, data) in &self.sections {
+            writer.write_str(name);
+            writer.write_bytes(data);
+        }
+
+        writer.into_inner()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut reader = SnapshotReader::new(data);
+        if reader.read_raw(SNAPSHOT_MAGIC.len())? != SNAPSHOT_MAGIC {
+            return Err(WieError::FatalError("Invalid snapshot magic".into()));
+        }
+
+        let version = reader.read_u32()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(WieError::FatalError(format!("Unsupported snapshot version {version}")));
+        }
+
+        let count = reader.read_u32()?;
+        let sections = (0..count)
+            .map(|_| Ok((reader.read_str()?, reader.read_bytes()?.to_vec())))
+            .collect::<Result<_>>()?;
+
+        Ok(Self { sections })
+    }
+}
+
+/// Little-endian writer used to build snapshot sections.
+#[derive(Default)]
+pub struct SnapshotWriter {
+    data: Vec<u8>,
+}
+
+impl SnapshotWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Length-prefixed byte string.
+    pub fn write_bytes(&mut self, data: &[u8]) {
+        self.write_u32(data.len() as _);
+        self.data.extend_from_slice(data);
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn write_raw(&mut self, data: &[u8]) {
+        self.data.extend_from_slice(data);
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+pub struct SnapshotReader<'a> {
+    data: &'a [u8],
+    cursor: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, cursor: 0 }
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_raw(1)?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_raw(4)?.try_into().unwrap()))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.read_raw(4)?.try_into().unwrap()))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_raw(8)?.try_into().unwrap()))
+    }
+
+    pub fn read_bytes(&mut self) -> Result<&'a [u8]> {
+        let length = self.read_u32()? as usize;
+
+        self.read_raw(length)
+    }
+
+    pub fn read_str(&mut self) -> Result<String> {
+        let bytes = self.read_bytes()?;
+
+        String::from_utf8(bytes.to_vec()).map_err(|_| WieError::FatalError("Invalid string in snapshot".into()))
+    }
+
+    pub fn read_raw(&mut self, length: usize) -> Result<&'a [u8]> {
+        if self.data.len() - self.cursor < length {
+            return Err(WieError::FatalError("Truncated snapshot".into()));
+        }
+
+        let result = &self.data[self.cursor..self.cursor + length];
+        self.cursor += length;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use wie_util::Result;
+
+    use super::{Snapshot, SnapshotReader, SnapshotWriter};
+
+    #[test]
+    fn test_snapshot_roundtrip() -> Result<()> {
+        let mut writer = SnapshotWriter::new();
+        writer.write_u32(0x12345678);
+        writer.write_str("hello");
+        writer.write_bool(true);
+
+        let mut snapshot = Snapshot::new();
+        snapshot.insert("test", writer.into_inner());
+        snapshot.insert("empty", vec![]);
+
+        let snapshot = Snapshot::from_bytes(&snapshot.to_bytes())?;
+        assert!(snapshot.get("empty")?.is_empty());
+
+        let mut reader = SnapshotReader::new(snapshot.get("test")?);
+        assert_eq!(reader.read_u32()?, 0x12345678);
+        assert_eq!(reader.read_str()?, "hello");
+        assert!(reader.read_bool()?);
+        assert!(reader.read_u8().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_rejects_bad_header() {
+        assert!(Snapshot::from_bytes(b"NOPE\x01\x00\x00\x00").is_err());
+        assert!(Snapshot::from_bytes(b"WIES\xff\x00\x00\x00\x00\x00\x00\x00").is_err());
+        assert!(Snapshot::from_bytes(b"WIE").is_err());
+    }
+}
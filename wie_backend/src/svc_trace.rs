@@ -0,0 +1,97 @@
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::fmt::Write;
+
+/// A call from guest code to an emulated function through an SVC stub.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SvcTrace {
+    pub category: u32,
+    pub id: u32,
+    /// Function name, if the loader names the functions of `category`.
+    pub name: Option<String>,
+    /// Arguments the function read, in order. The fifth and later ones are passed on the stack.
+    pub args: Vec<u32>,
+    /// Text of the arguments that point to a printable string, one per argument.
+    pub arg_strings: Vec<Option<String>>,
+    /// r0 after the call.
+    pub result: u32,
+    /// Return address in the calling guest code.
+    pub caller: u32,
+    /// Calls made while another emulated function runs, e.g. from a callback it invokes, are one deeper.
+    pub depth: u32,
+}
+
+impl SvcTrace {
+    /// Formats the call as a single line JSON object.
+    pub fn to_json(&self) -> String {
+        let mut result = format!("{{\"category\":{},\"id\":{},\"name\":", self.category, self.id);
+        write_json_string(&mut result, self.name.as_deref());
+
+        let args = self.args.iter().map(|x| format!("{x}")).collect::<Vec<_>>().join(",");
+        write!(result, ",\"args\":[{args}],\"arg_strings\":[").unwrap();
+        for (i, string) in self.arg_strings.iter().enumerate() {
+            if i != 0 {
+                result.push(',');
+            }
+            write_json_string(&mut result, string.as_deref());
+        }
+
+        write!(
+            result,
+            "],\"result\":{},\"caller\":{},\"depth\":{}}}",
+            self.result, self.caller, self.depth
+        )
+        .unwrap();
+
+        result
+    }
+}
+
+fn write_json_string(out: &mut String, value: Option<&str>) {
+    let Some(value) = value else {
+        out.push_str("null");
+        return;
+    };
+
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Called after every SVC-dispatched call returns, in the order the calls return.
+pub type SvcTraceCallback = Box<dyn FnMut(SvcTrace) + Send + Sync>;
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::SvcTrace;
+
+    #[test]
+    fn test_to_json() {
+        let trace = SvcTrace {
+            category: 3,
+            id: 0x10002,
+            name: Some("WIPIC::Kernel::Printk".into()),
+            args: vec![0x40000010, 1, 2, 3, 4],
+            arg_strings: vec![Some("\"%d\"\n".into()), None, None, None, None],
+            result: 0,
+            caller: 0x1001,
+            depth: 1,
+        };
+
+        assert_eq!(
+            trace.to_json(),
+            r#"{"category":3,"id":65538,"name":"WIPIC::Kernel::Printk","args":[1073741840,1,2,3,4],"arg_strings":["\"%d\"\n",null,null,null,null],"result":0,"caller":4097,"depth":1}"#
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use alloc::{collections::BTreeMap, format, string::String, sync::Arc, vec::Vec};
+
+use spin::Mutex;
+
+struct Symbol {
+    size: u32,
+    name: String,
+}
+
+/// Names of guest code, filled in by the loaders as code is loaded, so that profiles and debuggers can show
+/// `name+offset` instead of raw addresses. Clones share the same table.
+#[derive(Clone, Default)]
+pub struct SymbolResolver {
+    symbols: Arc<Mutex<BTreeMap<u32, Symbol>>>,
+}
+
+impl SymbolResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names `size` bytes from `address`. A `size` of 0 extends the symbol up to the next one.
+    pub fn add(&self, address: u32, size: u32, name: impl Into<String>) {
+        self.symbols.lock().insert(address & !1, Symbol { size, name: name.into() });
+    }
+
+    /// Name of the symbol containing `address` and the offset into it. The thumb bit of `address` is ignored.
+    pub fn resolve(&self, address: u32) -> Option<(String, u32)> {
+        let address = address & !1;
+        let symbols = self.symbols.lock();
+        let (start, symbol) = symbols.range(..=address).next_back()?;
+
+        let offset = address - start;
+        if symbol.size != 0 && offset >= symbol.size {
+            return None;
+        }
+
+        Some((symbol.name.clone(), offset))
+    }
+
+    /// Every symbol as `(address, size, name)`, sorted by address.
+    pub fn entries(&self) -> Vec<(u32, u32, String)> {
+        self.symbols
+            .lock()
+            .iter()
+            .map(|(&address, x)| (address, x.size, x.name.clone()))
+            .collect()
+    }
+
+    /// `name+0x10` for `address`, or the bare address if no symbol contains it.
+    pub fn format(&self, address: u32) -> String {
+        match self.resolve(address) {
+            Some((name, 0)) => name,
+            Some((name, offset)) => format!("{name}+{offset:#x}"),
+            None => format!("{address:#x}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SymbolResolver;
+
+    #[test]
+    fn test_resolve() {
+        let symbols = SymbolResolver::new();
+        symbols.add(0x1001, 0x10, "thumb_function");
+        symbols.add(0x2000, 0, "open_ended");
+
+        assert_eq!(symbols.format(0x1000), "thumb_function");
+        assert_eq!(symbols.format(0x1009), "thumb_function+0x8");
+        assert_eq!(symbols.format(0x1010), "0x1010");
+        assert_eq!(symbols.format(0xfff), "0xfff");
+        assert_eq!(symbols.format(0x3000), "open_ended+0x1000");
+        assert_eq!(symbols.resolve(0x2004), Some(("open_ended".into(), 4)));
+        assert_eq!(
+            symbols.entries(),
+            [(0x1000, 0x10, "thumb_function".into()), (0x2000, 0, "open_ended".into())]
+        );
+
+        // clones share the table
+        let clone = symbols.clone();
+        clone.add(0x1010, 4, "next");
+        assert_eq!(symbols.format(0x1012), "next+0x2");
+    }
+}
@@ -0,0 +1,513 @@
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::f32::consts::TAU;
+
+use num_traits::Float;
+use spin::Mutex;
+
+use crate::{
+    audio_sink::{AudioSink, WaveVoice},
+    mixer::Mixer,
+};
+
+const MAX_VOICES: usize = 32;
+const DRUM_CHANNEL: u8 = 9;
+// in semitones, the GM default
+const PITCH_BEND_RANGE: f32 = 2.0;
+// leaves room for a handful of voices at full velocity before clipping
+const MASTER_GAIN: f32 = 0.2;
+
+#[derive(Clone, Copy)]
+struct Patch {
+    /// Modulator frequency relative to the carrier.
+    ratio: f32,
+    /// Peak FM modulation index.
+    index: f32,
+    /// Per second decay rate of the modulation index; brightness fades out on plucked sounds.
+    index_decay: f32,
+    /// Share of white noise in the output, for drums and effects.
+    noise: f32,
+    /// Per second exponential pitch drop, for kick drums and toms.
+    sweep: f32,
+    attack: f32,
+    decay: f32,
+    sustain: f32,
+    release: f32,
+}
+
+impl Patch {
+    const fn new(ratio: f32, index: f32, index_decay: f32, attack: f32, decay: f32, sustain: f32, release: f32) -> Self {
+        Self {
+            ratio,
+            index,
+            index_decay,
+            noise: 0.0,
+            sweep: 0.0,
+            attack,
+            decay,
+            sustain,
+            release,
+        }
+    }
+
+    const fn drum(noise: f32, sweep: f32, decay: f32) -> Self {
+        Self {
+            ratio: 1.0,
+            index: 0.0,
+            index_decay: 0.0,
+            noise,
+            sweep,
+            attack: 0.001,
+            decay,
+            sustain: 0.0,
+            release: 0.05,
+        }
+    }
+}
+
+// two operator fm voice per GM instrument family, indexed by program / 8
+const PATCHES: [Patch; 16] = [
+    Patch::new(1.0, 2.0, 3.0, 0.002, 2.0, 0.0, 0.3),  // piano
+    Patch::new(3.5, 3.0, 8.0, 0.001, 0.8, 0.0, 0.3),  // chromatic percussion
+    Patch::new(2.0, 1.0, 0.0, 0.01, 0.1, 0.9, 0.05),  // organ
+    Patch::new(1.0, 2.5, 5.0, 0.002, 1.0, 0.0, 0.2),  // guitar
+    Patch::new(1.0, 1.5, 4.0, 0.005, 0.6, 0.4, 0.1),  // bass
+    Patch::new(1.0, 1.2, 0.0, 0.08, 0.2, 0.8, 0.3),   // strings
+    Patch::new(1.0, 1.0, 0.0, 0.1, 0.3, 0.8, 0.4),    // ensemble
+    Patch::new(1.0, 3.0, 0.0, 0.04, 0.2, 0.7, 0.15),  // brass
+    Patch::new(2.0, 1.5, 0.0, 0.03, 0.1, 0.8, 0.1),   // reed
+    Patch::new(1.0, 0.4, 0.0, 0.05, 0.1, 0.9, 0.1),   // pipe
+    Patch::new(1.0, 4.0, 0.0, 0.005, 0.1, 0.8, 0.1),  // synth lead
+    Patch::new(0.5, 1.5, 0.0, 0.3, 0.5, 0.7, 0.6),    // synth pad
+    Patch::new(1.41, 3.0, 1.0, 0.1, 0.5, 0.6, 0.5),   // synth effects
+    Patch::new(3.0, 2.0, 4.0, 0.002, 0.8, 0.1, 0.2),  // ethnic
+    Patch::new(1.6, 4.0, 10.0, 0.001, 0.4, 0.0, 0.1), // percussive
+    Patch::new(7.1, 6.0, 2.0, 0.01, 0.5, 0.3, 0.3),   // sound effects
+];
+
+// returns the patch and a fixed pitch for a note on the GM drum channel
+fn drum_patch(note: u8) -> (Patch, f32) {
+    match note {
+        35 | 36 => (Patch::drum(0.0, 12.0, 0.25), 90.0),        // kick
+        38 | 40 => (Patch::drum(0.7, 4.0, 0.15), 200.0),        // snare
+        42 | 44 => (Patch::drum(1.0, 0.0, 0.05), 0.0),          // closed hihat
+        46 => (Patch::drum(1.0, 0.0, 0.3), 0.0),                // open hihat
+        49 | 52 | 55 | 57 => (Patch::drum(1.0, 0.0, 1.0), 0.0), // cymbals
+        41 | 43 | 45 | 47 | 48 | 50 => (Patch::drum(0.1, 3.0, 0.3), note_frequency(note as f32 + 12.0)), // toms
+        _ => (Patch::drum(0.5, 2.0, 0.2), note_frequency(note as f32)),
+    }
+}
+
+fn note_frequency(note: f32) -> f32 {
+    440.0 * 2.0.powf((note - 69.0) / 12.0)
+}
+
+struct Channel {
+    program: u8,
+    volume: u8,
+    expression: u8,
+    pitch_bend: u16,
+    sustain: bool,
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Self {
+            program: 0,
+            volume: 100,
+            expression: 127,
+            pitch_bend: 0x2000,
+            sustain: false,
+        }
+    }
+}
+
+struct Voice {
+    channel: u8,
+    note: u8,
+    gain: f32,
+    frequency: f32,
+    patch: Patch,
+    carrier_phase: f32,
+    modulator_phase: f32,
+    time: f32,
+    // set once the key is up, with the envelope level at that moment
+    released: Option<(f32, f32)>,
+    // key is up but the sustain pedal is holding the voice
+    held: bool,
+    age: u64,
+}
+
+impl Voice {
+    fn envelope(&self) -> f32 {
+        let patch = &self.patch;
+
+        if let Some((released_at, level)) = self.released {
+            return level * (1.0 - (self.time - released_at) / patch.release).max(0.0);
+        }
+
+        if self.time < patch.attack {
+            self.time / patch.attack
+        } else if self.time < patch.attack + patch.decay {
+            1.0 - (1.0 - patch.sustain) * (self.time - patch.attack) / patch.decay
+        } else {
+            patch.sustain
+        }
+    }
+
+    fn release(&mut self) {
+        if self.released.is_none() {
+            self.released = Some((self.time, self.envelope()));
+        }
+    }
+
+    fn finished(&self) -> bool {
+        match self.released {
+            Some((released_at, _)) => self.time - released_at >= self.patch.release,
+            None => self.patch.sustain <= 0.0 && self.time >= self.patch.attack + self.patch.decay,
+        }
+    }
+}
+
+struct Synth {
+    sample_rate: u32,
+    channels: [Channel; 16],
+    voices: Vec<Voice>,
+    mixer: Mixer,
+    last_age: u64,
+    noise: u32,
+}
+
+impl Synth {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            channels: Default::default(),
+            voices: Vec::new(),
+            mixer: Mixer::new(sample_rate),
+            last_age: 0,
+            noise: 0x1234_5678,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.channels = Default::default();
+        self.voices.clear();
+    }
+
+    fn note_on(&mut self, channel_id: u8, note: u8, velocity: u8) {
+        if velocity == 0 {
+            return self.note_off(channel_id, note);
+        }
+
+        let channel_id = channel_id & 0x0f;
+        for voice in self.voices.iter_mut().filter(|x| x.channel == channel_id && x.note == note) {
+            voice.release();
+        }
+
+        if self.voices.len() >= MAX_VOICES
+            && let Some(oldest) = self.voices.iter().enumerate().min_by_key(|(_, x)| x.age).map(|(i, _)| i)
+        {
+            self.voices.remove(oldest);
+        }
+
+        let (patch, frequency) = if channel_id == DRUM_CHANNEL {
+            drum_patch(note)
+        } else {
+            (
+                PATCHES[(self.channels[channel_id as usize].program / 8) as usize],
+                note_frequency(note as f32),
+            )
+        };
+
+        self.last_age += 1;
+        self.voices.push(Voice {
+            channel: channel_id,
+            note,
+            gain: velocity as f32 / 127.0,
+            frequency,
+            patch,
+            carrier_phase: 0.0,
+            modulator_phase: 0.0,
+            time: 0.0,
+            released: None,
+            held: false,
+            age: self.last_age,
+        });
+    }
+
+    fn note_off(&mut self, channel_id: u8, note: u8) {
+        let channel_id = channel_id & 0x0f;
+        // drums are one shots and end on their own
+        if channel_id == DRUM_CHANNEL {
+            return;
+        }
+
+        let sustain = self.channels[channel_id as usize].sustain;
+        for voice in self.voices.iter_mut().filter(|x| x.channel == channel_id && x.note == note) {
+            if sustain {
+                voice.held = true;
+            } else {
+                voice.release();
+            }
+        }
+    }
+
+    fn control_change(&mut self, channel_id: u8, control: u8, value: u8) {
+        let channel_id = channel_id & 0x0f;
+        let channel = &mut self.channels[channel_id as usize];
+
+        match control {
+            7 => channel.volume = value,
+            11 => channel.expression = value,
+            64 => {
+                channel.sustain = value >= 64;
+                if !channel.sustain {
+                    for voice in self.voices.iter_mut().filter(|x| x.channel == channel_id && x.held) {
+                        voice.release();
+                    }
+                }
+            }
+            // all sound off
+            120 => self.voices.retain(|x| x.channel != channel_id),
+            // reset all controllers
+            121 => {
+                *channel = Channel {
+                    program: channel.program,
+                    ..Default::default()
+                }
+            }
+            // all notes off
+            123 => {
+                for voice in self.voices.iter_mut().filter(|x| x.channel == channel_id) {
+                    voice.release();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn sysex(&mut self, data: &[u8]) {
+        // gm system on, the only message we understand
+        let data = data.strip_prefix(&[0xf0]).unwrap_or(data);
+        if matches!(data, [0x7e, _, 0x09, 0x01, ..]) {
+            self.reset();
+        }
+    }
+
+    fn next_noise(&mut self) -> f32 {
+        // xorshift32
+        self.noise ^= self.noise << 13;
+        self.noise ^= self.noise >> 17;
+        self.noise ^= self.noise << 5;
+
+        self.noise as i32 as f32 / i32::MAX as f32
+    }
+
+    fn render(&mut self, out: &mut [i16]) {
+        let dt = 1.0 / self.sample_rate as f32;
+
+        // per voice values that don't change within a buffer
+        let voice_params = self
+            .voices
+            .iter()
+            .map(|voice| {
+                let channel = &self.channels[voice.channel as usize];
+                let bend = (channel.pitch_bend as f32 - 8192.0) / 8192.0 * PITCH_BEND_RANGE;
+                let gain = voice.gain * (channel.volume as f32 / 127.0) * (channel.expression as f32 / 127.0);
+
+                (voice.frequency * 2.0.powf(bend / 12.0), gain)
+            })
+            .collect::<Vec<_>>();
+
+        let mut waves = vec![0; out.len()];
+        self.mixer.mix_into(&mut waves);
+
+        for (sample, wave) in out.iter_mut().zip(waves) {
+            let mut mixed = 0.0;
+
+            for (i, &(frequency, gain)) in voice_params.iter().enumerate() {
+                let noise = if self.voices[i].patch.noise > 0.0 { self.next_noise() } else { 0.0 };
+                let voice = &mut self.voices[i];
+                let patch = voice.patch;
+
+                let frequency = if patch.sweep > 0.0 {
+                    frequency * (1.0 + 2.0 * (-patch.sweep * voice.time).exp())
+                } else {
+                    frequency
+                };
+                let index = patch.index * (-patch.index_decay * voice.time).exp();
+
+                let modulator = (voice.modulator_phase * TAU).sin() * index;
+                let tone = (voice.carrier_phase * TAU + modulator).sin();
+                let value = tone * (1.0 - patch.noise) + noise * patch.noise;
+
+                mixed += value * voice.envelope() * gain;
+
+                voice.carrier_phase = (voice.carrier_phase + frequency * dt).fract();
+                voice.modulator_phase = (voice.modulator_phase + frequency * patch.ratio * dt).fract();
+                voice.time += dt;
+            }
+
+            *sample = (mixed * MASTER_GAIN * i16::MAX as f32 + wave as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        }
+
+        self.voices.retain(|x| !x.finished());
+    }
+}
+
+/// [`AudioSink`] that renders midi with a built in fm synthesizer and mixes it with overlapping wave output into a single
+/// mono PCM stream. Clones share the same synthesizer; the host pulls samples with [`SynthAudioSink::render`].
+#[derive(Clone)]
+pub struct SynthAudioSink {
+    synth: Arc<Mutex<Synth>>,
+}
+
+impl SynthAudioSink {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            synth: Arc::new(Mutex::new(Synth::new(sample_rate))),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.synth.lock().sample_rate
+    }
+
+    /// Fills `out` with the next mono samples, advancing all playing voices.
+    pub fn render(&self, out: &mut [i16]) {
+        self.synth.lock().render(out)
+    }
+}
+
+impl AudioSink for SynthAudioSink {
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) -> WaveVoice {
+        self.synth.lock().mixer.play(channel, sampling_rate, wave_data, false)
+    }
+
+    fn stop_wave(&self, voice: WaveVoice) {
+        self.synth.lock().mixer.stop(voice)
+    }
+
+    fn set_wave_volume(&self, voice: WaveVoice, volume: u8) {
+        self.synth.lock().mixer.set_volume(voice, volume)
+    }
+
+    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
+        self.synth.lock().note_on(channel_id, note, velocity)
+    }
+
+    fn midi_note_off(&self, channel_id: u8, note: u8, _velocity: u8) {
+        self.synth.lock().note_off(channel_id, note)
+    }
+
+    fn midi_program_change(&self, channel_id: u8, program: u8) {
+        self.synth.lock().channels[(channel_id & 0x0f) as usize].program = program & 0x7f;
+    }
+
+    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
+        self.synth.lock().control_change(channel_id, control, value)
+    }
+
+    fn midi_pitch_bend(&self, channel_id: u8, value: u16) {
+        self.synth.lock().channels[(channel_id & 0x0f) as usize].pitch_bend = value & 0x3fff;
+    }
+
+    fn midi_sysex(&self, data: &[u8]) {
+        self.synth.lock().sysex(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use crate::audio_sink::AudioSink;
+
+    use super::SynthAudioSink;
+
+    fn peak(samples: &[i16]) -> i32 {
+        samples.iter().map(|&x| (x as i32).abs()).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_note_renders_until_released() {
+        let sink = SynthAudioSink::new(8000);
+        let mut buf = vec![0; 800];
+
+        sink.render(&mut buf);
+        assert_eq!(peak(&buf), 0);
+
+        sink.midi_program_change(0, 16); // organ, which sustains
+        sink.midi_note_on(0, 69, 127);
+        sink.render(&mut buf);
+        assert!(peak(&buf) > 1000);
+
+        sink.midi_note_off(0, 69, 0);
+        // past the release time
+        sink.render(&mut buf);
+        sink.render(&mut buf);
+        assert_eq!(peak(&buf), 0);
+    }
+
+    #[test]
+    fn test_sustain_pedal_holds_notes() {
+        let sink = SynthAudioSink::new(8000);
+        let mut buf = vec![0; 800];
+
+        sink.midi_program_change(0, 16);
+        sink.midi_control_change(0, 64, 127);
+        sink.midi_note_on(0, 60, 100);
+        sink.midi_note_off(0, 60, 0);
+        sink.render(&mut buf);
+        sink.render(&mut buf);
+        assert!(peak(&buf) > 1000);
+
+        sink.midi_control_change(0, 64, 0);
+        sink.render(&mut buf);
+        sink.render(&mut buf);
+        assert_eq!(peak(&buf), 0);
+    }
+
+    #[test]
+    fn test_wave_is_mixed_and_resampled() {
+        let sink = SynthAudioSink::new(8000);
+
+        // 4khz source at 8khz output plays every sample twice
+        sink.play_wave(0, 4000, &[100, 200, 300]);
+        let mut buf = vec![0; 8];
+        sink.render(&mut buf);
+        assert_eq!(buf, [100, 100, 200, 200, 300, 300, 0, 0]);
+
+        // waves overlap instead of queueing
+        sink.play_wave(0, 8000, &[1000; 4]);
+        sink.play_wave(0, 8000, &[10; 2]);
+        let mut buf: Vec<i16> = vec![0; 4];
+        sink.render(&mut buf);
+        assert_eq!(buf, [1010, 1010, 1000, 1000]);
+    }
+
+    #[test]
+    fn test_wave_voice_volume_and_stop() {
+        let sink = SynthAudioSink::new(8000);
+
+        let music = sink.play_wave(0, 8000, &[1000; 8]);
+        let effect = sink.play_wave(0, 8000, &[10; 8]);
+        let mut buf = vec![0; 2];
+        sink.render(&mut buf);
+        assert_eq!(buf, [1010, 1010]);
+
+        sink.set_wave_volume(music, 50);
+        sink.render(&mut buf);
+        assert_eq!(buf, [510, 510]);
+
+        sink.stop_wave(music);
+        sink.render(&mut buf);
+        assert_eq!(buf, [10, 10]);
+
+        // stopping a wave that is gone already does nothing
+        sink.stop_wave(music);
+        sink.stop_wave(effect);
+        sink.render(&mut buf);
+        assert_eq!(buf, [0, 0]);
+    }
+}
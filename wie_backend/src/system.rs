@@ -1,46 +1,94 @@
 mod audio;
 mod event_queue;
 mod file_system;
+mod sockets;
 
-use alloc::{borrow::ToOwned, boxed::Box, string::String, sync::Arc};
+use alloc::{borrow::ToOwned, boxed::Box, collections::BTreeMap, format, string::String, sync::Arc, vec::Vec};
 
 use spin::{RwLock, RwLockWriteGuard};
 
-use wie_util::Result;
+use wie_util::{Result, WieError};
 
 use crate::{
     AsyncCallable,
+    canvas::FontPack,
     executor::Executor,
+    game_config::GameConfig,
+    handset::HandsetProfile,
+    network::{NetworkError, SocketId},
     platform::Platform,
+    replay::{InputEvent, InputLog},
+    snapshot::{SnapshotReader, SnapshotWriter},
     task::{SleepFuture, YieldFuture},
     task_runner::TaskRunner,
+    time::VirtualClock,
 };
 
-use self::{audio::Audio, event_queue::EventQueue};
+use self::{audio::Audio, event_queue::EventQueue, sockets::Sockets};
 
 pub use self::{
     event_queue::{Event, KeyCode},
     file_system::FilesystemOverlay,
 };
 
+// executor steps per tick when running on a virtual clock
+const VIRTUAL_TICK_MAX_STEPS: usize = 1024;
+
+// how often a connecting socket is checked on
+const CONNECT_POLL_INTERVAL: u64 = 10;
+
+/// What happened since load on a virtual clock: the clock time between ticks and the input that arrived between them.
+/// Running it again on a freshly loaded app is how a checkpoint is reached, as executor tasks can't be serialized.
+#[derive(Default)]
+struct History {
+    // clock time elapsed before each tick, run-length encoded as (interval, count) as most ticks are evenly spaced
+    intervals: Vec<(u64, u64)>,
+    ticks: u64,
+    last_time: u64,
+    input: InputLog,
+}
+
+impl History {
+    fn push_tick(&mut self, time: u64) {
+        let interval = time - self.last_time;
+        self.last_time = time;
+        self.ticks += 1;
+
+        match self.intervals.last_mut() {
+            Some((last, count)) if *last == interval => *count += 1,
+            _ => self.intervals.push((interval, 1)),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct System {
     pid: String,
     aid: String,
     executor: Executor,
     platform: Arc<Box<dyn Platform>>,
+    handset: Arc<HandsetProfile>,
+    game_config: Arc<GameConfig>,
+    font_pack: Arc<FontPack>,
     filesystem: FilesystemOverlay,
     event_queue: Arc<RwLock<EventQueue>>,
     audio: Arc<RwLock<Audio>>,
+    sockets: Arc<RwLock<Sockets>>,
     task_runner: Arc<dyn TaskRunner>,
+    history: Arc<RwLock<History>>,
 }
 
 impl System {
-    pub fn new<T>(platform: Box<dyn Platform>, pid: &str, aid: &str, task_runner: T) -> Self
+    pub fn new<T>(platform: Box<dyn Platform>, handset: HandsetProfile, pid: &str, aid: &str, task_runner: T) -> Self
     where
         T: TaskRunner + 'static,
     {
         let audio_sink = platform.audio_sink();
+
+        let mut font_pack = platform.font_pack().unwrap_or_default();
+        let [small, medium, large] = handset.font_sizes;
+        font_pack.set_pixel_sizes(small, medium, large);
+
         let platform = Arc::new(platform);
 
         Self {
@@ -48,14 +96,26 @@ impl System {
             aid: aid.to_owned(), // TODO create metadata dictionary or something
             executor: Executor::new(),
             filesystem: FilesystemOverlay::new(platform.clone(), aid),
+            sockets: Arc::new(RwLock::new(Sockets::new(platform.clone()))),
             platform,
+            handset: Arc::new(handset),
+            game_config: Arc::new(GameConfig::default()),
+            font_pack: Arc::new(font_pack),
             event_queue: Arc::new(RwLock::new(EventQueue::new())),
             audio: Arc::new(RwLock::new(Audio::new(audio_sink))),
             task_runner: Arc::new(task_runner),
+            history: Arc::new(RwLock::new(History::default())),
         }
     }
 
     pub fn tick(&mut self) -> Result<()> {
+        if let Some(clock) = self.platform.virtual_clock() {
+            let now = clock.now();
+            self.history.write().push_tick(now.raw());
+
+            return self.executor.tick_virtual(now, VIRTUAL_TICK_MAX_STEPS);
+        }
+
         let platform = self.platform.clone();
         self.executor.tick(move || platform.now())
     }
@@ -99,11 +159,184 @@ impl System {
         self.platform.as_ref().as_ref()
     }
 
+    pub fn handset(&self) -> &HandsetProfile {
+        &self.handset
+    }
+
+    /// Fonts text is measured and drawn with: the platform's font pack at the handset's font sizes.
+    pub fn font_pack(&self) -> &FontPack {
+        &self.font_pack
+    }
+
+    pub fn game_config(&self) -> &GameConfig {
+        &self.game_config
+    }
+
+    /// Looks up and applies the [`GameConfig`] of the app made of `files`. Call before cloning the system, as clones keep the old config.
+    ///
+    /// The config's handset settings are left to the frontend, which applies them under its own options when building the [`HandsetProfile`].
+    pub fn load_game_config(&mut self, files: &BTreeMap<String, Vec<u8>>) -> Result<()> {
+        let Some(game_config) = GameConfig::find(files.values().map(|x| x.as_slice()))? else {
+            return Ok(());
+        };
+
+        self.event_queue.write().set_key_map(game_config.key_map.clone());
+        self.game_config = Arc::new(game_config);
+
+        Ok(())
+    }
+
     pub fn audio(&self) -> RwLockWriteGuard<'_, Audio> {
         self.audio.as_ref().write()
     }
 
+    /// Queues host input for the app, recording it for checkpoints when running on a virtual clock.
+    pub fn handle_event(&self, event: Event) {
+        if self.platform.virtual_clock().is_some()
+            && let Some(x) = InputEvent::from_event(&event)
+        {
+            let mut history = self.history.write();
+            let tick = history.ticks;
+            history.input.push(tick, x);
+        }
+
+        self.event_queue().push(event)
+    }
+
     pub fn event_queue(&self) -> RwLockWriteGuard<'_, EventQueue> {
         self.event_queue.write()
     }
+
+    pub fn sockets(&self) -> RwLockWriteGuard<'_, Sockets> {
+        self.sockets.write()
+    }
+
+    /// Connects socket `id`, sleeping while the platform is still making the connection so other tasks keep running.
+    pub async fn connect_socket(&self, id: SocketId, addr: [u8; 4], port: u16) -> core::result::Result<(), NetworkError> {
+        loop {
+            let result = self.sockets().connect(id, addr, port);
+            match result {
+                Err(NetworkError::WouldBlock) => self.sleep(CONNECT_POLL_INTERVAL).await,
+                x => return x,
+            }
+        }
+    }
+
+    /// Writes a checkpoint: the ticks and input since load, and a digest of the executor, event and audio state they
+    /// led to. Executor tasks and timer callbacks are rust futures and closures, so a checkpoint isn't a save state;
+    /// [`System::replay_checkpoint`] reaches it again by running the same ticks. Needs a virtual clock.
+    pub fn save_checkpoint(&self) -> Result<Vec<u8>> {
+        let clock = self.virtual_clock()?;
+
+        let mut writer = SnapshotWriter::new();
+        writer.write_u64(clock.now().raw());
+
+        let history = self.history.read();
+        writer.write_u32(history.intervals.len() as _);
+        for (interval, count) in &history.intervals {
+            writer.write_u64(*interval);
+            writer.write_u64(*count);
+        }
+        writer.write_bytes(&history.input.to_bytes());
+        writer.write_raw(&self.state_digest());
+
+        Ok(writer.into_inner())
+    }
+
+    /// Runs the ticks and input of a [`System::save_checkpoint`] checkpoint on a freshly loaded app, with audio muted,
+    /// and checks that it ended up in the same state.
+    ///
+    /// Everything the app does on the host happens again, database and filesystem writes, sockets and http included,
+    /// so the replay diverges once persistent storage has changed since the checkpoint was written.
+    pub fn replay_checkpoint(&mut self, data: &[u8]) -> Result<()> {
+        let clock = self.virtual_clock()?.clone();
+        if self.history.read().ticks != 0 {
+            return Err(WieError::FatalError("Checkpoints can only be replayed before the first tick".into()));
+        }
+
+        let mut reader = SnapshotReader::new(data);
+        let now = reader.read_u64()?;
+        let intervals = (0..reader.read_u32()?)
+            .map(|_| Ok((reader.read_u64()?, reader.read_u64()?)))
+            .collect::<Result<Vec<_>>>()?;
+        let input = InputLog::from_bytes(reader.read_bytes()?)?;
+        let expected = reader.read_raw(16)?;
+
+        self.audio().set_replaying(true);
+        let result = self.replay(&clock, &intervals, &input, now);
+        self.audio().set_replaying(false);
+        result?;
+
+        if self.state_digest() != expected {
+            return Err(WieError::FatalError("Replaying the checkpoint did not reproduce it".into()));
+        }
+
+        Ok(())
+    }
+
+    fn replay(&mut self, clock: &VirtualClock, intervals: &[(u64, u64)], input: &InputLog, now: u64) -> Result<()> {
+        let advance_to = |time: u64| {
+            let current = clock.now().raw();
+            if time < current {
+                return Err(WieError::FatalError(format!("Checkpoint goes back in time from {current} to {time}")));
+            }
+            clock.advance(time - current);
+
+            Ok(())
+        };
+
+        let mut entries = input.entries().iter().peekable();
+        let mut time = 0;
+        let mut tick = 0;
+        for (interval, count) in intervals {
+            for _ in 0..*count {
+                while let Some((_, event)) = entries.next_if(|(x, _)| *x <= tick) {
+                    self.handle_event(event.into_event());
+                }
+
+                time += interval;
+                tick += 1;
+                advance_to(time)?;
+                self.tick()?;
+            }
+        }
+
+        for (_, event) in entries {
+            self.handle_event(event.into_event());
+        }
+
+        advance_to(now)
+    }
+
+    // state that running the history rebuilds, reduced to a digest as it's only compared
+    fn state_digest(&self) -> [u8; 16] {
+        let mut writer = SnapshotWriter::new();
+        self.executor.write_state(&mut writer);
+        self.event_queue().write_state(&mut writer);
+        self.audio().write_state(&mut writer);
+
+        md5::compute(writer.into_inner()).0
+    }
+
+    fn virtual_clock(&self) -> Result<&VirtualClock> {
+        self.platform
+            .virtual_clock()
+            .ok_or_else(|| WieError::Unimplemented("Checkpoints need a virtual clock".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    #[test]
+    fn test_history_intervals() {
+        let mut history = History::default();
+        for time in [1000, 1016, 1032, 1048, 1100, 1116] {
+            history.push_tick(time);
+        }
+
+        assert_eq!(history.ticks, 6);
+        assert_eq!(history.intervals, [(1000, 1), (16, 3), (52, 1), (16, 1)]);
+    }
 }
@@ -4,11 +4,22 @@ use alloc::{
     sync::Arc,
     vec::Vec,
 };
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::{
+    mem,
+    sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering},
+};
 
 use smaf_player::{SmafEvent, parse_smaf};
 
-use crate::{System, audio_sink::AudioSink};
+use crate::{
+    System,
+    audio_sink::{AudioSink, WaveVoice},
+    snapshot::SnapshotWriter,
+    time::Instant,
+};
+
+mod midi;
+mod wave;
 
 pub type AudioHandle = u32;
 #[derive(Debug)]
@@ -17,54 +28,237 @@ pub enum AudioError {
     InvalidAudio,
 }
 
+// volumes are percentages, as the platform media apis use them
+pub const MAX_VOLUME: u8 = 100;
+
+// longest a player sleeps before looking at its state again, so pause and stop take effect promptly
+const STATE_POLL_INTERVAL: u64 = 20;
+
 enum AudioFile {
     Smaf(Vec<u8>),
+    Midi(Vec<u8>),
+    Wave(Vec<u8>),
+}
+
+impl AudioFile {
+    fn player(&self) -> Option<SmafPlayer> {
+        Some(match self {
+            Self::Smaf(data) => SmafPlayer::new(data),
+            Self::Midi(data) => SmafPlayer::from_events(midi::parse_midi(data)?),
+            Self::Wave(data) => SmafPlayer::from_events(wave::parse_wave(data)?),
+        })
+    }
+}
+
+struct AudioClip {
+    file: AudioFile,
+    volume: u8,
+    // where the next play starts, in milliseconds
+    start_position: u64,
+}
+
+/// Playback controls shared between [`Audio`] and the task running a [`SmafPlayer`].
+pub struct PlaybackState {
+    stopped: AtomicBool,
+    paused: AtomicBool,
+    position: AtomicU64,
+    volume: AtomicU8,
+}
+
+impl PlaybackState {
+    pub fn new(position: u64, volume: u8) -> Self {
+        Self {
+            stopped: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            position: AtomicU64::new(position),
+            volume: AtomicU8::new(volume.min(MAX_VOLUME)),
+        }
+    }
+
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Milliseconds into the clip the player has reached.
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    pub fn set_volume(&self, volume: u8) {
+        self.volume.store(volume.min(MAX_VOLUME), Ordering::Relaxed);
+    }
+
+    pub fn volume(&self) -> u8 {
+        self.volume.load(Ordering::Relaxed)
+    }
+}
+
+/// Forwards to the platform sink unless a checkpoint is being replayed, so replayed music isn't heard twice.
+struct ReplaySink {
+    sink: Box<dyn AudioSink>,
+    replaying: AtomicBool,
+}
+
+impl ReplaySink {
+    fn is_audible(&self) -> bool {
+        !self.replaying.load(Ordering::Relaxed)
+    }
+}
+
+impl AudioSink for ReplaySink {
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) -> WaveVoice {
+        if self.is_audible() {
+            self.sink.play_wave(channel, sampling_rate, wave_data)
+        } else {
+            0
+        }
+    }
+
+    fn stop_wave(&self, voice: WaveVoice) {
+        if self.is_audible() {
+            self.sink.stop_wave(voice)
+        }
+    }
+
+    fn set_wave_volume(&self, voice: WaveVoice, volume: u8) {
+        if self.is_audible() {
+            self.sink.set_wave_volume(voice, volume)
+        }
+    }
+
+    fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
+        if self.is_audible() {
+            self.sink.midi_note_on(channel_id, note, velocity)
+        }
+    }
+
+    fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
+        if self.is_audible() {
+            self.sink.midi_note_off(channel_id, note, velocity)
+        }
+    }
+
+    fn midi_program_change(&self, channel_id: u8, program: u8) {
+        if self.is_audible() {
+            self.sink.midi_program_change(channel_id, program)
+        }
+    }
+
+    fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
+        if self.is_audible() {
+            self.sink.midi_control_change(channel_id, control, value)
+        }
+    }
+
+    fn midi_pitch_bend(&self, channel_id: u8, value: u16) {
+        if self.is_audible() {
+            self.sink.midi_pitch_bend(channel_id, value)
+        }
+    }
+
+    fn midi_sysex(&self, data: &[u8]) {
+        if self.is_audible() {
+            self.sink.midi_sysex(data)
+        }
+    }
+}
+
+struct PlayingAudio {
+    state: Arc<PlaybackState>,
+    repeat: bool,
 }
 
 pub struct Audio {
-    sink: Arc<Box<dyn AudioSink>>,
-    files: BTreeMap<AudioHandle, AudioFile>,
-    playing: BTreeMap<AudioHandle, Arc<AtomicBool>>,
+    sink: Arc<ReplaySink>,
+    clips: BTreeMap<AudioHandle, AudioClip>,
+    playing: BTreeMap<AudioHandle, PlayingAudio>,
     last_audio_handle: AudioHandle,
+    muted: bool,
 }
 
 impl Audio {
     pub fn new(sink: Box<dyn AudioSink>) -> Self {
         Self {
-            sink: Arc::new(sink),
-            files: BTreeMap::new(),
+            sink: Arc::new(ReplaySink {
+                sink,
+                replaying: AtomicBool::new(false),
+            }),
+            clips: BTreeMap::new(),
             playing: BTreeMap::new(),
             last_audio_handle: 0,
+            muted: false,
         }
     }
 
     pub fn load_smaf(&mut self, data: &[u8]) -> Result<AudioHandle, AudioError> {
+        self.load(AudioFile::Smaf(data.to_vec()))
+    }
+
+    /// Loads a standard MIDI file (format 0 or 1).
+    pub fn load_midi(&mut self, data: &[u8]) -> Result<AudioHandle, AudioError> {
+        self.load(AudioFile::Midi(data.to_vec()))
+    }
+
+    /// Loads a RIFF WAVE file with PCM or IMA-ADPCM samples.
+    pub fn load_wave(&mut self, data: &[u8]) -> Result<AudioHandle, AudioError> {
+        self.load(AudioFile::Wave(data.to_vec()))
+    }
+
+    fn load(&mut self, file: AudioFile) -> Result<AudioHandle, AudioError> {
+        // parse once up front so broken files fail here rather than silently at play time
+        if file.player().is_none() {
+            return Err(AudioError::InvalidAudio);
+        }
+
         let audio_handle = self.last_audio_handle;
 
         self.last_audio_handle += 1;
-        self.files.insert(audio_handle, AudioFile::Smaf(data.to_vec()));
+        self.clips.insert(
+            audio_handle,
+            AudioClip {
+                file,
+                volume: MAX_VOLUME,
+                start_position: 0,
+            },
+        );
 
         Ok(audio_handle)
     }
 
+    pub fn contains(&self, audio_handle: AudioHandle) -> bool {
+        self.clips.contains_key(&audio_handle)
+    }
+
+    /// Starts the clip from its start position, which is the beginning unless [`Audio::set_position`] moved it.
     pub fn play(&mut self, system: &System, audio_handle: AudioHandle, repeat: bool) -> Result<(), AudioError> {
-        let player = match self.files.get(&audio_handle) {
-            Some(AudioFile::Smaf(data)) => SmafPlayer::new(data),
-            None => return Err(AudioError::InvalidHandle),
-        };
+        let clip = self.clips.get_mut(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        let player = clip.file.player().ok_or(AudioError::InvalidAudio)?;
+
+        let state = Arc::new(PlaybackState::new(clip.start_position, if self.muted { 0 } else { clip.volume }));
+        clip.start_position = 0;
 
         self.stop(audio_handle);
 
         let mut system_clone = system.clone();
         let sink_clone = self.sink.clone();
-
-        let stop_flag = Arc::new(AtomicBool::new(false));
-        let stop_flag_clone = stop_flag.clone();
-        self.playing.insert(audio_handle, stop_flag);
+        let state_clone = state.clone();
+        self.playing.insert(audio_handle, PlayingAudio { state, repeat });
 
         // TODO use dedicated audio player task
         system.spawn(async move || {
-            player.play(&mut system_clone, &**sink_clone, &stop_flag_clone, repeat).await;
+            player.play(&mut system_clone, &*sink_clone, &state_clone, repeat).await;
 
             Ok(())
         });
@@ -73,20 +267,331 @@ impl Audio {
     }
 
     pub fn stop(&mut self, audio_handle: AudioHandle) {
-        if let Some(stop_flag) = self.playing.remove(&audio_handle) {
-            stop_flag.store(true, Ordering::Relaxed);
+        if let Some(playing) = self.playing.remove(&audio_handle) {
+            playing.state.stop();
         }
     }
 
+    pub fn pause(&mut self, audio_handle: AudioHandle) -> Result<(), AudioError> {
+        self.playing.get(&audio_handle).ok_or(AudioError::InvalidHandle)?.state.set_paused(true);
+
+        Ok(())
+    }
+
+    pub fn resume(&mut self, audio_handle: AudioHandle) -> Result<(), AudioError> {
+        self.playing.get(&audio_handle).ok_or(AudioError::InvalidHandle)?.state.set_paused(false);
+
+        Ok(())
+    }
+
+    /// Seeks a playing clip, keeping it paused if it was, or sets where the next [`Audio::play`] starts.
+    pub fn set_position(&mut self, system: &System, audio_handle: AudioHandle, position: u64) -> Result<(), AudioError> {
+        self.clips.get_mut(&audio_handle).ok_or(AudioError::InvalidHandle)?.start_position = position;
+
+        if let Some(playing) = self.playing.get(&audio_handle) {
+            let (repeat, paused) = (playing.repeat, playing.state.is_paused());
+
+            self.play(system, audio_handle, repeat)?;
+            if paused {
+                self.pause(audio_handle)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Milliseconds into the clip, for a playing or paused clip, or the start position otherwise.
+    pub fn position(&self, audio_handle: AudioHandle) -> Result<u64, AudioError> {
+        let clip = self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+
+        Ok(match self.playing.get(&audio_handle) {
+            Some(playing) => playing.state.position(),
+            None => clip.start_position,
+        })
+    }
+
+    pub fn is_paused(&self, audio_handle: AudioHandle) -> bool {
+        self.playing.get(&audio_handle).is_some_and(|x| x.state.is_paused())
+    }
+
+    pub fn set_volume(&mut self, audio_handle: AudioHandle, volume: u8) -> Result<(), AudioError> {
+        let clip = self.clips.get_mut(&audio_handle).ok_or(AudioError::InvalidHandle)?;
+        clip.volume = volume.min(MAX_VOLUME);
+
+        if let Some(playing) = self.playing.get(&audio_handle)
+            && !self.muted
+        {
+            playing.state.set_volume(clip.volume);
+        }
+
+        Ok(())
+    }
+
+    pub fn volume(&self, audio_handle: AudioHandle) -> Result<u8, AudioError> {
+        Ok(self.clips.get(&audio_handle).ok_or(AudioError::InvalidHandle)?.volume)
+    }
+
+    /// Silences every clip without forgetting their volumes.
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+
+        for (handle, playing) in &self.playing {
+            let volume = self.clips.get(handle).map(|x| x.volume).unwrap_or(0);
+            playing.state.set_volume(if muted { 0 } else { volume });
+        }
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
     pub fn close(&mut self, audio_handle: AudioHandle) -> Result<(), AudioError> {
         self.stop(audio_handle);
 
-        if self.files.remove(&audio_handle).is_none() {
+        if self.clips.remove(&audio_handle).is_none() {
             return Err(AudioError::InvalidHandle);
         }
 
         Ok(())
     }
+
+    pub(crate) fn set_replaying(&self, replaying: bool) {
+        self.sink.replaying.store(replaying, Ordering::Relaxed);
+    }
+
+    pub(crate) fn write_state(&self, writer: &mut SnapshotWriter) {
+        writer.write_u32(self.last_audio_handle);
+        writer.write_bool(self.muted);
+
+        writer.write_u32(self.clips.len() as _);
+        for (handle, clip) in &self.clips {
+            writer.write_u32(*handle);
+            let (file_type, data) = match &clip.file {
+                AudioFile::Smaf(data) => (0, data),
+                AudioFile::Midi(data) => (1, data),
+                AudioFile::Wave(data) => (2, data),
+            };
+            writer.write_u8(file_type);
+            writer.write_bytes(data);
+            writer.write_u8(clip.volume);
+        }
+
+        writer.write_u32(self.playing.len() as _);
+        for (handle, playing) in &self.playing {
+            writer.write_u32(*handle);
+            writer.write_bool(playing.repeat);
+            writer.write_u64(playing.state.position());
+            writer.write_bool(playing.state.is_paused());
+        }
+    }
+}
+
+// a wave that may still be sounding, so pause, volume and stop can reach its voice in the sink
+struct PlayingWave<'a> {
+    channel: u8,
+    sampling_rate: u32,
+    data: &'a [i16],
+    // clip time of the wave's first sample, in milliseconds
+    start: u64,
+    // none while paused
+    voice: Option<WaveVoice>,
+}
+
+impl PlayingWave<'_> {
+    fn end(&self) -> u64 {
+        self.start + (self.data.len() as u64 * 1000).div_ceil(self.sampling_rate as u64)
+    }
+
+    // starts the voice at the sample that belongs at `position`
+    fn start_voice(&mut self, sink: &dyn AudioSink, position: u64, volume: u8) {
+        let offset = (position.saturating_sub(self.start) * self.sampling_rate as u64 / 1000) as usize;
+        if offset >= self.data.len() {
+            return;
+        }
+
+        let voice = sink.play_wave(self.channel, self.sampling_rate, &self.data[offset..]);
+        if volume != MAX_VOLUME {
+            sink.set_wave_volume(voice, volume);
+        }
+        self.voice = Some(voice);
+    }
+
+    fn stop_voice(&mut self, sink: &dyn AudioSink) {
+        if let Some(voice) = self.voice.take() {
+            sink.stop_wave(voice);
+        }
+    }
+}
+
+// what one run of a player has sent to the sink, kept across repeats so a stop can silence whatever is still sounding
+struct Playback<'a> {
+    sink: &'a dyn AudioSink,
+    state: &'a PlaybackState,
+    position: u64,
+    last_time: Instant,
+    paused: bool,
+    volume: u8,
+    active_notes: Vec<(u8, u8)>,
+    used_channels: BTreeSet<u8>,
+    waves: Vec<PlayingWave<'a>>,
+}
+
+impl<'a> Playback<'a> {
+    /// Waits until the clip reaches `time`, following pause and volume changes on the way. Returns false once stopped.
+    async fn wait_until(&mut self, system: &mut System, time: u64) -> bool {
+        loop {
+            if self.state.is_stopped() {
+                return false;
+            }
+
+            let now = system.platform().now();
+            if self.state.is_paused() {
+                if !self.paused {
+                    self.pause();
+                }
+            } else {
+                if self.paused {
+                    self.resume();
+                }
+
+                self.position += now - self.last_time;
+                self.state.position.store(self.position, Ordering::Relaxed);
+                self.update_volume();
+
+                let position = self.position;
+                self.waves.retain(|x| x.end() > position);
+
+                if time <= self.position {
+                    self.last_time = now;
+
+                    return true;
+                }
+            }
+            self.last_time = now;
+
+            let timeout = if self.paused {
+                STATE_POLL_INTERVAL
+            } else {
+                (time - self.position).min(STATE_POLL_INTERVAL)
+            };
+            system.sleep(timeout).await;
+        }
+    }
+
+    fn pause(&mut self) {
+        self.paused = true;
+
+        // held notes would ring through the pause
+        for (channel, note) in self.active_notes.drain(..) {
+            self.sink.midi_note_off(channel, note, 0);
+        }
+        for wave in &mut self.waves {
+            wave.stop_voice(self.sink);
+        }
+    }
+
+    fn resume(&mut self) {
+        self.paused = false;
+
+        self.volume = self.state.volume();
+        for wave in &mut self.waves {
+            wave.start_voice(self.sink, self.position, self.volume);
+        }
+    }
+
+    fn update_volume(&mut self) {
+        let volume = self.state.volume();
+        if volume == self.volume {
+            return;
+        }
+
+        self.volume = volume;
+        for voice in self.waves.iter().filter_map(|x| x.voice) {
+            self.sink.set_wave_volume(voice, volume);
+        }
+    }
+
+    /// Plays the part of a wave that comes after the current position, for waves starting at `time` or earlier.
+    fn play_wave(&mut self, time: u64, channel: u8, sampling_rate: u32, data: &'a [i16]) {
+        if sampling_rate == 0 || data.is_empty() {
+            return;
+        }
+
+        let mut wave = PlayingWave {
+            channel,
+            sampling_rate,
+            data,
+            start: time,
+            voice: None,
+        };
+        if wave.end() <= self.position {
+            return;
+        }
+
+        // a paused player starts it on resume
+        if !self.paused {
+            wave.start_voice(self.sink, self.position, self.volume);
+        }
+        self.waves.push(wave);
+    }
+
+    fn dispatch(&mut self, event: &'a SmafEvent) {
+        match event {
+            SmafEvent::Wave {
+                channel,
+                sampling_rate,
+                data,
+            } => {
+                // from its first sample, even if the wait for it overshot a little
+                self.play_wave(self.position, *channel, *sampling_rate, data);
+            }
+            SmafEvent::MidiNoteOn { channel, note, velocity } => {
+                // velocity 0 would turn this into a note off
+                let velocity = (*velocity as u32 * self.volume as u32 / MAX_VOLUME as u32) as u8;
+                if velocity > 0 {
+                    self.sink.midi_note_on(*channel, *note, velocity);
+                    self.active_notes.push((*channel, *note));
+                }
+                self.used_channels.insert(*channel);
+            }
+            SmafEvent::MidiNoteOff { channel, note, velocity } => {
+                self.sink.midi_note_off(*channel, *note, *velocity);
+                self.active_notes.retain(|(c, n)| !(*c == *channel && *n == *note));
+            }
+            SmafEvent::MidiProgramChange { channel, program } => {
+                self.sink.midi_program_change(*channel, *program);
+                self.used_channels.insert(*channel);
+            }
+            SmafEvent::MidiControlChange { channel, control, value } => {
+                self.sink.midi_control_change(*channel, *control, *value);
+                self.used_channels.insert(*channel);
+            }
+            SmafEvent::MidiPitchBend { channel, value } => {
+                self.sink.midi_pitch_bend(*channel, *value);
+                self.used_channels.insert(*channel);
+            }
+            SmafEvent::MidiSysEx(data) => {
+                self.sink.midi_sysex(data);
+            }
+            SmafEvent::End => {}
+        }
+    }
+
+    fn silence(&mut self) {
+        for (channel, note) in self.active_notes.drain(..) {
+            self.sink.midi_note_off(channel, note, 0);
+        }
+
+        for channel in mem::take(&mut self.used_channels) {
+            self.sink.midi_control_change(channel, 64, 0);
+            self.sink.midi_control_change(channel, 120, 0);
+            self.sink.midi_control_change(channel, 123, 0);
+        }
+
+        for mut wave in self.waves.drain(..) {
+            wave.stop_voice(self.sink);
+        }
+    }
 }
 
 pub struct SmafPlayer {
@@ -98,88 +603,88 @@ impl SmafPlayer {
         Self { events: parse_smaf(data) }
     }
 
-    pub async fn play(&self, system: &mut System, sink: &dyn AudioSink, stop_flag: &AtomicBool, repeat: bool) {
-        loop {
-            let mut active_notes: Vec<(u8, u8)> = Vec::new();
-            let mut used_channels: BTreeSet<u8> = BTreeSet::new();
+    /// Plays an already timed event list; the midi and wave loaders produce the same events smaf does.
+    pub fn from_events(events: Vec<(usize, SmafEvent)>) -> Self {
+        Self { events }
+    }
 
-            let start_time = system.platform().now();
-            for (time, event) in &self.events {
-                if stop_flag.load(Ordering::Relaxed) {
-                    break;
-                }
+    /// Plays from `state.position()` until the end, or until stopped when repeating, following `state` for pause and volume.
+    pub async fn play(&self, system: &mut System, sink: &dyn AudioSink, state: &PlaybackState, repeat: bool) {
+        let mut playback = Playback {
+            sink,
+            state,
+            position: state.position(),
+            last_time: system.platform().now(),
+            paused: state.is_paused(),
+            volume: state.volume(),
+            active_notes: Vec::new(),
+            used_channels: BTreeSet::new(),
+            waves: Vec::new(),
+        };
 
-                let now = system.platform().now();
-                if (*time as u64) > now - start_time {
-                    system.sleep(((*time as u64) - (now - start_time)) as _).await;
+        'outer: loop {
+            let start_position = playback.position;
+            playback.last_time = system.platform().now();
 
-                    if stop_flag.load(Ordering::Relaxed) {
-                        break;
+            for (time, event) in &self.events {
+                let time = *time as u64;
+
+                // events before a seek target only set up channel state, and waves still sounding there play from the middle
+                if time < start_position {
+                    match event {
+                        SmafEvent::Wave {
+                            channel,
+                            sampling_rate,
+                            data,
+                        } => playback.play_wave(time, *channel, *sampling_rate, data),
+                        SmafEvent::MidiNoteOn { .. } | SmafEvent::MidiNoteOff { .. } => {}
+                        _ => playback.dispatch(event),
                     }
+                    continue;
                 }
 
-                match event {
-                    SmafEvent::Wave {
-                        channel,
-                        sampling_rate,
-                        data,
-                    } => {
-                        sink.play_wave(*channel, *sampling_rate, data);
-                    }
-                    SmafEvent::MidiNoteOn { channel, note, velocity } => {
-                        sink.midi_note_on(*channel, *note, *velocity);
-                        active_notes.push((*channel, *note));
-                        used_channels.insert(*channel);
-                    }
-                    SmafEvent::MidiNoteOff { channel, note, velocity } => {
-                        sink.midi_note_off(*channel, *note, *velocity);
-                        active_notes.retain(|(c, n)| !(*c == *channel && *n == *note));
-                    }
-                    SmafEvent::MidiProgramChange { channel, program } => {
-                        sink.midi_program_change(*channel, *program);
-                        used_channels.insert(*channel);
-                    }
-                    SmafEvent::MidiControlChange { channel, control, value } => {
-                        sink.midi_control_change(*channel, *control, *value);
-                        used_channels.insert(*channel);
-                    }
-                    SmafEvent::MidiPitchBend { channel, value } => {
-                        sink.midi_pitch_bend(*channel, *value);
-                        used_channels.insert(*channel);
-                    }
-                    SmafEvent::MidiSysEx(data) => {
-                        sink.midi_sysex(data);
-                    }
-                    SmafEvent::End => {}
+                if !playback.wait_until(system, time).await {
+                    break 'outer;
                 }
-            }
 
-            for (channel, note) in &active_notes {
-                sink.midi_note_off(*channel, *note, 0);
+                playback.dispatch(event);
             }
 
-            for channel in &used_channels {
-                sink.midi_control_change(*channel, 64, 0);
-                sink.midi_control_change(*channel, 120, 0);
-                sink.midi_control_change(*channel, 123, 0);
+            // let the last waves ring out, so they can still be paused or stopped
+            if let Some(end) = playback.waves.iter().map(|x| x.end()).max()
+                && !playback.wait_until(system, end).await
+            {
+                break 'outer;
             }
 
-            if !repeat || stop_flag.load(Ordering::Relaxed) {
-                break;
+            playback.silence();
+
+            if !repeat || state.is_stopped() {
+                return;
             }
+
+            playback.position = 0;
+            state.position.store(0, Ordering::Relaxed);
         }
+
+        // stopped midway
+        playback.silence();
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use alloc::{boxed::Box, sync::Arc, vec};
-    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+    use core::sync::atomic::{AtomicUsize, Ordering};
 
     use smaf_player::SmafEvent;
+    use spin::Mutex;
 
-    use super::SmafPlayer;
-    use crate::{AudioSink, Database, DatabaseRepository, DefaultTaskRunner, Filesystem, Instant, Platform, Screen, System, canvas::Image};
+    use super::{Audio, AudioError, MAX_VOLUME, PlaybackState, SmafPlayer, wave::tests::wave_file};
+    use crate::{
+        AudioSink, Database, DatabaseRepository, DefaultTaskRunner, Filesystem, HandsetProfile, HttpRequest, HttpResponse, HttpTransport, Instant,
+        Network, NetworkError, Platform, Screen, SocketId, SocketType, SynthAudioSink, System, WaveVoice, canvas::Image,
+    };
 
     struct NullDatabase;
 
@@ -250,6 +755,43 @@ mod tests {
         async fn truncate(&self, _aid: &str, _path: &str, _len: usize) {}
     }
 
+    struct NullNetwork;
+
+    impl Network for NullNetwork {
+        fn resolve(&self, _host: &str) -> Result<[u8; 4], NetworkError> {
+            Err(NetworkError::HostNotFound)
+        }
+
+        fn socket(&self, _socket_type: SocketType) -> Result<SocketId, NetworkError> {
+            Err(NetworkError::Other)
+        }
+
+        fn connect(&self, _socket: SocketId, _addr: [u8; 4], _port: u16) -> Result<(), NetworkError> {
+            Err(NetworkError::InvalidSocket)
+        }
+
+        fn send(&self, _socket: SocketId, _data: &[u8]) -> Result<usize, NetworkError> {
+            Err(NetworkError::InvalidSocket)
+        }
+
+        fn recv(&self, _socket: SocketId, _buf: &mut [u8]) -> Result<usize, NetworkError> {
+            Err(NetworkError::InvalidSocket)
+        }
+
+        fn readable(&self, _socket: SocketId) -> bool {
+            false
+        }
+
+        fn close(&self, _socket: SocketId) {}
+    }
+
+    #[async_trait::async_trait]
+    impl HttpTransport for NullNetwork {
+        async fn request(&self, _request: HttpRequest) -> Result<HttpResponse, NetworkError> {
+            Err(NetworkError::HostNotFound)
+        }
+    }
+
     struct NullScreen;
 
     impl Screen for NullScreen {
@@ -272,6 +814,7 @@ mod tests {
         screen: NullScreen,
         database_repository: NullDatabaseRepository,
         filesystem: NullFilesystem,
+        network: NullNetwork,
         now: AtomicUsize,
     }
 
@@ -281,6 +824,7 @@ mod tests {
                 screen: NullScreen,
                 database_repository: NullDatabaseRepository,
                 filesystem: NullFilesystem,
+                network: NullNetwork,
                 now: AtomicUsize::new(0),
             }
         }
@@ -303,6 +847,14 @@ mod tests {
             &self.filesystem
         }
 
+        fn network(&self) -> &dyn Network {
+            &self.network
+        }
+
+        fn http(&self) -> &dyn HttpTransport {
+            &self.network
+        }
+
         fn audio_sink(&self) -> Box<dyn AudioSink> {
             Box::new(NoopAudioSink)
         }
@@ -319,7 +871,13 @@ mod tests {
     struct NoopAudioSink;
 
     impl AudioSink for NoopAudioSink {
-        fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) {}
+        fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) -> WaveVoice {
+            0
+        }
+
+        fn stop_wave(&self, _voice: WaveVoice) {}
+
+        fn set_wave_volume(&self, _voice: WaveVoice, _volume: u8) {}
 
         fn midi_note_on(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
 
@@ -336,21 +894,41 @@ mod tests {
 
     struct CountingSink {
         program_change_count: Arc<AtomicUsize>,
+        note_ons: Arc<Mutex<Vec<(u8, u8)>>>,
         stop_after: usize,
-        stop_flag: Arc<AtomicBool>,
+        state: Arc<PlaybackState>,
+    }
+
+    impl CountingSink {
+        fn new(stop_after: usize, state: Arc<PlaybackState>) -> Self {
+            Self {
+                program_change_count: Arc::new(AtomicUsize::new(0)),
+                note_ons: Arc::new(Mutex::new(Vec::new())),
+                stop_after,
+                state,
+            }
+        }
     }
 
     impl AudioSink for CountingSink {
-        fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) {}
+        fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) -> WaveVoice {
+            0
+        }
 
-        fn midi_note_on(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+        fn stop_wave(&self, _voice: WaveVoice) {}
+
+        fn set_wave_volume(&self, _voice: WaveVoice, _volume: u8) {}
+
+        fn midi_note_on(&self, _channel_id: u8, note: u8, velocity: u8) {
+            self.note_ons.lock().push((note, velocity));
+        }
 
         fn midi_note_off(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
 
         fn midi_program_change(&self, _channel_id: u8, _program: u8) {
             let count = self.program_change_count.fetch_add(1, Ordering::SeqCst) + 1;
             if count >= self.stop_after {
-                self.stop_flag.store(true, Ordering::SeqCst);
+                self.state.stop();
             }
         }
 
@@ -361,45 +939,304 @@ mod tests {
         fn midi_sysex(&self, _data: &[u8]) {}
     }
 
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    enum SinkCall {
+        NoteOn(u8),
+        NoteOff(u8),
+        AllNotesOff(u8),
+    }
+
+    type SinkHook = Box<dyn Fn(SinkCall, &PlaybackState) + Send + Sync>;
+
+    // records note traffic and lets a test drive the playback state from inside the player
+    struct RecordingSink {
+        calls: Mutex<Vec<SinkCall>>,
+        state: Arc<PlaybackState>,
+        hook: SinkHook,
+    }
+
+    impl RecordingSink {
+        fn new(state: Arc<PlaybackState>, hook: SinkHook) -> Self {
+            Self {
+                calls: Mutex::new(Vec::new()),
+                state,
+                hook,
+            }
+        }
+
+        fn record(&self, call: SinkCall) {
+            self.calls.lock().push(call);
+            (self.hook)(call, &self.state);
+        }
+    }
+
+    impl AudioSink for RecordingSink {
+        fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) -> WaveVoice {
+            0
+        }
+
+        fn stop_wave(&self, _voice: WaveVoice) {}
+
+        fn set_wave_volume(&self, _voice: WaveVoice, _volume: u8) {}
+
+        fn midi_note_on(&self, _channel_id: u8, note: u8, _velocity: u8) {
+            self.record(SinkCall::NoteOn(note));
+        }
+
+        fn midi_note_off(&self, _channel_id: u8, note: u8, _velocity: u8) {
+            self.record(SinkCall::NoteOff(note));
+        }
+
+        fn midi_program_change(&self, _channel_id: u8, _program: u8) {}
+
+        fn midi_control_change(&self, channel_id: u8, control: u8, _value: u8) {
+            if control == 123 {
+                self.record(SinkCall::AllNotesOff(channel_id));
+            }
+        }
+
+        fn midi_pitch_bend(&self, _channel_id: u8, _value: u16) {}
+
+        fn midi_sysex(&self, _data: &[u8]) {}
+    }
+
+    fn note_on(note: u8) -> SmafEvent {
+        SmafEvent::MidiNoteOn {
+            channel: 0,
+            note,
+            velocity: 100,
+        }
+    }
+
     fn new_system() -> System {
-        System::new(Box::new(NullPlatform::new()), "test-pid", "test-aid", DefaultTaskRunner)
+        System::new(
+            Box::new(NullPlatform::new()),
+            HandsetProfile::default(),
+            "test-pid",
+            "test-aid",
+            DefaultTaskRunner,
+        )
     }
 
     #[futures_test::test]
     async fn plays_once_when_repeat_is_false() {
-        let counter = Arc::new(AtomicUsize::new(0));
-        let stop_flag = Arc::new(AtomicBool::new(false));
-        let sink = CountingSink {
-            program_change_count: counter.clone(),
-            stop_after: usize::MAX,
-            stop_flag: stop_flag.clone(),
+        let state = Arc::new(PlaybackState::new(0, MAX_VOLUME));
+        let sink = CountingSink::new(usize::MAX, state.clone());
+        let player = SmafPlayer {
+            events: vec![(0, SmafEvent::MidiProgramChange { channel: 0, program: 1 })],
         };
+        let mut system = new_system();
+
+        player.play(&mut system, &sink, &state, false).await;
+
+        assert_eq!(sink.program_change_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[futures_test::test]
+    async fn repeats_until_stopped() {
+        let state = Arc::new(PlaybackState::new(0, MAX_VOLUME));
+        let sink = CountingSink::new(2, state.clone());
         let player = SmafPlayer {
             events: vec![(0, SmafEvent::MidiProgramChange { channel: 0, program: 1 })],
         };
         let mut system = new_system();
 
-        player.play(&mut system, &sink, &stop_flag, false).await;
+        player.play(&mut system, &sink, &state, true).await;
 
-        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(sink.program_change_count.load(Ordering::SeqCst), 2);
     }
 
     #[futures_test::test]
-    async fn repeats_until_stop_flag_is_set() {
-        let counter = Arc::new(AtomicUsize::new(0));
-        let stop_flag = Arc::new(AtomicBool::new(false));
-        let sink = CountingSink {
-            program_change_count: counter.clone(),
-            stop_after: 2,
-            stop_flag: stop_flag.clone(),
+    async fn starts_at_offset_with_volume() {
+        let state = Arc::new(PlaybackState::new(50, 50));
+        let sink = CountingSink::new(usize::MAX, state.clone());
+        let player = SmafPlayer {
+            events: vec![
+                (10, SmafEvent::MidiProgramChange { channel: 0, program: 1 }),
+                (
+                    10,
+                    SmafEvent::MidiNoteOn {
+                        channel: 0,
+                        note: 60,
+                        velocity: 100,
+                    },
+                ),
+                (
+                    55,
+                    SmafEvent::MidiNoteOn {
+                        channel: 0,
+                        note: 64,
+                        velocity: 100,
+                    },
+                ),
+            ],
         };
+        let mut system = new_system();
+
+        player.play(&mut system, &sink, &state, false).await;
+
+        // notes before the offset are skipped, but the program change still applies
+        assert_eq!(sink.program_change_count.load(Ordering::SeqCst), 1);
+        assert_eq!(*sink.note_ons.lock(), [(64, 50)]);
+        assert!(state.position() >= 55);
+    }
+
+    #[futures_test::test]
+    async fn stop_silences_held_notes() {
+        let state = Arc::new(PlaybackState::new(0, MAX_VOLUME));
+        let sink = RecordingSink::new(
+            state.clone(),
+            Box::new(|call, state| {
+                if call == SinkCall::NoteOn(60) {
+                    state.stop();
+                }
+            }),
+        );
         let player = SmafPlayer {
-            events: vec![(0, SmafEvent::MidiProgramChange { channel: 0, program: 1 })],
+            events: vec![(0, note_on(60)), (1000, note_on(64))],
+        };
+        let mut system = new_system();
+
+        player.play(&mut system, &sink, &state, true).await;
+
+        assert_eq!(
+            *sink.calls.lock(),
+            [SinkCall::NoteOn(60), SinkCall::NoteOff(60), SinkCall::AllNotesOff(0)]
+        );
+    }
+
+    #[test]
+    fn pause_releases_notes_until_resumed() {
+        let state = Arc::new(PlaybackState::new(0, MAX_VOLUME));
+        let sink = Arc::new(RecordingSink::new(
+            state.clone(),
+            Box::new(|call, state| match call {
+                SinkCall::NoteOn(60) => state.set_paused(true),
+                SinkCall::NoteOff(60) => state.set_paused(false),
+                _ => {}
+            }),
+        ));
+        let player = SmafPlayer {
+            events: vec![(0, note_on(60)), (40, note_on(64))],
         };
+
+        // pausing sleeps, so this one has to run on the executor
+        let mut system = new_system();
+        let (mut system_clone, sink_clone, state_clone) = (system.clone(), sink.clone(), state.clone());
+        system.spawn(async move || {
+            player.play(&mut system_clone, &*sink_clone, &state_clone, false).await;
+
+            Ok(())
+        });
+        for _ in 0..100 {
+            system.tick().unwrap();
+        }
+
+        // the held note is released when the pause is noticed, and playback picks up again on resume
+        assert_eq!(
+            *sink.calls.lock(),
+            [
+                SinkCall::NoteOn(60),
+                SinkCall::NoteOff(60),
+                SinkCall::NoteOn(64),
+                SinkCall::NoteOff(64),
+                SinkCall::AllNotesOff(0)
+            ]
+        );
+        assert!(state.position() >= 40);
+    }
+
+    // runs the players spawned on `system` for a while
+    fn run_players(system: &mut System) {
+        for _ in 0..10 {
+            system.tick().unwrap();
+        }
+    }
+
+    fn wave_samples(samples: impl Iterator<Item = i16>) -> Vec<u8> {
+        let data = samples.flat_map(|x| x.to_le_bytes()).collect::<Vec<_>>();
+
+        wave_file(1, 1, 1000, 2, 16, &data)
+    }
+
+    #[test]
+    fn wave_seek_starts_inside_the_samples() {
         let mut system = new_system();
+        let synth = SynthAudioSink::new(1000);
+        let mut audio = Audio::new(Box::new(synth.clone()));
+        // two seconds of a ramp, so each sample tells where it was taken from
+        let handle = audio.load_wave(&wave_samples(0..2000)).unwrap();
+
+        audio.set_position(&system, handle, 500).unwrap();
+        audio.play(&system, handle, false).unwrap();
+        run_players(&mut system);
+
+        let mut out = vec![0; 2];
+        synth.render(&mut out);
+        assert_eq!(out, [500, 501]);
+    }
 
-        player.play(&mut system, &sink, &stop_flag, true).await;
+    #[test]
+    fn wave_follows_volume_pause_and_stop() {
+        let mut system = new_system();
+        let synth = SynthAudioSink::new(1000);
+        let mut audio = Audio::new(Box::new(synth.clone()));
+        let handle = audio.load_wave(&wave_samples([1000; 2000].into_iter())).unwrap();
+        let mut out = vec![0; 4];
+
+        audio.play(&system, handle, false).unwrap();
+        run_players(&mut system);
+        synth.render(&mut out);
+        assert_eq!(out, [1000; 4]);
+
+        audio.set_volume(handle, 50).unwrap();
+        run_players(&mut system);
+        synth.render(&mut out);
+        assert_eq!(out, [500; 4]);
+
+        audio.pause(handle).unwrap();
+        run_players(&mut system);
+        synth.render(&mut out);
+        assert_eq!(out, [0; 4]);
+
+        audio.resume(handle).unwrap();
+        run_players(&mut system);
+        synth.render(&mut out);
+        assert_eq!(out, [500; 4]);
+
+        audio.stop(handle);
+        run_players(&mut system);
+        synth.render(&mut out);
+        assert_eq!(out, [0; 4]);
+    }
 
-        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    #[test]
+    fn seek_keeps_pause_state() {
+        let system = new_system();
+        let mut audio = Audio::new(Box::new(NoopAudioSink));
+        // one second of 16 bit mono silence
+        let handle = audio.load_wave(&wave_file(1, 1, 1000, 2, 16, &[0; 2000])).unwrap();
+
+        // seeking a stopped clip moves where the next play starts
+        audio.set_position(&system, handle, 300).unwrap();
+        assert_eq!(audio.position(handle).unwrap(), 300);
+        audio.play(&system, handle, false).unwrap();
+        assert_eq!(audio.position(handle).unwrap(), 300);
+
+        audio.pause(handle).unwrap();
+        audio.set_position(&system, handle, 600).unwrap();
+        assert_eq!(audio.position(handle).unwrap(), 600);
+        assert!(audio.is_paused(handle));
+
+        audio.resume(handle).unwrap();
+        assert!(!audio.is_paused(handle));
+
+        audio.stop(handle);
+        assert!(matches!(audio.pause(handle), Err(AudioError::InvalidHandle)));
+        assert!(matches!(audio.set_position(&system, 100, 0), Err(AudioError::InvalidHandle)));
+
+        assert!(audio.contains(handle));
+        audio.close(handle).unwrap();
+        assert!(!audio.contains(handle));
     }
 }
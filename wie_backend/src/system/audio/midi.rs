@@ -0,0 +1,282 @@
+use alloc::vec::Vec;
+
+use smaf_player::SmafEvent;
+
+// 120 bpm, the default until a set tempo meta event says otherwise
+const DEFAULT_TEMPO: u64 = 500_000;
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        let value = *self.data.get(self.offset)?;
+        self.offset += 1;
+
+        Some(value)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    fn bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        let value = self.data.get(self.offset..self.offset.checked_add(length)?)?;
+        self.offset += length;
+
+        Some(value)
+    }
+
+    fn u32_prefixed(&mut self) -> Option<&'a [u8]> {
+        let length = self.u32()?;
+
+        self.bytes(length as _)
+    }
+
+    fn var_len_prefixed(&mut self) -> Option<&'a [u8]> {
+        let length = self.var_len()?;
+
+        self.bytes(length as _)
+    }
+
+    fn var_len(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7f) as u32;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+enum TrackEvent {
+    Midi(SmafEvent),
+    Tempo(u64),
+    EndOfTrack,
+}
+
+/// Parses a standard MIDI file (format 0 or 1) into a millisecond-timed event list, closed by [`SmafEvent::End`]
+/// at the end of the longest track.
+pub fn parse_midi(data: &[u8]) -> Option<Vec<(usize, SmafEvent)>> {
+    let mut reader = Reader::new(data);
+    if reader.bytes(4)? != b"MThd" {
+        return None;
+    }
+
+    let mut header = Reader::new(reader.u32_prefixed()?);
+    let format = header.u16()?;
+    let track_count = header.u16()?;
+    let division = header.u16()?;
+    if format > 1 || division == 0 {
+        return None;
+    }
+
+    // (tick, order, event); order keeps simultaneous events in file order across tracks
+    let mut events = Vec::new();
+    for _ in 0..track_count {
+        let chunk_type = reader.bytes(4)?;
+        let chunk = reader.u32_prefixed()?;
+        if chunk_type == b"MTrk" {
+            parse_track(chunk, &mut events)?;
+        }
+    }
+    events.sort_by_key(|(tick, order, _)| (*tick, *order));
+
+    let mut result = Vec::with_capacity(events.len());
+    let mut tempo = DEFAULT_TEMPO;
+    let mut last_tick = 0;
+    let mut time_us = 0;
+    for (tick, _, event) in events {
+        time_us += tick_to_us(tick - last_tick, tempo, division);
+        last_tick = tick;
+
+        let time = (time_us / 1000) as usize;
+        match event {
+            TrackEvent::Midi(x) => result.push((time, x)),
+            TrackEvent::Tempo(x) => tempo = x,
+            TrackEvent::EndOfTrack => {}
+        }
+    }
+    result.push(((time_us / 1000) as usize, SmafEvent::End));
+
+    Some(result)
+}
+
+fn tick_to_us(ticks: u64, tempo: u64, division: u16) -> u64 {
+    if division & 0x8000 != 0 {
+        // smpte timing: negative frames per second in the high byte, ticks per frame in the low byte
+        let frames = (-((division >> 8) as i8) as i64) as u64;
+        let ticks_per_frame = (division & 0xff) as u64;
+
+        ticks * 1_000_000 / (frames * ticks_per_frame).max(1)
+    } else {
+        ticks * tempo / division as u64
+    }
+}
+
+fn parse_track(data: &[u8], events: &mut Vec<(u64, usize, TrackEvent)>) -> Option<()> {
+    let mut reader = Reader::new(data);
+    let mut tick = 0u64;
+    let mut running_status = 0u8;
+
+    while !reader.is_empty() {
+        tick += reader.var_len()? as u64;
+
+        let mut status = reader.u8()?;
+        let first_data = if status & 0x80 == 0 {
+            // running status, this byte is already the first data byte
+            let data = status;
+            status = running_status;
+
+            Some(data)
+        } else {
+            None
+        };
+
+        let event = match status {
+            0xff => {
+                let meta_type = reader.u8()?;
+                let meta = reader.var_len_prefixed()?;
+
+                match meta_type {
+                    0x51 if meta.len() == 3 => Some(TrackEvent::Tempo(u32::from_be_bytes([0, meta[0], meta[1], meta[2]]) as _)),
+                    0x2f => Some(TrackEvent::EndOfTrack),
+                    _ => None,
+                }
+            }
+            0xf0 | 0xf7 => {
+                let payload = reader.var_len_prefixed()?;
+
+                // 0xf7 escapes are raw bytes that don't form a complete message on their own
+                (status == 0xf0).then(|| TrackEvent::Midi(SmafEvent::MidiSysEx([&[0xf0], payload].concat())))
+            }
+            0x80..=0xef => {
+                running_status = status;
+
+                let first = match first_data {
+                    Some(x) => x,
+                    None => reader.u8()?,
+                };
+                // program change and channel pressure carry a single data byte
+                let second = if matches!(status & 0xf0, 0xc0 | 0xd0) { 0 } else { reader.u8()? };
+                let channel = status & 0x0f;
+
+                match status & 0xf0 {
+                    0x80 => Some(TrackEvent::Midi(SmafEvent::MidiNoteOff {
+                        channel,
+                        note: first,
+                        velocity: second,
+                    })),
+                    0x90 if second == 0 => Some(TrackEvent::Midi(SmafEvent::MidiNoteOff {
+                        channel,
+                        note: first,
+                        velocity: 0,
+                    })),
+                    0x90 => Some(TrackEvent::Midi(SmafEvent::MidiNoteOn {
+                        channel,
+                        note: first,
+                        velocity: second,
+                    })),
+                    0xb0 => Some(TrackEvent::Midi(SmafEvent::MidiControlChange {
+                        channel,
+                        control: first,
+                        value: second,
+                    })),
+                    0xc0 => Some(TrackEvent::Midi(SmafEvent::MidiProgramChange { channel, program: first })),
+                    0xe0 => Some(TrackEvent::Midi(SmafEvent::MidiPitchBend {
+                        channel,
+                        value: ((second as u16) << 7) | first as u16,
+                    })),
+                    // key and channel pressure, which the sink can't express
+                    _ => None,
+                }
+            }
+            _ => return None,
+        };
+
+        if let Some(event) = event {
+            let end = matches!(event, TrackEvent::EndOfTrack);
+            events.push((tick, events.len(), event));
+
+            if end {
+                break;
+            }
+        }
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use smaf_player::SmafEvent;
+
+    use super::parse_midi;
+
+    #[test]
+    fn test_parse_midi() {
+        let mut data = vec![];
+        data.extend_from_slice(b"MThd");
+        data.extend_from_slice(&[0, 0, 0, 6, 0, 0, 0, 1, 0, 96]); // format 0, 1 track, 96 ticks per quarter
+        let track = [
+            0x00, 0xff, 0x51, 0x03, 0x0f, 0x42, 0x40, // tempo 1_000_000us per quarter
+            0x00, 0xc0, 0x05, // program change
+            0x00, 0x90, 0x3c, 0x64, // note on
+            0x60, 0x3c, 0x00, // running status note on with velocity 0, one quarter later
+            0x30, 0xff, 0x2f, 0x00, // end of track
+        ];
+        data.extend_from_slice(b"MTrk");
+        data.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        data.extend_from_slice(&track);
+
+        let events = parse_midi(&data).unwrap();
+
+        assert_eq!(events.len(), 4);
+        assert!(matches!(events[0], (0, SmafEvent::MidiProgramChange { channel: 0, program: 5 })));
+        assert!(matches!(
+            events[1],
+            (
+                0,
+                SmafEvent::MidiNoteOn {
+                    channel: 0,
+                    note: 0x3c,
+                    velocity: 0x64
+                }
+            )
+        ));
+        assert!(matches!(
+            events[2],
+            (
+                1000,
+                SmafEvent::MidiNoteOff {
+                    channel: 0,
+                    note: 0x3c,
+                    velocity: 0
+                }
+            )
+        ));
+        assert!(matches!(events[3], (1500, SmafEvent::End)));
+
+        assert!(parse_midi(b"RIFF").is_none());
+    }
+}
@@ -0,0 +1,250 @@
+use alloc::{vec, vec::Vec};
+
+use smaf_player::SmafEvent;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_IMA_ADPCM: u16 = 0x11;
+
+const IMA_INDEX_TABLE: [i32; 16] = [-1, -1, -1, -1, 2, 4, 6, 8, -1, -1, -1, -1, 2, 4, 6, 8];
+const IMA_STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66, 73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190,
+    209, 230, 253, 279, 307, 337, 371, 408, 449, 494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272, 2499,
+    2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493, 10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350,
+    22385, 24623, 27086, 29794, 32767,
+];
+
+struct Format {
+    format: u16,
+    channels: u16,
+    sampling_rate: u32,
+    block_align: u16,
+    bits_per_sample: u16,
+}
+
+/// Decodes a RIFF WAVE file (PCM or IMA-ADPCM) into a single mono wave event followed by [`SmafEvent::End`]
+/// once the wave has finished playing.
+pub fn parse_wave(data: &[u8]) -> Option<Vec<(usize, SmafEvent)>> {
+    if data.get(0..4)? != b"RIFF" || data.get(8..12)? != b"WAVE" {
+        return None;
+    }
+
+    let mut format = None;
+    let mut samples = None;
+
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let chunk_type = &data[offset..offset + 4];
+        let length = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().ok()?) as usize;
+        // some encoders write a bogus length on the last chunk, so clamp it to what we have
+        let chunk = &data[offset + 8..(offset + 8).saturating_add(length).min(data.len())];
+
+        match chunk_type {
+            b"fmt " if chunk.len() >= 16 => {
+                format = Some(Format {
+                    format: u16::from_le_bytes([chunk[0], chunk[1]]),
+                    channels: u16::from_le_bytes([chunk[2], chunk[3]]),
+                    sampling_rate: u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+                    block_align: u16::from_le_bytes([chunk[12], chunk[13]]),
+                    bits_per_sample: u16::from_le_bytes([chunk[14], chunk[15]]),
+                })
+            }
+            b"data" => {
+                let format = format.as_ref()?;
+                samples = Some(match format.format {
+                    FORMAT_PCM => decode_pcm(chunk, format)?,
+                    FORMAT_IMA_ADPCM => decode_ima_adpcm(chunk, format)?,
+                    _ => return None,
+                });
+                break;
+            }
+            _ => {}
+        }
+
+        // chunks are word aligned
+        offset = offset.saturating_add(8).saturating_add(length).saturating_add(length & 1);
+    }
+
+    let format = format?;
+    let samples = samples?;
+    if format.sampling_rate == 0 {
+        return None;
+    }
+
+    let duration = samples.len() as u64 * 1000 / format.sampling_rate as u64;
+
+    Some(vec![
+        (
+            0,
+            SmafEvent::Wave {
+                channel: 0,
+                sampling_rate: format.sampling_rate,
+                data: samples,
+            },
+        ),
+        (duration as usize, SmafEvent::End),
+    ])
+}
+
+fn decode_pcm(data: &[u8], format: &Format) -> Option<Vec<i16>> {
+    let channels = format.channels.max(1) as usize;
+    let samples: Vec<i16> = match format.bits_per_sample {
+        8 => data.iter().map(|&x| ((x as i16) - 128) << 8).collect(),
+        16 => data.chunks_exact(2).map(|x| i16::from_le_bytes([x[0], x[1]])).collect(),
+        _ => return None,
+    };
+
+    Some(downmix(&samples, channels))
+}
+
+fn decode_ima_adpcm(data: &[u8], format: &Format) -> Option<Vec<i16>> {
+    let channels = format.channels.max(1) as usize;
+    let block_align = format.block_align as usize;
+    if format.bits_per_sample != 4 || block_align < 4 * channels {
+        return None;
+    }
+
+    let mut samples = Vec::new();
+    for block in data.chunks(block_align) {
+        if block.len() < 4 * channels {
+            break;
+        }
+
+        // each channel starts with a 4 byte header holding the first sample and step index
+        let mut predictors = Vec::with_capacity(channels);
+        let mut step_indices = Vec::with_capacity(channels);
+        for header in block.chunks_exact(4).take(channels) {
+            predictors.push(i16::from_le_bytes([header[0], header[1]]) as i32);
+            step_indices.push((header[2] as i32).clamp(0, 88));
+        }
+        let mut block_samples = vec![predictors.clone()];
+
+        // after the headers, channels take turns with 4 bytes (8 samples) each, low nibble first
+        let body = &block[4 * channels..];
+        for group in body.chunks_exact(4 * channels) {
+            let mut decoded = vec![vec![0; channels]; 8];
+            for (channel, bytes) in group.chunks_exact(4).enumerate() {
+                for (i, &byte) in bytes.iter().enumerate() {
+                    for (j, nibble) in [byte & 0x0f, byte >> 4].into_iter().enumerate() {
+                        decoded[i * 2 + j][channel] = decode_ima_nibble(nibble, &mut predictors[channel], &mut step_indices[channel]);
+                    }
+                }
+            }
+            block_samples.extend(decoded);
+        }
+
+        samples.extend(block_samples.into_iter().flatten().map(|x| x as i16));
+    }
+
+    Some(downmix(&samples, channels))
+}
+
+fn decode_ima_nibble(nibble: u8, predictor: &mut i32, step_index: &mut i32) -> i32 {
+    let step = IMA_STEP_TABLE[*step_index as usize];
+
+    let mut diff = step >> 3;
+    if nibble & 4 != 0 {
+        diff += step;
+    }
+    if nibble & 2 != 0 {
+        diff += step >> 1;
+    }
+    if nibble & 1 != 0 {
+        diff += step >> 2;
+    }
+    if nibble & 8 != 0 {
+        diff = -diff;
+    }
+
+    *predictor = (*predictor + diff).clamp(i16::MIN as i32, i16::MAX as i32);
+    *step_index = (*step_index + IMA_INDEX_TABLE[nibble as usize]).clamp(0, 88);
+
+    *predictor
+}
+
+fn downmix(samples: &[i16], channels: usize) -> Vec<i16> {
+    if channels == 1 {
+        return samples.to_vec();
+    }
+
+    samples
+        .chunks_exact(channels)
+        .map(|x| (x.iter().map(|&x| x as i32).sum::<i32>() / channels as i32) as i16)
+        .collect()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use alloc::vec::Vec;
+
+    use smaf_player::SmafEvent;
+
+    use super::parse_wave;
+
+    pub(in super::super) fn wave_file(
+        format: u16,
+        channels: u16,
+        sampling_rate: u32,
+        block_align: u16,
+        bits_per_sample: u16,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut result = Vec::new();
+        result.extend_from_slice(b"RIFF");
+        result.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        result.extend_from_slice(b"WAVEfmt ");
+        result.extend_from_slice(&16u32.to_le_bytes());
+        result.extend_from_slice(&format.to_le_bytes());
+        result.extend_from_slice(&channels.to_le_bytes());
+        result.extend_from_slice(&sampling_rate.to_le_bytes());
+        result.extend_from_slice(&(sampling_rate * block_align as u32).to_le_bytes());
+        result.extend_from_slice(&block_align.to_le_bytes());
+        result.extend_from_slice(&bits_per_sample.to_le_bytes());
+        result.extend_from_slice(b"data");
+        result.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        result.extend_from_slice(data);
+
+        result
+    }
+
+    #[test]
+    fn test_parse_pcm() {
+        // 16 bit stereo, two frames
+        let data = wave_file(1, 2, 1000, 4, 16, &[0x00, 0x10, 0x00, 0x30, 0xff, 0xff, 0x01, 0x00]);
+        let events = parse_wave(&data).unwrap();
+
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            (0, SmafEvent::Wave { sampling_rate, data, .. }) => {
+                assert_eq!(*sampling_rate, 1000);
+                assert_eq!(data, &[0x2000, 0]);
+            }
+            _ => panic!("expected a wave event"),
+        }
+        assert!(matches!(events[1], (2, SmafEvent::End)));
+
+        // 8 bit mono is unsigned
+        let data = wave_file(1, 1, 8000, 1, 8, &[0x80, 0xff, 0x00]);
+        match &parse_wave(&data).unwrap()[0] {
+            (_, SmafEvent::Wave { data, .. }) => assert_eq!(data, &[0, 0x7f00, -0x8000]),
+            _ => panic!("expected a wave event"),
+        }
+    }
+
+    #[test]
+    fn test_parse_ima_adpcm() {
+        // mono block: predictor 100, step index 0, then 8 nibbles
+        let data = wave_file(0x11, 1, 8000, 8, 4, &[100, 0, 0, 0, 0x07, 0x80, 0x00, 0x00]);
+        let events = parse_wave(&data).unwrap();
+
+        match &events[0] {
+            (0, SmafEvent::Wave { data, .. }) => {
+                // 7 adds 11 at step 7 and bumps the index by 8, so the following 0 adds 16 >> 3
+                assert_eq!(data.len(), 9);
+                assert_eq!(&data[..5], &[100, 111, 113, 114, 113]);
+            }
+            _ => panic!("expected a wave event"),
+        }
+
+        assert!(parse_wave(&wave_file(0x55, 1, 8000, 1, 8, &[0])).is_none());
+    }
+}
@@ -1,9 +1,9 @@
-use alloc::{boxed::Box, collections::VecDeque};
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
 use core::pin::Pin;
 
 use wie_util::Result;
 
-use crate::Instant;
+use crate::{Instant, snapshot::SnapshotWriter};
 
 #[allow(clippy::upper_case_acronyms, non_camel_case_types)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
@@ -36,6 +36,93 @@ pub enum KeyCode {
 }
 
 impl KeyCode {
+    // in declaration order, so `ALL[x as usize] == x`
+    pub(crate) const ALL: [KeyCode; 24] = [
+        KeyCode::UP,
+        KeyCode::DOWN,
+        KeyCode::LEFT,
+        KeyCode::RIGHT,
+        KeyCode::OK,
+        KeyCode::LEFT_SOFT_KEY,
+        KeyCode::RIGHT_SOFT_KEY,
+        KeyCode::CLEAR,
+        KeyCode::CALL,
+        KeyCode::HANGUP,
+        KeyCode::VOLUME_UP,
+        KeyCode::VOLUME_DOWN,
+        KeyCode::NUM0,
+        KeyCode::NUM1,
+        KeyCode::NUM2,
+        KeyCode::NUM3,
+        KeyCode::NUM4,
+        KeyCode::NUM5,
+        KeyCode::NUM6,
+        KeyCode::NUM7,
+        KeyCode::NUM8,
+        KeyCode::NUM9,
+        KeyCode::HASH,
+        KeyCode::STAR,
+    ];
+
+    /// Stable numeric id used by serialized formats.
+    pub fn raw(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_raw(raw: u8) -> Option<KeyCode> {
+        Self::ALL.get(raw as usize).copied()
+    }
+
+    /// Variant name, used by text formats such as input scripts.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyCode::UP => "UP",
+            KeyCode::DOWN => "DOWN",
+            KeyCode::LEFT => "LEFT",
+            KeyCode::RIGHT => "RIGHT",
+            KeyCode::OK => "OK",
+            KeyCode::LEFT_SOFT_KEY => "LEFT_SOFT_KEY",
+            KeyCode::RIGHT_SOFT_KEY => "RIGHT_SOFT_KEY",
+            KeyCode::CLEAR => "CLEAR",
+            KeyCode::CALL => "CALL",
+            KeyCode::HANGUP => "HANGUP",
+            KeyCode::VOLUME_UP => "VOLUME_UP",
+            KeyCode::VOLUME_DOWN => "VOLUME_DOWN",
+            KeyCode::NUM0 => "NUM0",
+            KeyCode::NUM1 => "NUM1",
+            KeyCode::NUM2 => "NUM2",
+            KeyCode::NUM3 => "NUM3",
+            KeyCode::NUM4 => "NUM4",
+            KeyCode::NUM5 => "NUM5",
+            KeyCode::NUM6 => "NUM6",
+            KeyCode::NUM7 => "NUM7",
+            KeyCode::NUM8 => "NUM8",
+            KeyCode::NUM9 => "NUM9",
+            KeyCode::HASH => "HASH",
+            KeyCode::STAR => "STAR",
+        }
+    }
+
+    /// Accepts both [`KeyCode::name`] and the short forms understood by [`KeyCode::parse`].
+    pub fn from_name(name: &str) -> Option<KeyCode> {
+        Self::ALL.into_iter().find(|x| x.name() == name).or(match name {
+            "0" => Some(KeyCode::NUM0),
+            "1" => Some(KeyCode::NUM1),
+            "2" => Some(KeyCode::NUM2),
+            "3" => Some(KeyCode::NUM3),
+            "4" => Some(KeyCode::NUM4),
+            "5" => Some(KeyCode::NUM5),
+            "6" => Some(KeyCode::NUM6),
+            "7" => Some(KeyCode::NUM7),
+            "8" => Some(KeyCode::NUM8),
+            "9" => Some(KeyCode::NUM9),
+            "#" => Some(KeyCode::HASH),
+            "*" => Some(KeyCode::STAR),
+            "CLR" => Some(KeyCode::CLEAR),
+            _ => None,
+        })
+    }
+
     // TODO we can use libraries like strum
     pub fn parse(string: &str) -> KeyCode {
         match string {
@@ -69,6 +156,10 @@ pub enum Event {
     Keydown(KeyCode),
     Keyup(KeyCode),
     Keyrepeat(KeyCode),
+    // touch screen, in screen coordinates
+    Pointerdown { x: i32, y: i32 },
+    Pointerdrag { x: i32, y: i32 },
+    Pointerup { x: i32, y: i32 },
     Timer { due: Instant, callback: TimerCallback },
     Notify { r#type: i32, param1: i32, param2: i32 }, // wipi notifyEvent
 }
@@ -89,18 +180,83 @@ impl Event {
 #[derive(Default)]
 pub struct EventQueue {
     events: VecDeque<Event>,
+    key_map: Vec<(KeyCode, KeyCode)>,
 }
 
 impl EventQueue {
     pub fn new() -> Self {
-        Self { events: VecDeque::new() }
+        Self {
+            events: VecDeque::new(),
+            key_map: Vec::new(),
+        }
+    }
+
+    /// Delivers key events for each `from` key as the paired `to` key.
+    pub fn set_key_map(&mut self, key_map: Vec<(KeyCode, KeyCode)>) {
+        self.key_map = key_map;
     }
 
     pub fn push(&mut self, event: Event) {
+        let map_key = |key| self.key_map.iter().find(|(from, _)| *from == key).map_or(key, |(_, to)| *to);
+
+        let event = match event {
+            Event::Keydown(x) => Event::Keydown(map_key(x)),
+            Event::Keyup(x) => Event::Keyup(map_key(x)),
+            Event::Keyrepeat(x) => Event::Keyrepeat(map_key(x)),
+            x => x,
+        };
+
         self.events.push_back(event);
     }
 
     pub fn pop(&mut self) -> Option<Event> {
         self.events.pop_front()
     }
+
+    /// Timer callbacks are rust closures, so only their due times are written.
+    pub(crate) fn write_state(&self, writer: &mut SnapshotWriter) {
+        writer.write_u32(self.events.len() as _);
+        for event in &self.events {
+            match event {
+                Event::Redraw => writer.write_u8(0),
+                Event::Keydown(code) => {
+                    writer.write_u8(1);
+                    writer.write_u8(code.raw());
+                }
+                Event::Keyup(code) => {
+                    writer.write_u8(2);
+                    writer.write_u8(code.raw());
+                }
+                Event::Keyrepeat(code) => {
+                    writer.write_u8(3);
+                    writer.write_u8(code.raw());
+                }
+                Event::Notify { r#type, param1, param2 } => {
+                    writer.write_u8(4);
+                    writer.write_i32(*r#type);
+                    writer.write_i32(*param1);
+                    writer.write_i32(*param2);
+                }
+                Event::Pointerdown { x, y } => {
+                    writer.write_u8(5);
+                    writer.write_i32(*x);
+                    writer.write_i32(*y);
+                }
+                Event::Pointerdrag { x, y } => {
+                    writer.write_u8(6);
+                    writer.write_i32(*x);
+                    writer.write_i32(*y);
+                }
+                Event::Pointerup { x, y } => {
+                    writer.write_u8(7);
+                    writer.write_i32(*x);
+                    writer.write_i32(*y);
+                }
+                Event::Timer { due, .. } => {
+                    writer.write_u8(8);
+                    writer.write_u64(due.raw());
+                }
+            }
+        }
+    }
 }
@@ -136,6 +136,7 @@ mod tests {
     use crate::{
         audio_sink::AudioSink,
         database::DatabaseRepository,
+        network::{HttpTransport, Network},
         platform::{Filesystem, Platform},
         screen::Screen,
         time::Instant,
@@ -197,6 +198,12 @@ mod tests {
         fn filesystem(&self) -> &dyn Filesystem {
             &self.fs
         }
+        fn network(&self) -> &dyn Network {
+            unimplemented!()
+        }
+        fn http(&self) -> &dyn HttpTransport {
+            unimplemented!()
+        }
         fn audio_sink(&self) -> Box<dyn AudioSink> {
             unimplemented!()
         }
@@ -0,0 +1,273 @@
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+
+use crate::{
+    network::{HttpRequest, HttpResponse, NetworkError, SocketId, SocketType},
+    platform::Platform,
+};
+
+enum HttpState {
+    Idle,
+    Requesting,
+    Done { response: HttpResponse, position: usize },
+    Failed(NetworkError),
+}
+
+/// A pending or completed HTTP exchange. The request body is written with
+/// [`Sockets::send`] before the request goes out, and the response body is read back with [`Sockets::recv`].
+pub struct HttpConnection {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    state: HttpState,
+}
+
+impl HttpConnection {
+    fn new(url: &str) -> Self {
+        Self {
+            method: "GET".into(),
+            url: url.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            state: HttpState::Idle,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    pub fn set_method(&mut self, method: &str) {
+        self.method = method.to_ascii_uppercase();
+    }
+
+    pub fn request_header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(x, _)| x.eq_ignore_ascii_case(name)).map(|(_, x)| x.as_str())
+    }
+
+    /// Sets a request header, replacing any previous value with the same case-insensitive name.
+    pub fn set_request_header(&mut self, name: &str, value: &str) {
+        self.headers.retain(|(x, _)| !x.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.to_string()));
+    }
+
+    /// Builds the request to send. Each connection sends exactly one request.
+    pub fn begin_request(&mut self) -> Result<HttpRequest, NetworkError> {
+        if !matches!(self.state, HttpState::Idle) {
+            return Err(NetworkError::Other);
+        }
+        self.state = HttpState::Requesting;
+
+        Ok(HttpRequest {
+            method: self.method.clone(),
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            body: core::mem::take(&mut self.body),
+        })
+    }
+
+    pub fn finish_request(&mut self, result: Result<HttpResponse, NetworkError>) {
+        self.state = match result {
+            Ok(response) => HttpState::Done { response, position: 0 },
+            Err(x) => HttpState::Failed(x),
+        };
+    }
+
+    /// The response, or [`NetworkError::WouldBlock`] while the request is in flight.
+    pub fn response(&self) -> Result<&HttpResponse, NetworkError> {
+        match &self.state {
+            HttpState::Idle => Err(NetworkError::NotConnected),
+            HttpState::Requesting => Err(NetworkError::WouldBlock),
+            HttpState::Done { response, .. } => Ok(response),
+            HttpState::Failed(x) => Err(*x),
+        }
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<usize, NetworkError> {
+        if !matches!(self.state, HttpState::Idle) {
+            return Err(NetworkError::Other); // request already sent
+        }
+        self.body.extend_from_slice(data);
+
+        Ok(data.len())
+    }
+
+    fn recv(&mut self, buf: &mut [u8]) -> Result<usize, NetworkError> {
+        match &mut self.state {
+            HttpState::Idle => Err(NetworkError::NotConnected),
+            HttpState::Requesting => Err(NetworkError::WouldBlock),
+            HttpState::Done { response, position } => {
+                let remaining = &response.body[*position..];
+                let length = buf.len().min(remaining.len());
+                buf[..length].copy_from_slice(&remaining[..length]);
+                *position += length;
+
+                Ok(length)
+            }
+            HttpState::Failed(x) => Err(*x),
+        }
+    }
+
+    fn readable(&self) -> bool {
+        matches!(self.state, HttpState::Done { .. } | HttpState::Failed(_))
+    }
+}
+
+enum Connection {
+    Socket(SocketId),
+    Http(HttpConnection),
+}
+
+struct Entry {
+    connection: Connection,
+    // id of the current read watch
+    read_watch: Option<u64>,
+}
+
+/// Sockets and HTTP connections opened by the running app on top of [`Platform::network`] and [`Platform::http`].
+/// Both share one id space local to the app; calls on ids the app didn't open fail with [`NetworkError::InvalidSocket`].
+pub struct Sockets {
+    platform: Arc<Box<dyn Platform>>,
+    entries: BTreeMap<SocketId, Entry>,
+    last_id: SocketId,
+    last_watch_id: u64,
+}
+
+impl Sockets {
+    pub(crate) fn new(platform: Arc<Box<dyn Platform>>) -> Self {
+        Self {
+            platform,
+            entries: BTreeMap::new(),
+            last_id: 0,
+            last_watch_id: 0,
+        }
+    }
+
+    pub fn resolve(&self, host: &str) -> Result<[u8; 4], NetworkError> {
+        self.platform.network().resolve(host)
+    }
+
+    pub fn open(&mut self, socket_type: SocketType) -> Result<SocketId, NetworkError> {
+        let socket = self.platform.network().socket(socket_type)?;
+
+        Ok(self.insert(Connection::Socket(socket)))
+    }
+
+    /// Creates an HTTP connection to `url`. Nothing goes out until [`HttpConnection::begin_request`].
+    pub fn open_http(&mut self, url: &str) -> SocketId {
+        self.insert(Connection::Http(HttpConnection::new(url)))
+    }
+
+    pub fn http(&mut self, id: SocketId) -> Result<&mut HttpConnection, NetworkError> {
+        match self.entries.get_mut(&id).map(|x| &mut x.connection) {
+            Some(Connection::Http(x)) => Ok(x),
+            _ => Err(NetworkError::InvalidSocket),
+        }
+    }
+
+    pub fn connect(&self, id: SocketId, addr: [u8; 4], port: u16) -> Result<(), NetworkError> {
+        match self.connection(id)? {
+            Connection::Socket(x) => self.platform.network().connect(*x, addr, port),
+            Connection::Http(_) => Err(NetworkError::InvalidSocket),
+        }
+    }
+
+    pub fn send(&mut self, id: SocketId, data: &[u8]) -> Result<usize, NetworkError> {
+        let platform = self.platform.clone();
+
+        match self.connection_mut(id)? {
+            Connection::Socket(x) => platform.network().send(*x, data),
+            Connection::Http(x) => x.send(data),
+        }
+    }
+
+    pub fn recv(&mut self, id: SocketId, buf: &mut [u8]) -> Result<usize, NetworkError> {
+        let platform = self.platform.clone();
+
+        match self.connection_mut(id)? {
+            Connection::Socket(x) => platform.network().recv(*x, buf),
+            Connection::Http(x) => x.recv(buf),
+        }
+    }
+
+    pub fn readable(&self, id: SocketId) -> bool {
+        match self.connection(id) {
+            Ok(Connection::Socket(x)) => self.platform.network().readable(*x),
+            Ok(Connection::Http(x)) => x.readable(),
+            Err(_) => false,
+        }
+    }
+
+    pub fn close(&mut self, id: SocketId) -> Result<(), NetworkError> {
+        let entry = self.entries.remove(&id).ok_or(NetworkError::InvalidSocket)?;
+        if let Connection::Socket(x) = entry.connection {
+            self.platform.network().close(x);
+        }
+
+        Ok(())
+    }
+
+    pub fn close_all(&mut self) {
+        for id in self.entries.keys().copied().collect::<Vec<_>>() {
+            let _ = self.close(id);
+        }
+    }
+
+    /// Starts a new read watch on `id`, replacing the previous one. The watcher
+    /// should stop once [`Sockets::is_watching`] returns false for the returned id.
+    pub fn watch_read(&mut self, id: SocketId) -> Result<u64, NetworkError> {
+        let entry = self.entries.get_mut(&id).ok_or(NetworkError::InvalidSocket)?;
+
+        self.last_watch_id += 1;
+        entry.read_watch = Some(self.last_watch_id);
+
+        Ok(self.last_watch_id)
+    }
+
+    pub fn unwatch_read(&mut self, id: SocketId) -> Result<(), NetworkError> {
+        self.entries.get_mut(&id).ok_or(NetworkError::InvalidSocket)?.read_watch = None;
+
+        Ok(())
+    }
+
+    pub fn is_watching(&self, id: SocketId, watch_id: u64) -> bool {
+        self.entries.get(&id).is_some_and(|x| x.read_watch == Some(watch_id))
+    }
+
+    fn insert(&mut self, connection: Connection) -> SocketId {
+        self.last_id += 1;
+        self.entries.insert(
+            self.last_id,
+            Entry {
+                connection,
+                read_watch: None,
+            },
+        );
+
+        self.last_id
+    }
+
+    fn connection(&self, id: SocketId) -> Result<&Connection, NetworkError> {
+        self.entries.get(&id).map(|x| &x.connection).ok_or(NetworkError::InvalidSocket)
+    }
+
+    fn connection_mut(&mut self, id: SocketId) -> Result<&mut Connection, NetworkError> {
+        self.entries.get_mut(&id).map(|x| &mut x.connection).ok_or(NetworkError::InvalidSocket)
+    }
+}
+
+impl Drop for Sockets {
+    fn drop(&mut self) {
+        self.close_all();
+    }
+}
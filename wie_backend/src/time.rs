@@ -1,4 +1,8 @@
-use core::ops::{Add, Sub};
+use alloc::sync::Arc;
+use core::{
+    ops::{Add, Sub},
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Instant {
@@ -30,3 +34,26 @@ impl Sub for Instant {
         self.value - rhs.value
     }
 }
+
+/// Clock that only moves when the host advances it, e.g. by 16ms per tick,
+/// so runs with the same input stream are reproducible.
+#[derive(Clone)]
+pub struct VirtualClock {
+    now: Arc<AtomicU64>,
+}
+
+impl VirtualClock {
+    pub fn new(start: Instant) -> Self {
+        Self {
+            now: Arc::new(AtomicU64::new(start.raw())),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        Instant::from_epoch_millis(self.now.load(Ordering::SeqCst))
+    }
+
+    pub fn advance(&self, millis: u64) {
+        self.now.fetch_add(millis, Ordering::SeqCst);
+    }
+}
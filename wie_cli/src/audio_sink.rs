@@ -1,17 +1,24 @@
-use std::sync::{Mutex, mpsc::Sender};
+use std::{num::NonZero, sync::Mutex, time::Duration};
 
 use midir::MidiOutputConnection;
+use rodio::{ChannelCount, SampleRate, Source};
 
+use wie_backend::{SynthAudioSink, WaveVoice};
+
+// samples rendered per lock of the synthesizer, ~12ms at 44.1khz
+const SYNTH_BUFFER_SIZE: usize = 512;
+
+/// Sends midi to an external device and mixes waves through the built in synthesizer's output.
 pub struct AudioSink {
-    midi_out: Option<Mutex<MidiOutputConnection>>,
-    audio_tx: Sender<(u8, u32, Vec<i16>)>,
+    midi_out: Mutex<MidiOutputConnection>,
+    synth: SynthAudioSink,
 }
 
 impl AudioSink {
-    pub fn new(midi_out: Option<MidiOutputConnection>, audio_tx: Sender<(u8, u32, Vec<i16>)>) -> Self {
+    pub fn new(midi_out: MidiOutputConnection, synth: SynthAudioSink) -> Self {
         Self {
-            midi_out: midi_out.map(Mutex::new),
-            audio_tx,
+            midi_out: Mutex::new(midi_out),
+            synth,
         }
     }
 }
@@ -23,46 +30,119 @@ unsafe impl Sync for AudioSink {}
 unsafe impl Send for AudioSink {}
 
 impl wie_backend::AudioSink for AudioSink {
-    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) {
-        self.audio_tx.send((channel, sampling_rate, wave_data.to_vec())).unwrap();
+    fn play_wave(&self, channel: u8, sampling_rate: u32, wave_data: &[i16]) -> WaveVoice {
+        wie_backend::AudioSink::play_wave(&self.synth, channel, sampling_rate, wave_data)
+    }
+
+    fn stop_wave(&self, voice: WaveVoice) {
+        wie_backend::AudioSink::stop_wave(&self.synth, voice)
+    }
+
+    fn set_wave_volume(&self, voice: WaveVoice, volume: u8) {
+        wie_backend::AudioSink::set_wave_volume(&self.synth, voice, volume)
     }
 
     fn midi_note_on(&self, channel_id: u8, note: u8, velocity: u8) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(&[0x90 | channel_id, note, velocity]).unwrap();
-        }
+        self.midi_out.lock().unwrap().send(&[0x90 | channel_id, note, velocity]).unwrap();
     }
 
     fn midi_note_off(&self, channel_id: u8, note: u8, velocity: u8) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(&[0x80 | channel_id, note, velocity]).unwrap();
-        }
+        self.midi_out.lock().unwrap().send(&[0x80 | channel_id, note, velocity]).unwrap();
     }
 
     fn midi_control_change(&self, channel_id: u8, control: u8, value: u8) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(&[0xB0 | channel_id, control, value]).unwrap()
-        }
+        self.midi_out.lock().unwrap().send(&[0xB0 | channel_id, control, value]).unwrap();
     }
 
     fn midi_program_change(&self, channel_id: u8, program: u8) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(&[0xC0 | channel_id, program]).unwrap()
-        }
+        self.midi_out.lock().unwrap().send(&[0xC0 | channel_id, program]).unwrap();
     }
 
     fn midi_pitch_bend(&self, channel_id: u8, value: u16) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock()
-                .unwrap()
-                .send(&[0xE0 | channel_id, (value & 0x7f) as u8, ((value >> 7) & 0x7f) as u8])
-                .unwrap();
-        }
+        self.midi_out
+            .lock()
+            .unwrap()
+            .send(&[0xE0 | channel_id, (value & 0x7f) as u8, ((value >> 7) & 0x7f) as u8])
+            .unwrap();
     }
 
     fn midi_sysex(&self, data: &[u8]) {
-        if let Some(x) = self.midi_out.as_ref() {
-            x.lock().unwrap().send(data).unwrap();
+        self.midi_out.lock().unwrap().send(data).unwrap();
+    }
+}
+
+/// Drops all sound, for runs that open no audio device.
+pub struct NullAudioSink;
+
+impl wie_backend::AudioSink for NullAudioSink {
+    fn play_wave(&self, _channel: u8, _sampling_rate: u32, _wave_data: &[i16]) -> WaveVoice {
+        0
+    }
+
+    fn stop_wave(&self, _voice: WaveVoice) {}
+
+    fn set_wave_volume(&self, _voice: WaveVoice, _volume: u8) {}
+
+    fn midi_note_on(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+
+    fn midi_note_off(&self, _channel_id: u8, _note: u8, _velocity: u8) {}
+
+    fn midi_control_change(&self, _channel_id: u8, _control: u8, _value: u8) {}
+
+    fn midi_program_change(&self, _channel_id: u8, _program: u8) {}
+
+    fn midi_pitch_bend(&self, _channel_id: u8, _value: u16) {}
+
+    fn midi_sysex(&self, _data: &[u8]) {}
+}
+
+/// Endless rodio source pulling the mixed output of a [`SynthAudioSink`].
+pub struct SynthSource {
+    synth: SynthAudioSink,
+    buffer: Vec<i16>,
+    position: usize,
+}
+
+impl SynthSource {
+    pub fn new(synth: SynthAudioSink) -> Self {
+        Self {
+            synth,
+            buffer: vec![0; SYNTH_BUFFER_SIZE],
+            position: SYNTH_BUFFER_SIZE,
+        }
+    }
+}
+
+impl Iterator for SynthSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position == self.buffer.len() {
+            self.synth.render(&mut self.buffer);
+            self.position = 0;
         }
+
+        let sample = self.buffer[self.position];
+        self.position += 1;
+
+        Some(sample as f32 / 32768.0)
+    }
+}
+
+impl Source for SynthSource {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        NonZero::new(1).unwrap()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        NonZero::new(self.synth.sample_rate()).unwrap()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
     }
 }
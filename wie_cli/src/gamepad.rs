@@ -0,0 +1,59 @@
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+// how far the stick has to be pushed to press a d-pad direction
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Game controller input. The left stick acts as the d-pad.
+pub struct Gamepad {
+    gilrs: Gilrs,
+    // left stick x and y, -1, 0 or 1
+    stick: [i8; 2],
+}
+
+impl Gamepad {
+    /// Returns `None` if controllers aren't supported on this system.
+    pub fn new() -> Option<Self> {
+        let gilrs = Gilrs::new().inspect_err(|x| tracing::warn!("Gamepad support disabled: {x}")).ok()?;
+
+        Some(Self { gilrs, stick: [0; 2] })
+    }
+
+    /// Button changes since the last call, as `(button, pressed)`.
+    pub fn poll(&mut self) -> Vec<(Button, bool)> {
+        let mut result = Vec::new();
+
+        while let Some(event) = self.gilrs.next_event() {
+            match event.event {
+                EventType::ButtonPressed(button, _) => result.push((button, true)),
+                EventType::ButtonReleased(button, _) => result.push((button, false)),
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => self.move_stick(0, value, Button::DPadLeft, Button::DPadRight, &mut result),
+                EventType::AxisChanged(Axis::LeftStickY, value, _) => self.move_stick(1, value, Button::DPadDown, Button::DPadUp, &mut result),
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    fn move_stick(&mut self, axis: usize, value: f32, negative: Button, positive: Button, result: &mut Vec<(Button, bool)>) {
+        let direction = if value <= -STICK_THRESHOLD {
+            -1
+        } else if value >= STICK_THRESHOLD {
+            1
+        } else {
+            0
+        };
+
+        let button = |x| if x < 0 { negative } else { positive };
+        let old = self.stick[axis];
+        if old != direction {
+            if old != 0 {
+                result.push((button(old), false));
+            }
+            if direction != 0 {
+                result.push((button(direction), true));
+            }
+            self.stick[axis] = direction;
+        }
+    }
+}
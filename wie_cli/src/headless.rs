@@ -0,0 +1,343 @@
+use std::{
+    collections::BTreeSet,
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use wie_backend::{Emulator, Event, KeyCode, Screen, VirtualClock, canvas::Image};
+
+use crate::CliScreen;
+
+/// When a scripted input fires: after a number of `Emulator::tick` calls, or
+/// after time since the run started (virtual time when running on a virtual clock).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ScriptTime {
+    Tick(u64),
+    Millis(u64),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ScriptEntry {
+    pub time: ScriptTime,
+    pub event: ScriptEvent,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ScriptEvent {
+    Keydown(KeyCode),
+    Keyup(KeyCode),
+    Keyrepeat(KeyCode),
+    Pointerdown { x: i32, y: i32 },
+    Pointerdrag { x: i32, y: i32 },
+    Pointerup { x: i32, y: i32 },
+}
+
+impl ScriptEvent {
+    fn into_event(self) -> Event {
+        match self {
+            Self::Keydown(x) => Event::Keydown(x),
+            Self::Keyup(x) => Event::Keyup(x),
+            Self::Keyrepeat(x) => Event::Keyrepeat(x),
+            Self::Pointerdown { x, y } => Event::Pointerdown { x, y },
+            Self::Pointerdrag { x, y } => Event::Pointerdrag { x, y },
+            Self::Pointerup { x, y } => Event::Pointerup { x, y },
+        }
+    }
+}
+
+/// Input script, one event per line:
+///
+/// ```text
+/// # at tick 30
+/// 30 down OK
+/// 35 up OK
+/// # 1.5s after start
+/// 1500ms down 5
+/// 1600ms repeat 5
+/// 1700ms up #
+/// # touch screen, at screen coordinates
+/// 2000ms touch 120,160
+/// 2050ms drag 130,160
+/// 2100ms release 130,160
+/// ```
+///
+/// Key names are [`KeyCode::from_name`] names, so `#` only starts a comment at the beginning of a line.
+#[derive(Default)]
+pub struct InputScript {
+    entries: Vec<ScriptEntry>,
+}
+
+impl InputScript {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(script: &str) -> anyhow::Result<Self> {
+        let entries = script
+            .lines()
+            .enumerate()
+            .filter_map(|(line_number, line)| {
+                let line = line.trim();
+                (!line.is_empty() && !line.starts_with('#'))
+                    .then(|| Self::parse_line(line).map_err(|e| anyhow::anyhow!("line {}: {e}", line_number + 1)))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self { entries })
+    }
+
+    fn parse_line(line: &str) -> anyhow::Result<ScriptEntry> {
+        let mut parts = line.split_whitespace();
+        let (Some(time), Some(action), Some(target), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+            anyhow::bail!("expected `<time> <down|up|repeat> <key>` or `<time> <touch|drag|release> <x>,<y>`");
+        };
+
+        let time = if let Some(millis) = time.strip_suffix("ms") {
+            ScriptTime::Millis(millis.parse()?)
+        } else {
+            ScriptTime::Tick(time.parse()?)
+        };
+
+        let key = || KeyCode::from_name(target).ok_or_else(|| anyhow::anyhow!("unknown key {target}"));
+        let position = || {
+            let (x, y) = target.split_once(',').ok_or_else(|| anyhow::anyhow!("expected <x>,<y>, got {target}"))?;
+
+            anyhow::Ok((x.parse()?, y.parse()?))
+        };
+        let event = match action {
+            "down" => ScriptEvent::Keydown(key()?),
+            "up" => ScriptEvent::Keyup(key()?),
+            "repeat" => ScriptEvent::Keyrepeat(key()?),
+            "touch" => {
+                let (x, y) = position()?;
+                ScriptEvent::Pointerdown { x, y }
+            }
+            "drag" => {
+                let (x, y) = position()?;
+                ScriptEvent::Pointerdrag { x, y }
+            }
+            "release" => {
+                let (x, y) = position()?;
+                ScriptEvent::Pointerup { x, y }
+            }
+            _ => anyhow::bail!("unknown action {action}"),
+        };
+
+        Ok(ScriptEntry { time, event })
+    }
+
+    /// Removes and returns the entries due at `tick` / `elapsed`, keeping file order.
+    fn take_due(&mut self, tick: u64, elapsed: Duration) -> Vec<ScriptEntry> {
+        let (due, pending) = mem::take(&mut self.entries).into_iter().partition(|x| match x.time {
+            ScriptTime::Tick(t) => t <= tick,
+            ScriptTime::Millis(ms) => ms as u128 <= elapsed.as_millis(),
+        });
+        self.entries = pending;
+
+        due
+    }
+}
+
+struct HeadlessState {
+    quit: AtomicBool,
+    redraw_requested: AtomicBool,
+    frame_count: AtomicU64,
+    frame_out: Option<PathBuf>,
+    frames: Option<BTreeSet<u64>>,
+}
+
+/// `Screen` without a window. Painted frames are optionally written as
+/// `frame_<index>.png` to `frame_out`; `frames` limits which paint indices are saved.
+#[derive(Clone)]
+pub struct HeadlessScreen {
+    width: u32,
+    height: u32,
+    state: Arc<HeadlessState>,
+}
+
+impl HeadlessScreen {
+    pub fn new(width: u32, height: u32, frame_out: Option<PathBuf>, frames: Option<BTreeSet<u64>>) -> anyhow::Result<Self> {
+        if let Some(frame_out) = &frame_out {
+            fs::create_dir_all(frame_out)?;
+        }
+
+        Ok(Self {
+            width,
+            height,
+            state: Arc::new(HeadlessState {
+                quit: AtomicBool::new(false),
+                redraw_requested: AtomicBool::new(false),
+                frame_count: AtomicU64::new(0),
+                frame_out,
+                frames,
+            }),
+        })
+    }
+
+    fn write_frame(path: &Path, image: &dyn Image) -> anyhow::Result<()> {
+        let data = image.colors().iter().flat_map(|x| [x.r, x.g, x.b]).collect::<Vec<_>>();
+
+        Ok(image::save_buffer(path, &data, image.width(), image.height(), image::ColorType::Rgb8)?)
+    }
+}
+
+impl Screen for HeadlessScreen {
+    fn request_redraw(&self) -> wie_util::Result<()> {
+        self.state.redraw_requested.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    fn paint(&self, image: &dyn Image) {
+        let index = self.state.frame_count.fetch_add(1, Ordering::Relaxed);
+
+        let Some(frame_out) = &self.state.frame_out else {
+            return;
+        };
+        if self.state.frames.as_ref().is_some_and(|x| !x.contains(&index)) {
+            return;
+        }
+
+        let path = frame_out.join(format!("frame_{index:06}.png"));
+        if let Err(e) = Self::write_frame(&path, image) {
+            tracing::error!("Failed to write {}: {e}", path.display());
+        }
+    }
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl CliScreen for HeadlessScreen {
+    fn quit(&self) {
+        self.state.quit.store(true, Ordering::Relaxed);
+    }
+}
+
+pub struct HeadlessOptions {
+    pub script: InputScript,
+    /// Stop after this many ticks instead of waiting for `Platform::exit`.
+    pub ticks: Option<u64>,
+    pub frame_out: Option<PathBuf>,
+    pub frames: Option<BTreeSet<u64>>,
+    /// Virtual clock step per tick; wall-clock time is used when `None`.
+    pub tick_millis: Option<u64>,
+}
+
+/// Runs `emulator` without a window until `options.ticks` ticks have run (if given),
+/// the app calls `Platform::exit`, or a tick fails. `clock` must be the platform's
+/// virtual clock when `options.tick_millis` is set.
+pub fn run(mut emulator: Box<dyn Emulator>, screen: HeadlessScreen, clock: Option<VirtualClock>, options: HeadlessOptions) -> anyhow::Result<()> {
+    let HeadlessOptions {
+        mut script,
+        ticks: max_ticks,
+        tick_millis,
+        ..
+    } = options;
+
+    let start = Instant::now();
+    let virtual_start = clock.as_ref().map(|x| x.now());
+    let elapsed = || match (&clock, virtual_start) {
+        (Some(clock), Some(virtual_start)) => Duration::from_millis(clock.now() - virtual_start),
+        _ => start.elapsed(),
+    };
+
+    let mut tick = 0;
+    while !screen.state.quit.load(Ordering::Relaxed) && max_ticks.is_none_or(|x| tick < x) {
+        for entry in script.take_due(tick, elapsed()) {
+            emulator.handle_event(entry.event.into_event());
+        }
+
+        if screen.state.redraw_requested.swap(false, Ordering::Relaxed) {
+            emulator.handle_event(Event::Redraw);
+        }
+
+        emulator.tick()?;
+        tick += 1;
+
+        if let (Some(clock), Some(tick_millis)) = (&clock, tick_millis) {
+            clock.advance(tick_millis);
+        }
+    }
+
+    tracing::info!(
+        "Headless run finished after {tick} ticks, {} frames painted",
+        screen.state.frame_count.load(Ordering::Relaxed)
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use wie_backend::KeyCode;
+
+    use super::{InputScript, ScriptEntry, ScriptEvent, ScriptTime};
+
+    #[test]
+    fn parse_script() -> anyhow::Result<()> {
+        let script = InputScript::parse("# start\n\n10 down OK\n12 up OK\n1500ms repeat #\n20 touch 12,-3\n")?;
+
+        assert_eq!(
+            script.entries,
+            vec![
+                ScriptEntry {
+                    time: ScriptTime::Tick(10),
+                    event: ScriptEvent::Keydown(KeyCode::OK)
+                },
+                ScriptEntry {
+                    time: ScriptTime::Tick(12),
+                    event: ScriptEvent::Keyup(KeyCode::OK)
+                },
+                ScriptEntry {
+                    time: ScriptTime::Millis(1500),
+                    event: ScriptEvent::Keyrepeat(KeyCode::HASH)
+                },
+                ScriptEntry {
+                    time: ScriptTime::Tick(20),
+                    event: ScriptEvent::Pointerdown { x: 12, y: -3 }
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_script_rejects_invalid_lines() {
+        assert!(InputScript::parse("10 press OK").is_err());
+        assert!(InputScript::parse("10 down FOO").is_err());
+        assert!(InputScript::parse("soon down OK").is_err());
+        assert!(InputScript::parse("10 down").is_err());
+        assert!(InputScript::parse("10 touch 12").is_err());
+        assert!(InputScript::parse("10 down 12,3").is_err());
+    }
+
+    #[test]
+    fn take_due_entries() -> anyhow::Result<()> {
+        let mut script = InputScript::parse("5 down UP\n100ms up UP\n10 down DOWN")?;
+
+        assert!(script.take_due(0, Duration::ZERO).is_empty());
+        assert_eq!(script.take_due(5, Duration::from_millis(50)).len(), 1);
+        assert_eq!(script.take_due(6, Duration::from_millis(100))[0].event, ScriptEvent::Keyup(KeyCode::UP));
+        assert_eq!(
+            script.take_due(20, Duration::from_millis(200))[0].event,
+            ScriptEvent::Keydown(KeyCode::DOWN)
+        );
+        assert!(script.entries.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    hash::Hash,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use gilrs::Button;
+use serde::{Deserialize, de::value::StrDeserializer};
+use winit::keyboard::{KeyCode as WinitKeyCode, PhysicalKey};
+
+use wie_backend::KeyCode;
+
+/// Key repeat timing: the first `Keyrepeat` `delay` after a key goes down, then one every `interval`.
+/// An interval of zero turns repeating off.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyRepeat {
+    pub delay: Duration,
+    pub interval: Duration,
+}
+
+impl Default for KeyRepeat {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(100),
+            interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Key repeat timing shared between the window loop and the platform, which the app may change at runtime.
+pub type SharedKeyRepeat = Arc<Mutex<KeyRepeat>>;
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+enum Layout {
+    /// Keypad on the left of the keyboard, `1 2 3` / `Q W E` / `A S D` / `Z X C`.
+    #[default]
+    Qwerty,
+    /// Keypad on the numeric keypad, rows flipped so `Numpad7` is `1`.
+    Numpad,
+}
+
+impl Layout {
+    fn keypad(self) -> [(WinitKeyCode, KeyCode); 12] {
+        match self {
+            Self::Qwerty => [
+                (WinitKeyCode::Digit1, KeyCode::NUM1),
+                (WinitKeyCode::Digit2, KeyCode::NUM2),
+                (WinitKeyCode::Digit3, KeyCode::NUM3),
+                (WinitKeyCode::KeyQ, KeyCode::NUM4),
+                (WinitKeyCode::KeyW, KeyCode::NUM5),
+                (WinitKeyCode::KeyE, KeyCode::NUM6),
+                (WinitKeyCode::KeyA, KeyCode::NUM7),
+                (WinitKeyCode::KeyS, KeyCode::NUM8),
+                (WinitKeyCode::KeyD, KeyCode::NUM9),
+                (WinitKeyCode::KeyZ, KeyCode::STAR),
+                (WinitKeyCode::KeyX, KeyCode::NUM0),
+                (WinitKeyCode::KeyC, KeyCode::HASH),
+            ],
+            Self::Numpad => [
+                (WinitKeyCode::Numpad7, KeyCode::NUM1),
+                (WinitKeyCode::Numpad8, KeyCode::NUM2),
+                (WinitKeyCode::Numpad9, KeyCode::NUM3),
+                (WinitKeyCode::Numpad4, KeyCode::NUM4),
+                (WinitKeyCode::Numpad5, KeyCode::NUM5),
+                (WinitKeyCode::Numpad6, KeyCode::NUM6),
+                (WinitKeyCode::Numpad1, KeyCode::NUM7),
+                (WinitKeyCode::Numpad2, KeyCode::NUM8),
+                (WinitKeyCode::Numpad3, KeyCode::NUM9),
+                (WinitKeyCode::NumpadDivide, KeyCode::STAR),
+                (WinitKeyCode::Numpad0, KeyCode::NUM0),
+                (WinitKeyCode::NumpadMultiply, KeyCode::HASH),
+            ],
+        }
+    }
+}
+
+// bound in every layout
+const COMMON_BINDINGS: [(WinitKeyCode, KeyCode); 12] = [
+    (WinitKeyCode::Space, KeyCode::OK),
+    (WinitKeyCode::ArrowUp, KeyCode::UP),
+    (WinitKeyCode::ArrowDown, KeyCode::DOWN),
+    (WinitKeyCode::ArrowLeft, KeyCode::LEFT),
+    (WinitKeyCode::ArrowRight, KeyCode::RIGHT),
+    (WinitKeyCode::Backspace, KeyCode::CLEAR),
+    (WinitKeyCode::ShiftLeft, KeyCode::LEFT_SOFT_KEY),
+    (WinitKeyCode::ShiftRight, KeyCode::RIGHT_SOFT_KEY),
+    (WinitKeyCode::Backquote, KeyCode::VOLUME_UP),
+    (WinitKeyCode::Tab, KeyCode::VOLUME_DOWN),
+    (WinitKeyCode::F1, KeyCode::CALL),
+    (WinitKeyCode::F2, KeyCode::HANGUP),
+];
+
+const GAMEPAD_BINDINGS: [(Button, KeyCode); 12] = [
+    (Button::DPadUp, KeyCode::UP),
+    (Button::DPadDown, KeyCode::DOWN),
+    (Button::DPadLeft, KeyCode::LEFT),
+    (Button::DPadRight, KeyCode::RIGHT),
+    (Button::South, KeyCode::OK),
+    (Button::East, KeyCode::CLEAR),
+    (Button::West, KeyCode::NUM5),
+    (Button::North, KeyCode::NUM0),
+    (Button::LeftTrigger, KeyCode::STAR),
+    (Button::RightTrigger, KeyCode::HASH),
+    (Button::Select, KeyCode::LEFT_SOFT_KEY),
+    (Button::Start, KeyCode::RIGHT_SOFT_KEY),
+];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawKeyConfig {
+    #[serde(default)]
+    layout: Layout,
+    repeat_delay: Option<u64>,
+    repeat_interval: Option<u64>,
+    #[serde(default)]
+    bindings: BTreeMap<String, String>,
+    #[serde(default)]
+    gamepad: BTreeMap<String, String>,
+}
+
+/// Host key bindings and key repeat timing, loaded from a toml file:
+///
+/// ```toml
+/// # keypad placement, `qwerty` (default) or `numpad`
+/// layout = "numpad"
+/// # milliseconds before the first repeat and between repeats, 100 by default
+/// repeat_delay = 300
+/// repeat_interval = 50
+///
+/// # winit `KeyCode` names to `KeyCode::from_name` names, on top of the layout; "" unbinds a key
+/// [bindings]
+/// Enter = "OK"
+/// KeyJ = "LEFT"
+/// Tab = ""
+///
+/// # gilrs `Button` names to key names, on top of the defaults: d-pad for the arrows, `South` for OK, `East` for CLEAR,
+/// # `West` and `North` for 5 and 0, `LeftTrigger` and `RightTrigger` for * and #, `Select` and `Start` for the soft keys
+/// [gamepad]
+/// LeftTrigger2 = "1"
+/// RightTrigger2 = "3"
+/// ```
+pub struct KeyConfig {
+    bindings: HashMap<WinitKeyCode, KeyCode>,
+    gamepad: HashMap<Button, KeyCode>,
+    pub repeat: KeyRepeat,
+}
+
+impl KeyConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(config: &str) -> anyhow::Result<Self> {
+        let raw: RawKeyConfig = toml::from_str(config)?;
+
+        let mut bindings = raw.layout.keypad().into_iter().chain(COMMON_BINDINGS).collect();
+        Self::bind(&mut bindings, raw.bindings, "host key")?;
+
+        let mut gamepad = GAMEPAD_BINDINGS.into_iter().collect();
+        Self::bind(&mut gamepad, raw.gamepad, "gamepad button")?;
+
+        let default_repeat = KeyRepeat::default();
+        let repeat = KeyRepeat {
+            delay: raw.repeat_delay.map(Duration::from_millis).unwrap_or(default_repeat.delay),
+            interval: raw.repeat_interval.map(Duration::from_millis).unwrap_or(default_repeat.interval),
+        };
+
+        Ok(Self { bindings, gamepad, repeat })
+    }
+
+    // `from` names are serde variant names of `T`
+    fn bind<T>(bindings: &mut HashMap<T, KeyCode>, overrides: BTreeMap<String, String>, kind: &str) -> anyhow::Result<()>
+    where
+        T: for<'de> Deserialize<'de> + Eq + Hash,
+    {
+        for (from, to) in overrides {
+            let from =
+                T::deserialize(StrDeserializer::<serde::de::value::Error>::new(&from)).map_err(|_| anyhow::anyhow!("unknown {kind} `{from}`"))?;
+
+            if to.is_empty() {
+                bindings.remove(&from);
+            } else {
+                let to = KeyCode::from_name(&to).ok_or_else(|| anyhow::anyhow!("unknown key `{to}`"))?;
+                bindings.insert(from, to);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn convert(&self, key: PhysicalKey) -> Option<KeyCode> {
+        match key {
+            PhysicalKey::Code(x) => self.bindings.get(&x).copied(),
+            PhysicalKey::Unidentified(_) => None,
+        }
+    }
+
+    pub fn convert_button(&self, button: Button) -> Option<KeyCode> {
+        self.gamepad.get(&button).copied()
+    }
+}
+
+impl Default for KeyConfig {
+    fn default() -> Self {
+        Self::parse("").unwrap()
+    }
+}
+
+/// Tracks held keys and generates their `Keyrepeat`s.
+pub struct KeyRepeater {
+    repeat: SharedKeyRepeat,
+    // when each held key repeats next
+    held: HashMap<KeyCode, Instant>,
+}
+
+impl KeyRepeater {
+    pub fn new(repeat: SharedKeyRepeat) -> Self {
+        Self {
+            repeat,
+            held: HashMap::new(),
+        }
+    }
+
+    /// Returns false if the key is already held.
+    pub fn press(&mut self, key: KeyCode, now: Instant) -> bool {
+        if self.held.contains_key(&key) {
+            return false;
+        }
+
+        let delay = self.repeat.lock().unwrap().delay;
+        self.held.insert(key, now + delay);
+
+        true
+    }
+
+    /// Returns false if the key isn't held.
+    pub fn release(&mut self, key: KeyCode) -> bool {
+        self.held.remove(&key).is_some()
+    }
+
+    /// Keys due to repeat at `now`.
+    pub fn poll(&mut self, now: Instant) -> Vec<KeyCode> {
+        let interval = self.repeat.lock().unwrap().interval;
+        if interval.is_zero() {
+            return Vec::new();
+        }
+
+        self.held
+            .iter_mut()
+            .filter(|(_, next)| **next <= now)
+            .map(|(key, next)| {
+                *next = now + interval;
+
+                *key
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use gilrs::Button;
+    use winit::keyboard::{KeyCode as WinitKeyCode, PhysicalKey};
+
+    use wie_backend::KeyCode;
+
+    use super::{KeyConfig, KeyRepeat, KeyRepeater};
+
+    #[test]
+    fn test_parse() {
+        let config = KeyConfig::parse(
+            r#"
+layout = "numpad"
+repeat_delay = 300
+
+[bindings]
+Enter = "OK"
+Tab = ""
+
+[gamepad]
+RightTrigger2 = "3"
+West = ""
+"#,
+        )
+        .unwrap();
+
+        let convert = |x| config.convert(PhysicalKey::Code(x));
+        assert_eq!(convert(WinitKeyCode::Numpad7), Some(KeyCode::NUM1));
+        assert_eq!(convert(WinitKeyCode::Digit1), None);
+        assert_eq!(convert(WinitKeyCode::Enter), Some(KeyCode::OK));
+        assert_eq!(convert(WinitKeyCode::Space), Some(KeyCode::OK));
+        assert_eq!(convert(WinitKeyCode::Tab), None);
+        assert_eq!(config.convert_button(Button::DPadUp), Some(KeyCode::UP));
+        assert_eq!(config.convert_button(Button::RightTrigger2), Some(KeyCode::NUM3));
+        assert_eq!(config.convert_button(Button::West), None);
+        assert_eq!(config.repeat.delay, Duration::from_millis(300));
+        assert_eq!(config.repeat.interval, KeyRepeat::default().interval);
+
+        assert!(KeyConfig::parse("[bindings]\nNoSuchKey = \"OK\"").is_err());
+        assert!(KeyConfig::parse("[bindings]\nEnter = \"NO_SUCH_KEY\"").is_err());
+        assert!(KeyConfig::parse("[gamepad]\nNoSuchButton = \"OK\"").is_err());
+    }
+
+    #[test]
+    fn test_repeat() {
+        let repeat = Arc::new(Mutex::new(KeyRepeat {
+            delay: Duration::from_millis(300),
+            interval: Duration::from_millis(50),
+        }));
+        let mut repeater = KeyRepeater::new(repeat.clone());
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(repeater.press(KeyCode::OK, start));
+        assert!(!repeater.press(KeyCode::OK, at(10)));
+        assert!(repeater.poll(at(299)).is_empty());
+        assert_eq!(repeater.poll(at(300)), [KeyCode::OK]);
+        assert!(repeater.poll(at(349)).is_empty());
+        assert_eq!(repeater.poll(at(350)), [KeyCode::OK]);
+
+        repeat.lock().unwrap().interval = Duration::ZERO;
+        assert!(repeater.poll(at(1000)).is_empty());
+
+        assert!(repeater.release(KeyCode::OK));
+        assert!(!repeater.release(KeyCode::OK));
+    }
+}
@@ -3,110 +3,148 @@ extern crate alloc;
 mod audio_sink;
 mod database;
 mod filesystem;
+mod gamepad;
+mod headless;
+mod keymap;
+mod network;
+mod presentation;
 mod window;
 
 use core::str;
 use std::{
-    collections::{HashMap, hash_map::Entry},
+    collections::BTreeSet,
     error::Error,
     fs::{self, File},
     io::{LineWriter, Write, stderr},
-    num::NonZero,
-    path::PathBuf,
-    sync::{
-        Mutex,
-        mpsc::{Receiver, Sender, channel},
-    },
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
     thread,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{self, Duration, SystemTime, UNIX_EPOCH},
 };
 
 use clap::Parser;
+use directories::ProjectDirs;
 use midir::MidiOutput;
-use rodio::{DeviceSinkBuilder, Player, buffer::SamplesBuffer, conversions::SampleTypeConverter};
-use winit::keyboard::{KeyCode as WinitKeyCode, PhysicalKey};
+use rodio::DeviceSinkBuilder;
 
-use wie_backend::{Emulator, Event, Filesystem, Instant, KeyCode, Options, Platform, ProfileSample, Screen, extract_zip};
+use wie_backend::{
+    CaptureAudioSink, Emulator, Event, Filesystem, GameConfig, HandsetProfile, HttpTransport, InputLog, InputPlayer, InputRecorder, Instant, KeyCode,
+    Network, Options, Platform, ProfileSample, Screen, SharedInputLog, SvcTrace, SymbolResolver, SynthAudioSink, VirtualClock, canvas::FontPack,
+    extract_zip,
+};
 use wie_j2me::J2MEEmulator;
 use wie_ktf::KtfEmulator;
 use wie_lgt::LgtEmulator;
 use wie_skt::SktEmulator;
 
 use self::{
-    audio_sink::AudioSink,
+    audio_sink::{AudioSink, NullAudioSink, SynthSource},
     database::DatabaseRepository,
     filesystem::CliFilesystem,
-    window::{WindowCallbackEvent, WindowHandle, WindowImpl},
+    gamepad::Gamepad,
+    headless::{HeadlessOptions, HeadlessScreen, InputScript},
+    keymap::{KeyConfig, KeyRepeat, KeyRepeater, SharedKeyRepeat},
+    network::{CliHttp, CliNetwork},
+    presentation::{Bezel, Filter, ScaleMode},
+    window::{WindowCallbackEvent, WindowImpl, WindowOptions},
 };
 
-struct WieCliPlatform {
-    audio_thread_tx: Sender<(u8, u32, Vec<i16>)>,
+/// Screen the cli platform renders to: a winit window or the headless frame dumper.
+trait CliScreen: Screen {
+    fn quit(&self);
+}
+
+// 2010-01-01T00:00:00Z, so virtual-clock runs see a fixed, plausible date
+const VIRTUAL_CLOCK_EPOCH: u64 = 1_262_304_000_000;
+const SYNTH_SAMPLE_RATE: u32 = 44100;
+
+/// Where the cli platform plays sound.
+enum AudioOutput {
+    /// The default audio device, and a midi output port if there is one.
+    Device,
+    /// No device is opened. Sound still reaches the audio capture if there is one.
+    Off,
+}
+
+struct WieCliPlatform<S> {
+    // None when no audio device is opened
+    synth: Option<SynthAudioSink>,
+    capture: Option<CaptureAudioSink>,
     database_repository: DatabaseRepository,
     filesystem: CliFilesystem,
-    window: WindowHandle,
+    network: CliNetwork,
+    http: CliHttp,
+    screen: S,
+    clock: Option<VirtualClock>,
+    font_pack: Option<FontPack>,
+    key_repeat: SharedKeyRepeat,
 }
 
-impl WieCliPlatform {
-    fn new(window: WindowHandle) -> Self {
-        let (tx, rx) = channel();
-        thread::spawn(|| Self::audio_thread(rx));
+impl<S: 'static> WieCliPlatform<S> {
+    fn new(
+        screen: S,
+        clock: Option<VirtualClock>,
+        audio_output: AudioOutput,
+        capture_audio: bool,
+        font_pack: Option<FontPack>,
+        key_repeat: KeyRepeat,
+    ) -> Self {
+        let synth = match audio_output {
+            AudioOutput::Device => {
+                let synth = SynthAudioSink::new(SYNTH_SAMPLE_RATE);
+                let synth_clone = synth.clone();
+                thread::spawn(|| Self::audio_thread(synth_clone));
+
+                Some(synth)
+            }
+            AudioOutput::Off => None,
+        };
+
+        let capture = capture_audio.then(|| {
+            let clock = clock.clone();
+
+            CaptureAudioSink::new(Box::new(move || now(clock.as_ref())))
+        });
 
         Self {
-            audio_thread_tx: tx,
+            synth,
+            capture,
             database_repository: DatabaseRepository::new(),
             filesystem: CliFilesystem::new(),
-            window,
+            network: CliNetwork::new(),
+            http: CliHttp::new(),
+            screen,
+            clock,
+            font_pack,
+            key_repeat: Arc::new(Mutex::new(key_repeat)),
         }
     }
 
-    fn audio_thread(rx: Receiver<(u8, u32, Vec<i16>)>) {
-        let default_output = DeviceSinkBuilder::open_default_sink();
-        if default_output.is_err() {
+    fn audio_thread(synth: SynthAudioSink) {
+        let Ok(output_sink) = DeviceSinkBuilder::open_default_sink() else {
             // do nothing if we can't open output
-            loop {
-                rx.recv().unwrap();
-            }
-        }
+            return;
+        };
 
-        let output_sink = default_output.unwrap();
-        let player = Player::connect_new(output_sink.mixer());
+        output_sink.mixer().add(SynthSource::new(synth));
 
+        // the device sink stops playback when dropped, and it can't leave this thread
         loop {
-            let result = rx.recv();
-            if result.is_err() {
-                break;
-            }
-            let (channel, sampling_rate, wave_data) = result.unwrap();
-
-            let Some(channel_count) = NonZero::new(channel.into()) else {
-                continue;
-            };
-            let Some(sample_rate) = NonZero::new(sampling_rate) else {
-                continue;
-            };
-
-            let buffer = SamplesBuffer::new(
-                channel_count,
-                sample_rate,
-                SampleTypeConverter::new(wave_data.into_iter()).collect::<Vec<_>>(),
-            );
-
-            // TODO we should be able to play multiple audio at once
-            player.append(buffer);
+            thread::park();
         }
     }
 }
 
-impl Platform for WieCliPlatform {
+impl<S> Platform for WieCliPlatform<S>
+where
+    S: CliScreen + 'static,
+{
     fn screen(&self) -> &dyn Screen {
-        &self.window
+        &self.screen
     }
 
     fn now(&self) -> Instant {
-        let now = SystemTime::now();
-        let since_the_epoch = now.duration_since(UNIX_EPOCH).unwrap();
-
-        Instant::from_epoch_millis(since_the_epoch.as_millis() as _)
+        now(self.clock.as_ref())
     }
 
     fn database_repository(&self) -> &dyn wie_backend::DatabaseRepository {
@@ -117,7 +155,22 @@ impl Platform for WieCliPlatform {
         &self.filesystem
     }
 
+    fn network(&self) -> &dyn Network {
+        &self.network
+    }
+
+    fn http(&self) -> &dyn HttpTransport {
+        &self.http
+    }
+
     fn audio_sink(&self) -> Box<dyn wie_backend::AudioSink> {
+        let Some(synth) = &self.synth else {
+            return match &self.capture {
+                Some(capture) => Box::new(capture.clone()),
+                None => Box::new(NullAudioSink),
+            };
+        };
+
         let midi_out = (|| {
             let midi_out = MidiOutput::new("wie_cli")?;
             let midi_ports = midi_out.ports();
@@ -127,7 +180,16 @@ impl Platform for WieCliPlatform {
         })()
         .ok();
 
-        Box::new(AudioSink::new(midi_out, self.audio_thread_tx.clone()))
+        // without a midi device, render everything with the built in synthesizer instead
+        let sink: Box<dyn wie_backend::AudioSink> = match midi_out {
+            Some(midi_out) => Box::new(AudioSink::new(midi_out, synth.clone())),
+            None => Box::new(synth.clone()),
+        };
+
+        match &self.capture {
+            Some(capture) => Box::new(capture.with_output(sink)),
+            None => sink,
+        }
     }
 
     fn write_stdout(&self, buf: &[u8]) {
@@ -143,12 +205,38 @@ impl Platform for WieCliPlatform {
     }
 
     fn exit(&self) {
-        self.window.send_quit_event();
+        self.screen.quit();
     }
 
     fn vibrate(&self, duration_ms: u64, intensity: u8) {
         tracing::info!("vibrate({duration_ms}ms, {intensity}%) - not supported on this platform");
     }
+
+    fn virtual_clock(&self) -> Option<&VirtualClock> {
+        self.clock.as_ref()
+    }
+
+    fn font_pack(&self) -> Option<FontPack> {
+        self.font_pack.clone()
+    }
+
+    fn set_key_repeat(&self, delay_ms: u64, interval_ms: u64) {
+        *self.key_repeat.lock().unwrap() = KeyRepeat {
+            delay: Duration::from_millis(delay_ms),
+            interval: Duration::from_millis(interval_ms),
+        };
+    }
+}
+
+fn now(clock: Option<&VirtualClock>) -> Instant {
+    if let Some(clock) = clock {
+        return clock.now();
+    }
+
+    let now = SystemTime::now();
+    let since_the_epoch = now.duration_since(UNIX_EPOCH).unwrap();
+
+    Instant::from_epoch_millis(since_the_epoch.as_millis() as _)
 }
 
 #[derive(Parser)]
@@ -156,10 +244,105 @@ struct Args {
     filename: String,
     #[arg(long, default_value_t = false)]
     debug: bool,
+    /// Run the app's code from cached decoded blocks instead of stepping each instruction.
+    #[arg(long, default_value_t = false, conflicts_with = "debug")]
+    block_cache: bool,
     /// Write a flamegraph-folded sampling profile to this path (one line per
-    /// flushed batch; `flamegraph.pl` aggregates duplicates).
+    /// flushed batch; `flamegraph.pl` aggregates duplicates). Frames are named
+    /// after the app's methods and functions where the loader knows them.
     #[arg(long)]
     profile_out: Option<PathBuf>,
+    /// Write every call the app makes to emulated functions (WIPI C, java bridge, stdlib, ...) to this path as JSON lines.
+    #[arg(long)]
+    trace_svc: Option<PathBuf>,
+    /// Run without a window, e.g. for regression tests in CI.
+    #[arg(long, default_value_t = false)]
+    headless: bool,
+    /// Headless input script (`<tick|Nms> <down|up|repeat> <key>` or `<tick|Nms> <touch|drag|release> <x>,<y>` per line).
+    #[arg(long, requires = "headless")]
+    input_script: Option<PathBuf>,
+    /// Stop the headless run after this many ticks instead of waiting for the app to exit.
+    #[arg(long, requires = "headless")]
+    ticks: Option<u64>,
+    /// Write painted frames to this directory as PNG files.
+    #[arg(long, requires = "headless")]
+    frame_out: Option<PathBuf>,
+    /// Comma-separated paint indices to write; all frames if omitted.
+    #[arg(long, value_delimiter = ',', requires = "frame_out")]
+    frames: Option<Vec<u64>>,
+    /// Run on a virtual clock advanced by this many milliseconds per tick, for reproducible runs.
+    #[arg(long, requires = "headless")]
+    virtual_clock: Option<u64>,
+    /// Record host input to this replay file.
+    #[arg(long)]
+    record: Option<PathBuf>,
+    /// Feed input from a replay file recorded with `--record`; host input is ignored.
+    #[arg(long, conflicts_with_all = ["record", "input_script"])]
+    replay: Option<PathBuf>,
+    /// Record the session's sound to `<PATH>.wav` (wave output) and `<PATH>.mid` (midi output).
+    #[arg(long)]
+    capture_audio: Option<PathBuf>,
+    /// Screen size of the emulated handset, as `<WIDTH>x<HEIGHT>`. This and the other handset options take precedence over the
+    /// title's settings in `data/game_configs.toml`.
+    #[arg(long, value_parser = parse_screen_size)]
+    screen_size: Option<(u32, u32)>,
+    /// Model name the emulated handset reports.
+    #[arg(long)]
+    model: Option<String>,
+    /// Carrier name the emulated handset reports, instead of the app platform's own carrier.
+    #[arg(long)]
+    carrier: Option<String>,
+    /// Emulate a touch screen handset, sending mouse input to the app as pointer events.
+    #[arg(long, default_value_t = false)]
+    touch_screen: bool,
+    /// Draw text with this TrueType or BDF font instead of the built-in one. Repeat to add fallbacks for missing glyphs.
+    #[arg(long = "font")]
+    fonts: Vec<PathBuf>,
+    /// Key bindings and key repeat timing file, `keymap.toml` in the config directory if omitted. Apps may still change the repeat timing.
+    #[arg(long)]
+    keymap: Option<PathBuf>,
+    /// How the screen is scaled up to the window size. The screen is letterboxed to keep its aspect ratio.
+    #[arg(long, value_enum, default_value_t = ScaleMode::Smooth)]
+    scale_mode: ScaleMode,
+    /// Post-process filter drawn over the screen, from 2x scale.
+    #[arg(long, value_enum, default_value_t = Filter::None)]
+    filter: Filter,
+    /// Draw this handset picture around the screen. Its fully transparent area must match the screen size.
+    #[arg(long)]
+    bezel: Option<PathBuf>,
+}
+
+/// `--record` / `--replay` settings shared by the window and headless modes.
+struct ReplayOptions {
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+}
+
+impl ReplayOptions {
+    fn wrap(&self, emulator: Box<dyn Emulator>) -> anyhow::Result<(Box<dyn Emulator>, Option<SharedInputLog>)> {
+        if let Some(path) = &self.replay {
+            let log = InputLog::from_bytes(&fs::read(path)?)?;
+
+            return Ok((Box::new(InputPlayer::new(emulator, log)), None));
+        }
+
+        if self.record.is_some() {
+            let recorder = InputRecorder::new(emulator);
+            let log = recorder.log();
+
+            return Ok((Box::new(recorder), Some(log)));
+        }
+
+        Ok((emulator, None))
+    }
+
+    fn save(&self, log: Option<SharedInputLog>) -> anyhow::Result<()> {
+        if let (Some(path), Some(log)) = (&self.record, log) {
+            fs::write(path, log.lock().to_bytes())?;
+        }
+
+        Ok(())
+    }
 }
 
 fn main() -> anyhow::Result<()> {
@@ -170,40 +353,179 @@ fn main() -> anyhow::Result<()> {
 
     let args = Args::parse();
 
-    let profile = args.profile_out.as_ref().map(|path| profile_callback(path)).transpose()?;
+    // also names the emulated java methods in svc traces and the svc stubs in the gdb monitor
+    let symbols = (args.profile_out.is_some() || args.trace_svc.is_some() || args.debug).then(SymbolResolver::new);
+    let profile = args
+        .profile_out
+        .as_ref()
+        .zip(symbols.clone())
+        .map(|(path, symbols)| profile_callback(path, symbols))
+        .transpose()?;
+    let svc_trace = args.trace_svc.as_ref().map(|path| svc_trace_callback(path)).transpose()?;
     let options = Options {
         enable_gdbserver: args.debug,
+        enable_block_cache: args.block_cache,
         profile,
+        symbols,
+        svc_trace,
+    };
+
+    let replay_options = ReplayOptions {
+        record: args.record,
+        replay: args.replay,
     };
 
-    start(&args.filename, options)
+    let font_pack = (!args.fonts.is_empty()).then(|| load_font_pack(&args.fonts)).transpose()?;
+
+    let mut handset = HandsetProfile::default();
+    if let Some(game_config) = find_game_config(&args.filename)? {
+        game_config.handset.apply(&mut handset);
+    }
+    if let Some((width, height)) = args.screen_size {
+        handset.screen_width = width;
+        handset.screen_height = height;
+    }
+    if let Some(model) = args.model {
+        handset.model = model;
+    }
+    if let Some(carrier) = args.carrier {
+        handset.carrier = Some(carrier);
+    }
+    if args.touch_screen {
+        handset.touch_screen = true;
+    }
+
+    if args.headless {
+        let headless_options = HeadlessOptions {
+            script: args.input_script.as_deref().map(InputScript::load).transpose()?.unwrap_or_default(),
+            ticks: args.ticks,
+            frame_out: args.frame_out,
+            frames: args.frames.map(|x| x.into_iter().collect::<BTreeSet<_>>()),
+            tick_millis: args.virtual_clock,
+        };
+
+        start_headless(
+            &args.filename,
+            handset,
+            options,
+            replay_options,
+            args.capture_audio,
+            font_pack,
+            headless_options,
+        )
+    } else {
+        let key_config = load_key_config(args.keymap.as_deref())?;
+        let window_options = WindowOptions {
+            scale_mode: args.scale_mode,
+            filter: args.filter,
+            bezel: args
+                .bezel
+                .as_deref()
+                .map(|x| Bezel::load(x, handset.screen_width, handset.screen_height))
+                .transpose()?,
+        };
+
+        start(
+            &args.filename,
+            handset,
+            options,
+            replay_options,
+            args.capture_audio,
+            font_pack,
+            key_config,
+            window_options,
+        )
+    }
+}
+
+fn parse_screen_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value.split_once('x').ok_or("expected <WIDTH>x<HEIGHT>")?;
+
+    Ok((width.parse().map_err(|x| format!("{x}"))?, height.parse().map_err(|x| format!("{x}"))?))
+}
+
+fn load_font_pack(paths: &[PathBuf]) -> anyhow::Result<FontPack> {
+    let mut font_pack = FontPack::new();
+    for path in paths {
+        let data = fs::read(path)?;
+
+        if path.extension().is_some_and(|x| x.eq_ignore_ascii_case("bdf")) {
+            font_pack.add_bdf(&data)?;
+        } else {
+            font_pack.add_truetype(data)?;
+        }
+    }
+
+    Ok(font_pack)
+}
+
+fn load_key_config(path: Option<&Path>) -> anyhow::Result<KeyConfig> {
+    if let Some(path) = path {
+        return KeyConfig::load(path);
+    }
+
+    let base_dir = ProjectDirs::from("net", "dlunch", "wie").unwrap();
+    let path = base_dir.config_dir().join("keymap.toml");
+    if path.exists() {
+        return KeyConfig::load(&path);
+    }
+
+    Ok(KeyConfig::default())
+}
+
+fn save_audio_capture(path: Option<&Path>, capture: Option<CaptureAudioSink>) -> anyhow::Result<()> {
+    if let (Some(path), Some(capture)) = (path, capture) {
+        fs::write(path.with_extension("wav"), capture.to_wav(SYNTH_SAMPLE_RATE))?;
+        fs::write(path.with_extension("mid"), capture.to_midi())?;
+    }
+
+    Ok(())
 }
 
-fn profile_callback(path: &PathBuf) -> anyhow::Result<wie_backend::ProfileCallback> {
+fn profile_callback(path: &PathBuf, symbols: SymbolResolver) -> anyhow::Result<wie_backend::ProfileCallback> {
     let writer = Mutex::new(LineWriter::new(File::create(path)?));
     Ok(Box::new(move |batch: Vec<ProfileSample>| {
         let mut writer = writer.lock().unwrap();
         for sample in batch {
-            let folded: Vec<String> = sample.stack.iter().rev().map(|pc| format!("0x{pc:x}")).collect();
+            // `;` separates frames in the folded format, and java descriptors contain it
+            let folded: Vec<String> = sample.stack.iter().rev().map(|&pc| symbols.format(pc).replace(';', ",")).collect();
             let _ = writeln!(writer, "{} {}", folded.join(";"), sample.count);
         }
     }))
 }
 
-pub fn start(filename: &str, options: Options) -> anyhow::Result<()> {
-    let window = WindowImpl::new(240, 320).unwrap(); // TODO hardcoded size
-    let platform = Box::new(WieCliPlatform::new(window.handle()));
+fn svc_trace_callback(path: &PathBuf) -> anyhow::Result<wie_backend::SvcTraceCallback> {
+    let mut writer = LineWriter::new(File::create(path)?);
+    Ok(Box::new(move |trace: SvcTrace| {
+        let _ = writeln!(writer, "{}", trace.to_json());
+    }))
+}
+
+fn find_game_config(filename: &str) -> anyhow::Result<Option<GameConfig>> {
+    let buf = fs::read(filename)?;
+
+    let game_config = if filename.ends_with("zip") {
+        GameConfig::find(extract_zip(&buf)?.values().map(|x| x.as_slice()))?
+    } else if filename.ends_with("jad") {
+        GameConfig::find([fs::read(filename.replace(".jad", ".jar"))?.as_slice()])?
+    } else {
+        GameConfig::find([buf.as_slice()])?
+    };
+
+    Ok(game_config)
+}
 
+fn load_emulator(filename: &str, platform: Box<dyn Platform>, handset: HandsetProfile, options: Options) -> anyhow::Result<Box<dyn Emulator>> {
     let buf = fs::read(filename)?;
-    let mut emulator: Box<dyn Emulator> = if filename.ends_with("zip") {
+    let emulator: Box<dyn Emulator> = if filename.ends_with("zip") {
         let files = extract_zip(&buf).unwrap();
 
         if KtfEmulator::loadable_archive(&files) {
-            Box::new(KtfEmulator::from_archive(platform, files, options)?)
+            Box::new(KtfEmulator::from_archive(platform, handset, files, options)?)
         } else if LgtEmulator::loadable_archive(&files) {
-            Box::new(LgtEmulator::from_archive(platform, files, options)?)
+            Box::new(LgtEmulator::from_archive(platform, handset, files, options)?)
         } else if SktEmulator::loadable_archive(&files) {
-            Box::new(SktEmulator::from_archive(platform, files)?)
+            Box::new(SktEmulator::from_archive(platform, handset, files)?)
         } else {
             anyhow::bail!("Unknown archive format");
         }
@@ -213,7 +535,7 @@ pub fn start(filename: &str, options: Options) -> anyhow::Result<()> {
 
         let jar_filename = jar_filename[jar_filename.rfind('/').unwrap_or(0) + 1..].to_owned();
 
-        Box::new(J2MEEmulator::from_jad_jar(platform, buf, jar_filename, jar)?)
+        Box::new(J2MEEmulator::from_jad_jar(platform, handset, buf, jar_filename, jar)?)
     } else if filename.ends_with("jar") {
         let filename_without_path = filename[filename.rfind('/').unwrap_or(0) + 1..].to_owned();
         let filename_without_ext = filename_without_path.trim_end_matches(".jar");
@@ -221,6 +543,7 @@ pub fn start(filename: &str, options: Options) -> anyhow::Result<()> {
         if KtfEmulator::loadable_jar(&buf) {
             Box::new(KtfEmulator::from_jar(
                 platform,
+                handset,
                 &filename_without_path,
                 buf,
                 filename_without_ext,
@@ -231,6 +554,7 @@ pub fn start(filename: &str, options: Options) -> anyhow::Result<()> {
         } else if LgtEmulator::loadable_jar(&buf) {
             Box::new(LgtEmulator::from_jar(
                 platform,
+                handset,
                 &filename_without_path,
                 buf,
                 filename_without_ext,
@@ -239,85 +563,134 @@ pub fn start(filename: &str, options: Options) -> anyhow::Result<()> {
                 options,
             )?)
         } else if SktEmulator::loadable_jar(&buf) {
-            Box::new(SktEmulator::from_jar(platform, &filename_without_path, buf, filename_without_ext, None)?)
+            Box::new(SktEmulator::from_jar(
+                platform,
+                handset,
+                &filename_without_path,
+                buf,
+                filename_without_ext,
+                None,
+            )?)
         } else {
-            Box::new(J2MEEmulator::from_jar(platform, &filename_without_path, buf)?)
+            Box::new(J2MEEmulator::from_jar(platform, handset, &filename_without_path, buf)?)
         }
     } else {
         anyhow::bail!("Unknown file format");
     };
 
-    let mut key_events = HashMap::new();
-    window.run(move |event| {
+    Ok(emulator)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn start(
+    filename: &str,
+    handset: HandsetProfile,
+    options: Options,
+    replay_options: ReplayOptions,
+    capture_audio: Option<PathBuf>,
+    font_pack: Option<FontPack>,
+    key_config: KeyConfig,
+    window_options: WindowOptions,
+) -> anyhow::Result<()> {
+    let window = WindowImpl::new(handset.screen_width, handset.screen_height, window_options).unwrap();
+    let keys = handset.keys.clone();
+    let touch_screen = handset.touch_screen;
+    let platform = Box::new(WieCliPlatform::new(
+        window.handle(),
+        None,
+        AudioOutput::Device,
+        capture_audio.is_some(),
+        font_pack,
+        key_config.repeat,
+    ));
+    let capture = platform.capture.clone();
+    let mut key_repeater = KeyRepeater::new(platform.key_repeat.clone());
+    let mut gamepad = Gamepad::new();
+
+    let (mut emulator, log) = replay_options.wrap(load_emulator(filename, platform, handset, options)?)?;
+
+    let result = window.run(move |event| {
         match event {
             WindowCallbackEvent::Update => {
-                let now = SystemTime::now();
-
-                for entry in key_events.iter_mut() {
-                    let (keycode, time) = entry;
-
-                    // TODO const
-                    if now.duration_since(*time).unwrap().as_millis() > 100 {
-                        emulator.handle_event(Event::Keyrepeat(*keycode));
-                        *time = now;
+                if let Some(gamepad) = &mut gamepad {
+                    for (button, pressed) in gamepad.poll() {
+                        let keycode = key_config.convert_button(button);
+                        handle_key(&mut *emulator, &mut key_repeater, &keys, keycode, pressed);
                     }
                 }
 
+                for keycode in key_repeater.poll(time::Instant::now()) {
+                    emulator.handle_event(Event::Keyrepeat(keycode));
+                }
+
                 emulator.tick()?
             }
             WindowCallbackEvent::Redraw => emulator.handle_event(Event::Redraw),
-            WindowCallbackEvent::Keydown(x) => {
-                if let Some(keycode) = convert_key(x) {
-                    let entry = key_events.entry(keycode);
-                    if let Entry::Vacant(entry) = entry {
-                        emulator.handle_event(Event::Keydown(keycode));
-
-                        let now = SystemTime::now();
-
-                        entry.insert(now);
-                    }
-                }
-            }
-            WindowCallbackEvent::Keyup(x) => {
-                if let Some(keycode) = convert_key(x)
-                    && key_events.contains_key(&keycode)
-                {
-                    key_events.remove(&keycode);
-                    emulator.handle_event(Event::Keyup(keycode));
-                }
-            }
+            WindowCallbackEvent::Keydown(x) => handle_key(&mut *emulator, &mut key_repeater, &keys, key_config.convert(x), true),
+            WindowCallbackEvent::Keyup(x) => handle_key(&mut *emulator, &mut key_repeater, &keys, key_config.convert(x), false),
+            WindowCallbackEvent::Pointerdown { x, y } if touch_screen => emulator.handle_event(Event::Pointerdown { x, y }),
+            WindowCallbackEvent::Pointerdrag { x, y } if touch_screen => emulator.handle_event(Event::Pointerdrag { x, y }),
+            WindowCallbackEvent::Pointerup { x, y } if touch_screen => emulator.handle_event(Event::Pointerup { x, y }),
+            _ => {}
         }
 
         Ok(())
-    })
+    });
+    replay_options.save(log)?;
+    save_audio_capture(capture_audio.as_deref(), capture)?;
+
+    result
 }
 
-fn convert_key(key: PhysicalKey) -> Option<KeyCode> {
-    match key {
-        PhysicalKey::Code(WinitKeyCode::Digit1) => Some(KeyCode::NUM1),
-        PhysicalKey::Code(WinitKeyCode::Digit2) => Some(KeyCode::NUM2),
-        PhysicalKey::Code(WinitKeyCode::Digit3) => Some(KeyCode::NUM3),
-        PhysicalKey::Code(WinitKeyCode::KeyQ) => Some(KeyCode::NUM4),
-        PhysicalKey::Code(WinitKeyCode::KeyW) => Some(KeyCode::NUM5),
-        PhysicalKey::Code(WinitKeyCode::KeyE) => Some(KeyCode::NUM6),
-        PhysicalKey::Code(WinitKeyCode::KeyA) => Some(KeyCode::NUM7),
-        PhysicalKey::Code(WinitKeyCode::KeyS) => Some(KeyCode::NUM8),
-        PhysicalKey::Code(WinitKeyCode::KeyD) => Some(KeyCode::NUM9),
-        PhysicalKey::Code(WinitKeyCode::KeyZ) => Some(KeyCode::STAR),
-        PhysicalKey::Code(WinitKeyCode::KeyX) => Some(KeyCode::NUM0),
-        PhysicalKey::Code(WinitKeyCode::KeyC) => Some(KeyCode::HASH),
-        PhysicalKey::Code(WinitKeyCode::Space) => Some(KeyCode::OK),
-        PhysicalKey::Code(WinitKeyCode::ArrowUp) => Some(KeyCode::UP),
-        PhysicalKey::Code(WinitKeyCode::ArrowDown) => Some(KeyCode::DOWN),
-        PhysicalKey::Code(WinitKeyCode::ArrowLeft) => Some(KeyCode::LEFT),
-        PhysicalKey::Code(WinitKeyCode::ArrowRight) => Some(KeyCode::RIGHT),
-        PhysicalKey::Code(WinitKeyCode::Backspace) => Some(KeyCode::CLEAR),
-        PhysicalKey::Code(WinitKeyCode::ShiftLeft) => Some(KeyCode::LEFT_SOFT_KEY),
-        PhysicalKey::Code(WinitKeyCode::ShiftRight) => Some(KeyCode::RIGHT_SOFT_KEY),
-        PhysicalKey::Code(WinitKeyCode::Backquote) => Some(KeyCode::VOLUME_UP),
-        PhysicalKey::Code(WinitKeyCode::Tab) => Some(KeyCode::VOLUME_DOWN),
-        PhysicalKey::Code(WinitKeyCode::F1) => Some(KeyCode::CALL),
-        PhysicalKey::Code(WinitKeyCode::F2) => Some(KeyCode::HANGUP),
-        _ => None,
+/// Sends a host key or gamepad button change to the app, ignoring keys the handset doesn't have and repeated presses.
+fn handle_key(emulator: &mut dyn Emulator, key_repeater: &mut KeyRepeater, keys: &[KeyCode], keycode: Option<KeyCode>, pressed: bool) {
+    let Some(keycode) = keycode else {
+        return;
+    };
+
+    if pressed {
+        if keys.contains(&keycode) && key_repeater.press(keycode, time::Instant::now()) {
+            emulator.handle_event(Event::Keydown(keycode));
+        }
+    } else if key_repeater.release(keycode) {
+        emulator.handle_event(Event::Keyup(keycode));
     }
 }
+
+fn start_headless(
+    filename: &str,
+    handset: HandsetProfile,
+    options: Options,
+    replay_options: ReplayOptions,
+    capture_audio: Option<PathBuf>,
+    font_pack: Option<FontPack>,
+    mut headless_options: HeadlessOptions,
+) -> anyhow::Result<()> {
+    let screen = HeadlessScreen::new(
+        handset.screen_width,
+        handset.screen_height,
+        headless_options.frame_out.take(),
+        headless_options.frames.take(),
+    )?;
+    let clock = headless_options
+        .tick_millis
+        .map(|_| VirtualClock::new(Instant::from_epoch_millis(VIRTUAL_CLOCK_EPOCH)));
+    // ci machines running headless may have no audio hardware, so sound only goes to the capture if there is one
+    let platform = Box::new(WieCliPlatform::new(
+        screen.clone(),
+        clock.clone(),
+        AudioOutput::Off,
+        capture_audio.is_some(),
+        font_pack,
+        KeyRepeat::default(),
+    ));
+    let capture = platform.capture.clone();
+
+    let (emulator, log) = replay_options.wrap(load_emulator(filename, platform, handset, options)?)?;
+
+    let result = headless::run(emulator, screen, clock, headless_options);
+    replay_options.save(log)?;
+    save_audio_capture(capture_audio.as_deref(), capture)?;
+
+    result
+}
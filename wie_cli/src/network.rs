@@ -0,0 +1,373 @@
+use core::{future, str, task::Poll};
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind, Read, Write},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::{
+        Mutex,
+        mpsc::{self, Receiver, TryRecvError},
+    },
+    thread,
+    time::Duration,
+};
+
+use wie_backend::{HttpRequest, HttpResponse, HttpTransport, Network, NetworkError, SocketId, SocketType};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+enum CliSocket {
+    Unconnected(SocketType),
+    Connecting(SocketType, Receiver<io::Result<CliSocket>>),
+    Tcp(TcpStream),
+    Udp(UdpSocket),
+}
+
+/// `Network` backed by `std::net`. Connects run on a worker thread and connected sockets are switched to non-blocking
+/// mode, so the emulator thread never waits on the network.
+#[derive(Default)]
+pub struct CliNetwork {
+    sockets: Mutex<HashMap<SocketId, CliSocket>>,
+    last_socket_id: Mutex<SocketId>,
+}
+
+impl CliNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn connect_socket(socket_type: SocketType, addr: SocketAddr) -> io::Result<CliSocket> {
+        Ok(match socket_type {
+            SocketType::Stream => {
+                let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+                stream.set_nonblocking(true)?;
+                stream.set_nodelay(true)?;
+
+                CliSocket::Tcp(stream)
+            }
+            SocketType::Datagram => {
+                let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+                socket.connect(addr)?;
+                socket.set_nonblocking(true)?;
+
+                CliSocket::Udp(socket)
+            }
+        })
+    }
+}
+
+fn convert_error(error: io::Error) -> NetworkError {
+    match error.kind() {
+        ErrorKind::WouldBlock => NetworkError::WouldBlock,
+        ErrorKind::NotConnected => NetworkError::NotConnected,
+        ErrorKind::ConnectionRefused => NetworkError::ConnectionRefused,
+        _ => {
+            tracing::warn!("Network error: {error}");
+
+            NetworkError::Other
+        }
+    }
+}
+
+impl Network for CliNetwork {
+    fn resolve(&self, host: &str) -> Result<[u8; 4], NetworkError> {
+        (host, 0)
+            .to_socket_addrs()
+            .map_err(|_| NetworkError::HostNotFound)?
+            .find_map(|x| match x {
+                SocketAddr::V4(x) => Some(x.ip().octets()),
+                SocketAddr::V6(_) => None,
+            })
+            .ok_or(NetworkError::HostNotFound)
+    }
+
+    fn socket(&self, socket_type: SocketType) -> Result<SocketId, NetworkError> {
+        let mut last_socket_id = self.last_socket_id.lock().unwrap();
+        *last_socket_id += 1;
+
+        self.sockets.lock().unwrap().insert(*last_socket_id, CliSocket::Unconnected(socket_type));
+
+        Ok(*last_socket_id)
+    }
+
+    fn connect(&self, socket: SocketId, addr: [u8; 4], port: u16) -> Result<(), NetworkError> {
+        let mut sockets = self.sockets.lock().unwrap();
+        let entry = sockets.get_mut(&socket).ok_or(NetworkError::InvalidSocket)?;
+
+        match entry {
+            CliSocket::Unconnected(socket_type) => {
+                let socket_type = *socket_type;
+                let addr = SocketAddr::V4(SocketAddrV4::new(addr.into(), port));
+
+                let (sender, receiver) = mpsc::channel();
+                thread::spawn(move || {
+                    let _ = sender.send(Self::connect_socket(socket_type, addr));
+                });
+                *entry = CliSocket::Connecting(socket_type, receiver);
+
+                Err(NetworkError::WouldBlock)
+            }
+            CliSocket::Connecting(socket_type, receiver) => match receiver.try_recv() {
+                Ok(Ok(x)) => {
+                    *entry = x;
+
+                    Ok(())
+                }
+                Ok(Err(x)) => {
+                    *entry = CliSocket::Unconnected(*socket_type);
+
+                    Err(convert_error(x))
+                }
+                Err(TryRecvError::Empty) => Err(NetworkError::WouldBlock),
+                Err(TryRecvError::Disconnected) => Err(NetworkError::Other),
+            },
+            _ => Err(NetworkError::Other), // already connected
+        }
+    }
+
+    fn send(&self, socket: SocketId, data: &[u8]) -> Result<usize, NetworkError> {
+        match self.sockets.lock().unwrap().get_mut(&socket) {
+            Some(CliSocket::Tcp(x)) => x.write(data).map_err(convert_error),
+            Some(CliSocket::Udp(x)) => x.send(data).map_err(convert_error),
+            Some(CliSocket::Connecting(..)) => Err(NetworkError::WouldBlock),
+            Some(CliSocket::Unconnected(_)) => Err(NetworkError::NotConnected),
+            None => Err(NetworkError::InvalidSocket),
+        }
+    }
+
+    fn recv(&self, socket: SocketId, buf: &mut [u8]) -> Result<usize, NetworkError> {
+        match self.sockets.lock().unwrap().get_mut(&socket) {
+            Some(CliSocket::Tcp(x)) => x.read(buf).map_err(convert_error),
+            Some(CliSocket::Udp(x)) => x.recv(buf).map_err(convert_error),
+            Some(CliSocket::Connecting(..)) => Err(NetworkError::WouldBlock),
+            Some(CliSocket::Unconnected(_)) => Err(NetworkError::NotConnected),
+            None => Err(NetworkError::InvalidSocket),
+        }
+    }
+
+    fn readable(&self, socket: SocketId) -> bool {
+        let mut buf = [0; 1];
+        let result = match self.sockets.lock().unwrap().get(&socket) {
+            Some(CliSocket::Tcp(x)) => x.peek(&mut buf),
+            Some(CliSocket::Udp(x)) => x.peek(&mut buf),
+            _ => return false,
+        };
+
+        // eof and errors count as readable so the app gets to see them from recv
+        !matches!(result, Err(x) if x.kind() == ErrorKind::WouldBlock)
+    }
+
+    fn close(&self, socket: SocketId) {
+        self.sockets.lock().unwrap().remove(&socket);
+    }
+}
+
+/// `HttpTransport` speaking plain HTTP/1.0 over `std::net`. Each request runs on a worker thread that the calling
+/// task polls, so other tasks keep running meanwhile; `https` urls are not supported.
+#[derive(Default)]
+pub struct CliHttp;
+
+impl CliHttp {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn send_request(request: &HttpRequest) -> Result<Vec<u8>, NetworkError> {
+        let Some(rest) = request.url.strip_prefix("http://") else {
+            tracing::warn!("Unsupported url {}", request.url);
+
+            return Err(NetworkError::Other);
+        };
+        let (authority, path) = rest.find('/').map_or((rest, "/"), |x| (&rest[..x], &rest[x..]));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| NetworkError::HostNotFound)?),
+            None => (authority, 80),
+        };
+
+        let addr = (host, port)
+            .to_socket_addrs()
+            .map_err(|_| NetworkError::HostNotFound)?
+            .next()
+            .ok_or(NetworkError::HostNotFound)?;
+        let mut stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).map_err(convert_error)?;
+        stream.set_read_timeout(Some(HTTP_TIMEOUT)).map_err(convert_error)?;
+        stream.set_write_timeout(Some(HTTP_TIMEOUT)).map_err(convert_error)?;
+
+        let mut head = format!("{} {path} HTTP/1.0\r\nHost: {authority}\r\n", request.method);
+        for (name, value) in &request.headers {
+            if !["host", "content-length", "connection"].iter().any(|x| name.eq_ignore_ascii_case(x)) {
+                head += &format!("{name}: {value}\r\n");
+            }
+        }
+        head += &format!("Content-Length: {}\r\nConnection: close\r\n\r\n", request.body.len());
+
+        stream.write_all(head.as_bytes()).map_err(convert_error)?;
+        stream.write_all(&request.body).map_err(convert_error)?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(convert_error)?;
+
+        Ok(response)
+    }
+}
+
+fn parse_response(data: &[u8]) -> Option<HttpResponse> {
+    let header_end = data.windows(4).position(|x| x == b"\r\n\r\n")?;
+    let head = str::from_utf8(&data[..header_end]).ok()?;
+    let mut lines = head.split("\r\n");
+
+    // HTTP/1.1 200 OK
+    let mut status_line = lines.next()?.splitn(3, ' ');
+    if !status_line.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    let status = status_line.next()?.parse().ok()?;
+    let message = status_line.next().unwrap_or_default().into();
+
+    let headers = lines
+        .filter_map(|x| x.split_once(':'))
+        .map(|(name, value)| (name.trim().into(), value.trim().into()))
+        .collect::<Vec<(String, String)>>();
+
+    let mut response = HttpResponse {
+        status,
+        message,
+        headers,
+        body: data[header_end + 4..].to_vec(),
+    };
+    if response.header("Transfer-Encoding").is_some_and(|x| x.eq_ignore_ascii_case("chunked")) {
+        response.body = decode_chunked(&response.body)?;
+    }
+
+    Some(response)
+}
+
+fn decode_chunked(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    loop {
+        let line_end = data.windows(2).position(|x| x == b"\r\n")?;
+        let size = str::from_utf8(&data[..line_end]).ok()?.split(';').next()?;
+        let size = usize::from_str_radix(size.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(result);
+        }
+
+        let chunk = data.get(line_end + 2..line_end + 2 + size)?;
+        result.extend_from_slice(chunk);
+        data = data.get(line_end + 2 + size + 2..)?;
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpTransport for CliHttp {
+    async fn request(&self, request: HttpRequest) -> Result<HttpResponse, NetworkError> {
+        let (sender, receiver) = mpsc::channel();
+        let url = request.url.clone();
+        thread::spawn(move || {
+            let _ = sender.send(Self::send_request(&request));
+        });
+
+        // the executor polls pending tasks on every step, so there's no waker to register
+        let response = future::poll_fn(move |_| match receiver.try_recv() {
+            Ok(x) => Poll::Ready(x),
+            Err(TryRecvError::Empty) => Poll::Pending,
+            Err(TryRecvError::Disconnected) => Poll::Ready(Err(NetworkError::Other)),
+        })
+        .await?;
+
+        parse_response(&response).ok_or_else(|| {
+            tracing::warn!("Malformed http response from {url}");
+
+            NetworkError::Other
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{Ipv4Addr, TcpListener},
+        pin::pin,
+        task::{Context, Poll, Waker},
+        thread,
+    };
+
+    use wie_backend::{HttpRequest, HttpTransport, Network, NetworkError, SocketType};
+
+    use super::{CliHttp, CliNetwork, parse_response};
+
+    #[test]
+    fn test_connect_in_background() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let network = CliNetwork::new();
+        let socket = network.socket(SocketType::Stream).unwrap();
+        assert_eq!(network.connect(socket, [127, 0, 0, 1], port), Err(NetworkError::WouldBlock));
+
+        let (mut peer, _) = listener.accept().unwrap();
+        while network.connect(socket, [127, 0, 0, 1], port) == Err(NetworkError::WouldBlock) {
+            thread::yield_now();
+        }
+
+        assert_eq!(network.send(socket, b"ping"), Ok(4));
+        let mut buf = [0; 4];
+        peer.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+
+    #[test]
+    fn test_request_on_worker_thread() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let http = CliHttp::new();
+        let request = HttpRequest {
+            method: "GET".into(),
+            url: format!("http://127.0.0.1:{port}/"),
+            ..Default::default()
+        };
+        let mut response = pin!(http.request(request));
+        let mut context = Context::from_waker(Waker::noop());
+
+        // nothing answers yet, so the request has to stay pending rather than block
+        assert!(response.as_mut().poll(&mut context).is_pending());
+
+        let (mut peer, _) = listener.accept().unwrap();
+        let mut buf = [0; 1024];
+        let _ = peer.read(&mut buf).unwrap();
+        peer.write_all(b"HTTP/1.0 200 OK\r\n\r\nok").unwrap();
+        drop(peer);
+
+        let response = loop {
+            if let Poll::Ready(x) = response.as_mut().poll(&mut context) {
+                break x.unwrap();
+            }
+            thread::yield_now();
+        };
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"ok");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let response = parse_response(b"HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nX-Rank:  12 \r\n\r\nmissing").unwrap();
+
+        assert_eq!(response.status, 404);
+        assert_eq!(response.message, "Not Found");
+        assert_eq!(response.header("content-type"), Some("text/plain"));
+        assert_eq!(response.header("X-Rank"), Some("12"));
+        assert_eq!(response.body, b"missing");
+    }
+
+    #[test]
+    fn test_parse_chunked_response() {
+        let response = parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nitem\r\n5;x=y\r\n=1234\r\n0\r\n\r\n").unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"item=1234");
+
+        assert!(parse_response(b"garbage").is_none());
+    }
+}
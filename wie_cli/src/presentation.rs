@@ -0,0 +1,228 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+
+/// How the screen is scaled up to the window.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ScaleMode {
+    /// Lanczos3 at any scale.
+    #[default]
+    Smooth,
+    /// Nearest neighbour at any scale.
+    Nearest,
+    /// Nearest neighbour at whole multiples only, for evenly sized pixels.
+    Integer,
+    /// hq2x, hq3x or hq4x pixel art scaling.
+    Hqx,
+}
+
+impl ScaleMode {
+    /// Scale actually used when the window fits `scale`.
+    pub fn effective_scale(self, scale: f64) -> f64 {
+        match self {
+            Self::Smooth | Self::Nearest => scale,
+            Self::Integer => scale.floor().max(1.0),
+            Self::Hqx => scale.floor().clamp(1.0, 4.0),
+        }
+    }
+}
+
+/// Post-process filter drawn over the scaled screen.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Filter {
+    #[default]
+    None,
+    /// Darkens the bottom row of every screen pixel, like a crt.
+    Scanlines,
+    /// Darkens the bottom row and right column of every screen pixel, like a handset lcd.
+    LcdGrid,
+}
+
+impl Filter {
+    /// Applies the filter to `buf`, `size` scaled from a screen of `src_size`. Does nothing below 2x.
+    pub fn apply(self, buf: &mut [u32], size: (u32, u32), src_size: (u32, u32)) {
+        if self == Self::None || size.0 < src_size.0 * 2 || size.1 < src_size.1 * 2 {
+            return;
+        }
+
+        // whether `i` is the last scaled pixel of a screen pixel
+        let is_edge = |i: u32, len: u32, src_len: u32| (i + 1) as u64 * src_len as u64 / len as u64 != i as u64 * src_len as u64 / len as u64;
+
+        for y in 0..size.1 {
+            let row_edge = is_edge(y, size.1, src_size.1);
+            for x in 0..size.0 {
+                let dim = match self {
+                    Self::Scanlines => row_edge,
+                    Self::LcdGrid => row_edge || is_edge(x, size.0, src_size.0),
+                    Self::None => false,
+                };
+
+                if dim {
+                    let pixel = &mut buf[(y * size.0 + x) as usize];
+                    *pixel = scale_color(*pixel, 160);
+                }
+            }
+        }
+    }
+}
+
+/// Handset picture drawn around the screen. The screen goes where the picture is fully transparent.
+pub struct Bezel {
+    width: u32,
+    height: u32,
+    // argb
+    pixels: Vec<u32>,
+    screen_x: u32,
+    screen_y: u32,
+}
+
+impl Bezel {
+    /// Loads a bezel image whose transparent screen area is `screen_width`x`screen_height`.
+    pub fn load(path: &Path, screen_width: u32, screen_height: u32) -> anyhow::Result<Self> {
+        let image = image::open(path)?.into_rgba8();
+
+        Self::from_rgba(image.width(), image.height(), image.as_raw(), screen_width, screen_height)
+    }
+
+    fn from_rgba(width: u32, height: u32, rgba: &[u8], screen_width: u32, screen_height: u32) -> anyhow::Result<Self> {
+        let pixels = rgba
+            .chunks_exact(4)
+            .map(|x| u32::from_be_bytes([x[3], x[0], x[1], x[2]]))
+            .collect::<Vec<_>>();
+
+        // bounding box of the transparent pixels
+        let (mut left, mut top, mut right, mut bottom) = (u32::MAX, u32::MAX, 0, 0);
+        for (i, pixel) in pixels.iter().enumerate() {
+            if pixel >> 24 == 0 {
+                let (x, y) = (i as u32 % width, i as u32 / width);
+                (left, top, right, bottom) = (left.min(x), top.min(y), right.max(x + 1), bottom.max(y + 1));
+            }
+        }
+
+        if left == u32::MAX {
+            anyhow::bail!("bezel has no transparent screen area");
+        }
+        if (right - left, bottom - top) != (screen_width, screen_height) {
+            anyhow::bail!(
+                "bezel screen area is {}x{}, the handset screen is {screen_width}x{screen_height}",
+                right - left,
+                bottom - top
+            );
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+            screen_x: left,
+            screen_y: top,
+        })
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Position of the screen in the bezel.
+    pub fn screen_position(&self) -> (u32, u32) {
+        (self.screen_x, self.screen_y)
+    }
+
+    /// Draws the bezel scaled by `scale` at `origin` of `dst`, a `dst_width` wide buffer on a black background.
+    pub fn draw(&self, dst: &mut [u32], dst_width: u32, origin: (u32, u32), scale: f64) {
+        let width = (self.width as f64 * scale) as u32;
+        let height = (self.height as f64 * scale) as u32;
+        let dst_height = dst.len() as u32 / dst_width;
+
+        for y in 0..height.min(dst_height.saturating_sub(origin.1)) {
+            let src_y = ((y as f64 / scale) as u32).min(self.height - 1);
+            for x in 0..width.min(dst_width.saturating_sub(origin.0)) {
+                let src_x = ((x as f64 / scale) as u32).min(self.width - 1);
+                let pixel = self.pixels[(src_y * self.width + src_x) as usize];
+
+                let alpha = pixel >> 24;
+                if alpha != 0 {
+                    dst[((origin.1 + y) * dst_width + origin.0 + x) as usize] = scale_color(pixel, alpha);
+                }
+            }
+        }
+    }
+}
+
+/// Copies `src`, a `src_width` wide image, to `origin` of `dst`, a `dst_width` wide buffer, clipping to `dst`.
+pub fn blit(dst: &mut [u32], dst_width: u32, src: &[u32], src_width: u32, origin: (u32, u32)) {
+    if origin.0 >= dst_width {
+        return;
+    }
+
+    let width = src_width.min(dst_width - origin.0) as usize;
+    for (src_row, dst_row) in src
+        .chunks_exact(src_width as usize)
+        .zip(dst.chunks_exact_mut(dst_width as usize).skip(origin.1 as usize))
+    {
+        dst_row[origin.0 as usize..origin.0 as usize + width].copy_from_slice(&src_row[..width]);
+    }
+}
+
+// multiplies the rgb channels by `factor` / 255
+fn scale_color(pixel: u32, factor: u32) -> u32 {
+    let channel = |shift: u32| (((pixel >> shift) & 0xff) * factor / 255) << shift;
+
+    channel(16) | channel(8) | channel(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bezel, Filter, ScaleMode, blit};
+
+    #[test]
+    fn test_effective_scale() {
+        assert_eq!(ScaleMode::Smooth.effective_scale(2.5), 2.5);
+        assert_eq!(ScaleMode::Integer.effective_scale(2.5), 2.0);
+        assert_eq!(ScaleMode::Integer.effective_scale(0.5), 1.0);
+        assert_eq!(ScaleMode::Hqx.effective_scale(5.0), 4.0);
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut buf = vec![0xffffff; 4 * 4];
+        Filter::Scanlines.apply(&mut buf, (4, 4), (2, 2));
+        assert_eq!(&buf[0..4], [0xffffff; 4]);
+        assert_eq!(&buf[4..8], [0xa0a0a0; 4]);
+
+        let mut buf = vec![0xffffff; 4 * 4];
+        Filter::LcdGrid.apply(&mut buf, (4, 4), (2, 2));
+        assert_eq!(&buf[0..4], [0xffffff, 0xa0a0a0, 0xffffff, 0xa0a0a0]);
+
+        let mut buf = vec![0xffffff; 3 * 3];
+        Filter::LcdGrid.apply(&mut buf, (3, 3), (2, 2));
+        assert_eq!(buf, [0xffffff; 9]);
+    }
+
+    #[test]
+    fn test_bezel() -> anyhow::Result<()> {
+        // 4x3 opaque red with a transparent 2x1 screen at (1, 1)
+        let mut rgba = [255, 0, 0, 255].repeat(12);
+        rgba[(4 + 1) * 4 + 3] = 0;
+        rgba[(4 + 2) * 4 + 3] = 0;
+
+        let bezel = Bezel::from_rgba(4, 3, &rgba, 2, 1)?;
+        assert_eq!(bezel.size(), (4, 3));
+        assert_eq!(bezel.screen_position(), (1, 1));
+        assert!(Bezel::from_rgba(4, 3, &rgba, 2, 2).is_err());
+
+        let mut dst = vec![0; 8 * 6];
+        bezel.draw(&mut dst, 8, (0, 0), 2.0);
+        assert_eq!(&dst[0..8], [0xff0000; 8]);
+        assert_eq!(&dst[2 * 8..3 * 8], [0xff0000, 0xff0000, 0, 0, 0, 0, 0xff0000, 0xff0000]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_blit() {
+        let mut dst = vec![0; 3 * 3];
+        blit(&mut dst, 3, &[1, 2, 3, 4], 2, (2, 1));
+        assert_eq!(dst, [0, 0, 0, 0, 0, 1, 0, 0, 3]);
+    }
+}
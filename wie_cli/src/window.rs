@@ -7,8 +7,8 @@ use fast_image_resize::{PixelType, ResizeOptions, SrcCropping};
 use softbuffer::{Context, Surface};
 use winit::{
     application::ApplicationHandler,
-    dpi::{LogicalSize, PhysicalSize},
-    event::{ElementState, KeyEvent, StartCause, WindowEvent},
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
+    event::{ElementState, KeyEvent, MouseButton, StartCause, WindowEvent},
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
     keyboard::PhysicalKey,
     window::{Window as WinitWindow, WindowId},
@@ -16,6 +16,11 @@ use winit::{
 
 use wie_backend::{Screen, canvas::Image};
 
+use crate::{
+    CliScreen,
+    presentation::{Bezel, Filter, ScaleMode, blit},
+};
+
 #[derive(Debug)]
 pub enum WindowInternalEvent {
     RequestRedraw,
@@ -28,6 +33,10 @@ pub enum WindowCallbackEvent {
     Redraw,
     Keydown(PhysicalKey),
     Keyup(PhysicalKey),
+    // left mouse button, in content screen coordinates
+    Pointerdown { x: i32, y: i32 },
+    Pointerdrag { x: i32, y: i32 },
+    Pointerup { x: i32, y: i32 },
 }
 
 pub struct WindowHandle {
@@ -48,6 +57,12 @@ impl WindowHandle {
     }
 }
 
+impl CliScreen for WindowHandle {
+    fn quit(&self) {
+        self.send_quit_event();
+    }
+}
+
 impl Screen for WindowHandle {
     fn request_redraw(&self) -> wie_util::Result<()> {
         self.send_event(WindowInternalEvent::RequestRedraw)
@@ -72,17 +87,31 @@ impl Screen for WindowHandle {
     }
 }
 
+/// How the screen is drawn in the window.
+#[derive(Default)]
+pub struct WindowOptions {
+    pub scale_mode: ScaleMode,
+    pub filter: Filter,
+    pub bezel: Option<Bezel>,
+}
+
 pub struct WindowImpl {
     width: u32,
     height: u32,
+    options: WindowOptions,
     event_loop: EventLoop<WindowInternalEvent>,
 }
 
 impl WindowImpl {
-    pub fn new(width: u32, height: u32) -> anyhow::Result<Self> {
+    pub fn new(width: u32, height: u32, options: WindowOptions) -> anyhow::Result<Self> {
         let event_loop = EventLoop::<WindowInternalEvent>::with_user_event().build()?;
 
-        Ok(Self { width, height, event_loop })
+        Ok(Self {
+            width,
+            height,
+            options,
+            event_loop,
+        })
     }
 
     pub fn handle(&self) -> WindowHandle {
@@ -101,12 +130,21 @@ impl WindowImpl {
 
         const DEFAULT_USER_SCALE_FACTOR: f64 = 1.0;
         let orig_size = LogicalSize::new(self.width, self.height);
+        let (frame_size, screen_position) = match &self.options.bezel {
+            Some(bezel) => (bezel.size().into(), bezel.screen_position()),
+            None => (orig_size, (0, 0)),
+        };
         let mut handler = ApplicationHandlerImpl {
             native_scale_factor: 1.0,
             user_scale_factor: DEFAULT_USER_SCALE_FACTOR,
             content_size: orig_size,
             scaled_size: orig_size.to_physical(1.0),
+            frame_size,
+            screen_position,
+            content_origin: (0, 0),
+            frame_origin: (0, 0),
             window_size: Default::default(),
+            options: self.options,
             scaler: Scaler::Native,
             scaled_image_buf: Default::default(),
             window: None,
@@ -114,6 +152,8 @@ impl WindowImpl {
             surface: None,
             callback: Box::new(callback),
             last_frame: vec![0u32; (self.width * self.height) as usize],
+            cursor_position: Default::default(),
+            pointer_down: false,
         };
 
         Ok(self.event_loop.run_app(&mut handler)?)
@@ -125,8 +165,12 @@ enum Scaler {
     Native,
     /// hq2x, hq3x, hq4x scaling.
     Hqx { scale: i8 },
-    /// Lanczos3 scaling
-    Lanczos3 { scale: f64, resizer: fast_image_resize::Resizer },
+    /// Resampling at any scale.
+    Resize {
+        scale: f64,
+        algorithm: ResizeAlg,
+        resizer: fast_image_resize::Resizer,
+    },
 }
 
 impl fmt::Display for Scaler {
@@ -134,30 +178,36 @@ impl fmt::Display for Scaler {
         match self {
             Scaler::Native => f.write_str("Native")?,
             Scaler::Hqx { scale } => f.write_fmt(format_args!("Hq{scale}x"))?,
-            Scaler::Lanczos3 { scale, resizer: _ } => f.write_fmt(format_args!("Lanczos3({scale})"))?,
+            Scaler::Resize {
+                scale,
+                algorithm,
+                resizer: _,
+            } => f.write_fmt(format_args!("{algorithm:?}({scale})"))?,
         }
         Ok(())
     }
 }
 
 impl Scaler {
-    fn new(scale: f64) -> Scaler {
-        match scale {
-            _ if (scale - 1.0).abs() < 1e-3 => Scaler::Native,
-            _ => Scaler::Lanczos3 {
-                scale,
-                resizer: fast_image_resize::Resizer::new(),
-            },
+    fn new(mode: ScaleMode, scale: f64) -> Scaler {
+        let scale = mode.effective_scale(scale);
+        if (scale - 1.0).abs() < 1e-3 {
+            return Scaler::Native;
         }
-    }
 
-    #[allow(dead_code)]
-    fn new_hqx(scale: f64) -> Scaler {
-        match scale {
-            _ if scale < 1.5 => Scaler::Native,
-            _ if scale < 2.5 => Scaler::Hqx { scale: 2 },
-            _ if scale < 3.5 => Scaler::Hqx { scale: 3 },
-            _ => Scaler::Hqx { scale: 4 },
+        let algorithm = match mode {
+            ScaleMode::Hqx => return Scaler::Hqx { scale: scale as i8 },
+            ScaleMode::Nearest | ScaleMode::Integer => ResizeAlg::Nearest,
+            #[cfg(debug_assertions)]
+            ScaleMode::Smooth => ResizeAlg::Nearest,
+            #[cfg(not(debug_assertions))]
+            ScaleMode::Smooth => ResizeAlg::Convolution(fast_image_resize::FilterType::Lanczos3),
+        };
+
+        Scaler::Resize {
+            scale,
+            algorithm,
+            resizer: fast_image_resize::Resizer::new(),
         }
     }
 
@@ -165,7 +215,7 @@ impl Scaler {
         match self {
             Scaler::Native => 1.0,
             Scaler::Hqx { scale } => *scale as f64,
-            Scaler::Lanczos3 { scale, resizer: _ } => *scale,
+            Scaler::Resize { scale, .. } => *scale,
         }
     }
 
@@ -173,7 +223,7 @@ impl Scaler {
         match self {
             Scaler::Native => PhysicalSize::new(logical_size.width, logical_size.height),
             Scaler::Hqx { scale } => PhysicalSize::new(logical_size.width * *scale as u32, logical_size.height * *scale as u32),
-            Scaler::Lanczos3 { scale, resizer: _ } => PhysicalSize::new(
+            Scaler::Resize { scale, .. } => PhysicalSize::new(
                 (logical_size.width as f64 * *scale).floor() as u32,
                 (logical_size.height as f64 * *scale).floor() as u32,
             ),
@@ -187,7 +237,7 @@ impl Scaler {
             Scaler::Hqx { scale } if *scale == 3 => hqx::hq3x(src.as_slice(), dst.as_mut_slice(), src_size.width as usize, src_size.height as usize),
             Scaler::Hqx { scale } if *scale == 4 => hqx::hq4x(src.as_slice(), dst.as_mut_slice(), src_size.width as usize, src_size.height as usize),
             Scaler::Hqx { scale } => panic!("invalid hqx scale factor {scale}"),
-            Scaler::Lanczos3 { scale: _, resizer } => {
+            Scaler::Resize { algorithm, resizer, .. } => {
                 let (_, srcarr, _) = unsafe { src.align_to::<u8>() };
                 let srcimg = fast_image_resize::images::ImageRef::new(src_size.width, src_size.height, srcarr, PixelType::U8x4).unwrap();
                 let (_, dstarr, _) = unsafe { dst.as_mut_slice().align_to_mut::<u8>() };
@@ -197,10 +247,7 @@ impl Scaler {
                         &srcimg,
                         &mut dstimg,
                         Some(&ResizeOptions {
-                            #[cfg(debug_assertions)]
-                            algorithm: ResizeAlg::Nearest,
-                            #[cfg(not(debug_assertions))]
-                            algorithm: ResizeAlg::Convolution(fast_image_resize::FilterType::Lanczos3),
+                            algorithm: *algorithm,
                             cropping: SrcCropping::None,
                             mul_div_alpha: false,
                         }),
@@ -230,10 +277,22 @@ where
     /// Scaled screen size.
     /// Equals to orig_size * scale_factor.
     scaled_size: PhysicalSize<u32>,
+    /// Size of what is drawn, the bezel or the content screen.
+    frame_size: LogicalSize<u32>,
+    /// Position of the content screen in the frame.
+    screen_position: (u32, u32),
+    /// Position of the scaled frame and content screen in the window, which letterboxes them.
+    frame_origin: (u32, u32),
+    content_origin: (u32, u32),
     /// Size of the OS window.
     window_size: PhysicalSize<u32>,
+    options: WindowOptions,
     /// Last content screen image data.
     last_frame: Vec<u32>,
+    /// Mouse cursor position in the window.
+    cursor_position: PhysicalPosition<f64>,
+    /// Whether the left mouse button went down on the content.
+    pointer_down: bool,
 
     window: Option<Arc<WinitWindow>>,
     context: Option<Context<Arc<WinitWindow>>>,
@@ -272,45 +331,64 @@ where
             self.user_scale_factor = 0.0;
         }
 
-        self.scaler = Scaler::new(self.native_scale_factor + self.user_scale_factor);
+        self.scaler = Scaler::new(self.options.scale_mode, self.native_scale_factor + self.user_scale_factor);
         self.scaled_size = self.scaler.to_physical(self.content_size);
         self.scaled_image_buf = vec![0u32; self.scaled_size.width as usize * self.scaled_size.height as usize];
     }
 
-    /// Updates the scaled content image surface's size.
+    /// Updates the window surface's size and where the frame is drawn in it.
     fn on_resize(&mut self) {
         tracing::info!(
             "on_resize scale=(native {}, actual {}), content={:?}, scaled={:?}, window={:?}",
             self.native_scale_factor,
-            self.scaler.scale(),
+            self.scaler,
             self.content_size,
             self.scaled_size,
             self.window_size
         );
+
+        let scale = self.scaler.scale();
+        let frame_size = self.scaler.to_physical(self.frame_size);
+        self.frame_origin = (
+            self.window_size.width.saturating_sub(frame_size.width) / 2,
+            self.window_size.height.saturating_sub(frame_size.height) / 2,
+        );
+        self.content_origin = (
+            self.frame_origin.0 + (self.screen_position.0 as f64 * scale) as u32,
+            self.frame_origin.1 + (self.screen_position.1 as f64 * scale) as u32,
+        );
+
+        let (Some(width), Some(height)) = (NonZeroU32::new(self.window_size.width), NonZeroU32::new(self.window_size.height)) else {
+            // minimized
+            return;
+        };
+
         let surface = match self.surface.as_mut() {
             None => {
                 self.surface = Some(Surface::new(self.context.as_ref().unwrap(), self.window.as_ref().unwrap().clone()).unwrap());
                 self.surface.as_mut().unwrap()
             }
-            Some(surface) => {
-                let desired_len = self.scaled_size.width * self.scaled_size.height;
-                if surface.buffer_mut().unwrap().len() == desired_len as usize {
-                    // nothing to do
-                    return;
-                };
-                surface
-            }
+            Some(surface) => surface,
         };
 
-        surface
-            .resize(
-                NonZeroU32::new(self.scaled_size.width).unwrap(),
-                NonZeroU32::new(self.scaled_size.height).unwrap(),
-            )
-            .unwrap();
+        surface.resize(width, height).unwrap();
         self.paint_last_frame();
     }
 
+    /// Cursor position in content screen coordinates clamped to the content, and whether the cursor is on the content.
+    fn content_position(&self) -> (i32, i32, bool) {
+        let x =
+            ((self.cursor_position.x - self.content_origin.0 as f64) * self.content_size.width as f64 / self.scaled_size.width as f64).floor() as i32;
+        let y = ((self.cursor_position.y - self.content_origin.1 as f64) * self.content_size.height as f64 / self.scaled_size.height as f64).floor()
+            as i32;
+
+        let inside = (0..self.content_size.width as i32).contains(&x) && (0..self.content_size.height as i32).contains(&y);
+        let x = x.clamp(0, self.content_size.width as i32 - 1);
+        let y = y.clamp(0, self.content_size.height as i32 - 1);
+
+        (x, y, inside)
+    }
+
     /// Displays the last content frame to the window.
     fn paint_last_frame(&mut self) -> Option<()> {
         let data = &self.last_frame;
@@ -319,23 +397,35 @@ where
         } else {
             self.scaler
                 .scale_image(&mut self.scaled_image_buf, data, self.scaled_size, self.content_size);
+            self.options
+                .filter
+                .apply(&mut self.scaled_image_buf, self.scaled_size.into(), self.content_size.into());
             &self.scaled_image_buf
         };
 
-        let mut win_buf = self.surface.as_mut().unwrap().buffer_mut().unwrap();
-        if win_buf.len() == data_to_blit.len() {
-            win_buf.copy_from_slice(data_to_blit);
-        } else {
+        let mut win_buf = self.surface.as_mut()?.buffer_mut().unwrap();
+        if win_buf.len() != self.window_size.width as usize * self.window_size.height as usize {
             tracing::warn!(
-                "buffer size mismatch, skipping paint: {}, {} (content {:?}, scaled {:?}, win {:?})",
+                "buffer size mismatch, skipping paint: {} (content {:?}, scaled {:?}, win {:?})",
                 win_buf.len(),
-                data_to_blit.len(),
                 self.content_size,
                 self.scaled_size,
                 self.window_size
             );
             return None;
         }
+
+        win_buf.fill(0);
+        if let Some(bezel) = &self.options.bezel {
+            bezel.draw(&mut win_buf, self.window_size.width, self.frame_origin, self.scaler.scale());
+        }
+        blit(
+            &mut win_buf,
+            self.window_size.width,
+            data_to_blit,
+            self.scaled_size.width,
+            self.content_origin,
+        );
         win_buf.present().unwrap();
         Some(())
     }
@@ -352,7 +442,7 @@ where
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         // Initialize the window.
         let window_attributes = WinitWindow::default_attributes()
-            .with_inner_size(self.content_size.to_physical::<u32>(1.0))
+            .with_inner_size(self.frame_size.to_physical::<u32>(1.0))
             .with_title("WIE");
         let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
         let context = Context::new(window.clone()).unwrap();
@@ -362,7 +452,7 @@ where
 
         // After the window is initialized we resize the window again with the correct scale factor.
         self.update_scale_factor(Some(window.scale_factor()), Some(1.0));
-        if let Some(new_size) = window.request_inner_size(self.scaled_size) {
+        if let Some(new_size) = window.request_inner_size(self.scaler.to_physical(self.frame_size)) {
             self.window_size = new_size;
         }
         self.on_resize();
@@ -403,16 +493,43 @@ where
                     self.callback(WindowCallbackEvent::Keyup(physical_key), event_loop);
                 }
             },
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_position = position;
+
+                if self.pointer_down {
+                    let (x, y, _) = self.content_position();
+                    self.callback(WindowCallbackEvent::Pointerdrag { x, y }, event_loop);
+                }
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                let (x, y, inside) = self.content_position();
+
+                match state {
+                    ElementState::Pressed if inside => {
+                        self.pointer_down = true;
+                        self.callback(WindowCallbackEvent::Pointerdown { x, y }, event_loop);
+                    }
+                    ElementState::Released if self.pointer_down => {
+                        self.pointer_down = false;
+                        self.callback(WindowCallbackEvent::Pointerup { x, y }, event_loop);
+                    }
+                    _ => {}
+                }
+            }
             WindowEvent::RedrawRequested => {
                 self.callback(WindowCallbackEvent::Redraw, event_loop);
             }
             WindowEvent::Resized(new_size) => {
                 tracing::debug!("WindowResized {new_size:?}");
                 self.window_size = new_size;
-                if self.window_size != self.scaled_size {
-                    // Determine the new scale factor.
-                    let wscale = self.window_size.width as f64 / self.content_size.width as f64;
-                    let hscale = self.window_size.height as f64 / self.content_size.height as f64;
+                if self.window_size != self.scaler.to_physical(self.frame_size) {
+                    // Determine the new scale factor. The frame is letterboxed in whatever space is left.
+                    let wscale = self.window_size.width as f64 / self.frame_size.width as f64;
+                    let hscale = self.window_size.height as f64 / self.frame_size.height as f64;
                     let new_scale = wscale.min(hscale);
                     let new_user_scale = new_scale - self.native_scale_factor;
                     self.update_scale_factor(None, Some(new_user_scale));
@@ -425,7 +542,7 @@ where
             } => {
                 tracing::info!("ScaleFactorChanged {scale_factor}");
                 self.update_scale_factor(Some(scale_factor), None);
-                let _ = inner_size_writer.request_inner_size(self.scaled_size);
+                let _ = inner_size_writer.request_inner_size(self.scaler.to_physical(self.frame_size));
                 // Will receive WindowEvent::Resized soon, so no need to call self.on_resize().
             }
             _ => {}
@@ -1,7 +1,9 @@
 mod bucket;
 mod list;
 
-use wie_util::Result;
+use alloc::vec::Vec;
+
+use wie_util::{ByteRead, Result};
 
 use crate::{
     ArmCore,
@@ -40,4 +42,15 @@ impl Allocator {
             BucketAllocator::free(core, HEAP_BASE + HEAP_SIZE / 2, address, size)
         }
     }
+
+    /// Live allocations as `(address, size)`. `size` is the usable size of the block, which may exceed the requested size.
+    pub fn allocations<R>(memory: &R) -> Result<Vec<(u32, u32)>>
+    where
+        R: ?Sized + ByteRead,
+    {
+        let mut result = ListAllocator::allocations(memory, HEAP_BASE, HEAP_SIZE / 2)?;
+        result.extend(BucketAllocator::allocations(memory, HEAP_BASE + HEAP_SIZE / 2)?);
+
+        Ok(result)
+    }
 }
@@ -1,4 +1,4 @@
-use alloc::vec;
+use alloc::{vec, vec::Vec};
 
 use wie_util::{ByteRead, ByteWrite, Result, WieError};
 
@@ -116,6 +116,34 @@ impl BucketAllocator {
         Ok(())
     }
 
+    pub fn allocations<R>(memory: &R, base_address: u32) -> Result<Vec<(u32, u32)>>
+    where
+        R: ?Sized + ByteRead,
+    {
+        let mut result = Vec::new();
+
+        for (bucket_index, &(slot_size, _)) in BUCKETS.iter().enumerate() {
+            let header_address = base_address + region_offset(bucket_index) as u32;
+            let header_len = header_length(bucket_index);
+
+            let mut header = vec![0u8; header_len];
+            memory.read_bytes(header_address, &mut header)?;
+
+            for (i, item) in header.iter().enumerate() {
+                let mut allocated = !*item;
+                while allocated != 0 {
+                    let bit = allocated.trailing_zeros();
+                    allocated &= !(1 << bit);
+
+                    let address = header_address + header_len as u32 + (i as u32 * 8 + bit) * slot_size as u32;
+                    result.push((address, slot_size as u32));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
     fn find_bucket_index(size: u32) -> usize {
         BUCKETS.iter().position(|&(s, _)| size as usize <= s).unwrap_or(BUCKETS.len() - 1)
     }
@@ -171,6 +199,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_allocations() -> Result<()> {
+        let mut core = ArmCore::new(false, None).unwrap();
+        core.map(0x40000000, 0x8000000)?;
+
+        BucketAllocator::init(&mut core, 0x40000000, 0x8000000)?;
+
+        let address1 = BucketAllocator::alloc(&mut core, 0x40000000, 4)?;
+        let address2 = BucketAllocator::alloc(&mut core, 0x40000000, 4)?;
+        let address3 = BucketAllocator::alloc(&mut core, 0x40000000, 100)?;
+        BucketAllocator::free(&mut core, 0x40000000, address1, 4)?;
+
+        let allocations = BucketAllocator::allocations(&core, 0x40000000)?;
+        assert_eq!(allocations, [(address2, 4), (address3, 128)]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_init_rejects_undersized_region() {
         let mut core = ArmCore::new(false, None).unwrap();
@@ -1,9 +1,9 @@
-use alloc::format;
+use alloc::{format, vec::Vec};
 use core::mem::size_of;
 
 use bytemuck::{Pod, Zeroable};
 
-use wie_util::{Result, WieError, read_generic, write_generic};
+use wie_util::{ByteRead, Result, WieError, read_generic, write_generic};
 
 use crate::core::ArmCore;
 
@@ -87,6 +87,33 @@ impl ListAllocator {
         Ok(())
     }
 
+    pub fn allocations<R>(memory: &R, base_address: u32, base_size: u32) -> Result<Vec<(u32, u32)>>
+    where
+        R: ?Sized + ByteRead,
+    {
+        let mut result = Vec::new();
+
+        let mut cursor = base_address;
+        while cursor < base_address + base_size {
+            let header: ListAllocationHeader = read_generic(memory, cursor)?;
+            if header.size() == 0 {
+                return Err(WieError::FatalError(format!("Invalid allocation header at {cursor:#x}")));
+            }
+
+            if header.in_use() {
+                let header_size = size_of::<ListAllocationHeader>() as u32;
+                let size = header
+                    .size()
+                    .checked_sub(header_size + CANARY_SIZE)
+                    .ok_or_else(|| WieError::FatalError(format!("Corrupt allocation header at {cursor:#x}: size {:#x}", header.size())))?;
+                result.push((cursor + header_size, size));
+            }
+            cursor += header.size();
+        }
+
+        Ok(result)
+    }
+
     fn find_address(core: &ArmCore, base_address: u32, base_size: u32, size: u32) -> Result<u32> {
         let mut cursor = base_address;
         loop {
@@ -112,7 +139,7 @@ impl ListAllocator {
 
 #[cfg(test)]
 mod tests {
-    use wie_util::Result;
+    use wie_util::{Result, write_generic};
 
     use crate::ArmCore;
 
@@ -130,4 +157,36 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_allocations() -> Result<()> {
+        let mut core = ArmCore::new(false, None).unwrap();
+        core.map(0x40000000, 0x1000)?;
+
+        ListAllocator::init(&mut core, 0x40000000, 0x1000)?;
+        let address1 = ListAllocator::alloc(&mut core, 0x40000000, 0x1000, 4)?;
+        let address2 = ListAllocator::alloc(&mut core, 0x40000000, 0x1000, 10)?;
+        let address3 = ListAllocator::alloc(&mut core, 0x40000000, 0x1000, 4)?;
+        ListAllocator::free(&mut core, address2)?;
+
+        let allocations = ListAllocator::allocations(&core, 0x40000000, 0x1000)?;
+        assert_eq!(allocations, [(address1, 4), (address3, 4)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_allocations_corrupt_header() -> Result<()> {
+        let mut core = ArmCore::new(false, None).unwrap();
+        core.map(0x40000000, 0x1000)?;
+
+        ListAllocator::init(&mut core, 0x40000000, 0x1000)?;
+        let address = ListAllocator::alloc(&mut core, 0x40000000, 0x1000, 4)?;
+        // in use, but smaller than a header and canary
+        write_generic(&mut core, address - 4, 0x80000004u32)?;
+
+        assert!(ListAllocator::allocations(&core, 0x40000000, 0x1000).is_err());
+
+        Ok(())
+    }
 }
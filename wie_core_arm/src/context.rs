@@ -1,5 +1,7 @@
 use core::clone::Clone;
 
+use wie_backend::SnapshotWriter;
+
 #[derive(Clone)]
 pub struct ArmCoreContext {
     pub r0: u32,
@@ -20,3 +22,16 @@ pub struct ArmCoreContext {
     pub pc: u32,
     pub cpsr: u32,
 }
+
+impl ArmCoreContext {
+    pub(crate) fn write_state(&self, writer: &mut SnapshotWriter) {
+        let registers = [
+            self.r0, self.r1, self.r2, self.r3, self.r4, self.r5, self.r6, self.r7, self.r8, self.sb, self.sl, self.fp, self.ip, self.sp, self.lr,
+            self.pc, self.cpsr,
+        ];
+
+        for register in registers {
+            writer.write_u32(register);
+        }
+    }
+}
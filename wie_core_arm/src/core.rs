@@ -3,27 +3,28 @@ use core::mem::size_of;
 
 use spin::Mutex;
 
-use wie_backend::{ProfileCallback, ProfileSample};
+use wie_backend::{ProfileCallback, ProfileSample, SnapshotWriter, SvcTrace, SvcTraceCallback, SymbolResolver};
 use wie_util::{ByteRead, ByteWrite, Result, WieError, read_generic};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    DebugCaller, DebugEntry,
+    engine::{DebugInner, DebuggedArm32CpuEngine},
+    gdb::GdbTarget,
+};
 use crate::{
-    EmulatedFunction, ResultWriter, ThreadId,
+    DebugMonitor, EmulatedFunction, ResultWriter, ThreadId,
     context::ArmCoreContext,
-    engine::{Arm32CpuEngine, ArmEngine, ArmRegister, EngineRunResult, MemoryPermission},
+    engine::{Arm32CpuEngine, ArmEngine, ArmRegister, CachedArm32CpuEngine, EngineRunResult, MemoryPermission},
     function::{RegisteredFunction, RegisteredFunctionHolder},
     thread::ThreadState,
     thread_wrapper::ArmCoreThreadWrapper,
 };
-#[cfg(not(target_arch = "wasm32"))]
-use crate::{
-    engine::{DebugInner, DebuggedArm32CpuEngine},
-    gdb::GdbTarget,
-};
 
 const GLOBAL_DATA_BASE: u32 = 0x7fff0000;
-const FUNCTIONS_BASE: u32 = 0x71000000;
-const FUNCTIONS_SIZE: usize = 0x10000;
-const SVC_STUB_SIZE: u32 = 16;
+pub(crate) const FUNCTIONS_BASE: u32 = 0x71000000;
+pub(crate) const FUNCTIONS_SIZE: usize = 0x10000;
+pub(crate) const SVC_STUB_SIZE: u32 = 16;
 pub const RUN_FUNCTION_LR: u32 = 0x7f000000;
 pub const HEAP_BASE: u32 = 0x40000000;
 pub const HEAP_SIZE: u32 = 0x10000000;
@@ -33,6 +34,11 @@ pub const HEAP_SIZE: u32 = 0x10000000;
 const PROFILE_MAX_STACK: usize = 32;
 /// Flush the per-stack counter map every this many samples taken.
 const PROFILE_FLUSH_INTERVAL: u32 = 1000;
+/// Longest argument string decoded in SVC traces.
+const SVC_TRACE_MAX_STRING: u32 = 64;
+/// Granularity of memory in state digests. All-zero chunks are skipped, which
+/// keeps the mostly untouched heap mapping from being hashed.
+const DIGEST_CHUNK_SIZE: usize = 0x1000;
 
 struct ProfileState {
     samples: BTreeMap<Vec<u32>, u64>,
@@ -40,6 +46,12 @@ struct ProfileState {
     callback: ProfileCallback,
 }
 
+struct SvcTraceState {
+    callback: SvcTraceCallback,
+    // calls in progress per guest thread, innermost last. `None` is code run outside of guest threads.
+    calls: BTreeMap<Option<ThreadId>, Vec<SvcTrace>>,
+}
+
 pub(crate) struct ArmCoreInner {
     pub(crate) engine: Box<dyn ArmEngine>,
     last_thread_id: ThreadId,
@@ -47,6 +59,10 @@ pub(crate) struct ArmCoreInner {
     svc_handlers: BTreeMap<u32, Arc<Box<dyn RegisteredFunction>>>,
     next_stub_address: u32,
     profile: Option<ProfileState>,
+    symbols: Option<SymbolResolver>,
+    svc_names: BTreeMap<u32, fn(u32) -> String>,
+    svc_trace: Option<SvcTraceState>,
+    current_thread: Option<ThreadId>,
 }
 
 impl Drop for ArmCoreInner {
@@ -74,7 +90,7 @@ pub struct ArmCore {
 
 impl ArmCore {
     pub fn new(enable_gdbserver: bool, profile: Option<ProfileCallback>) -> Result<Self> {
-        let mut engine = if enable_gdbserver {
+        let engine = if enable_gdbserver {
             #[cfg(not(target_arch = "wasm32"))]
             let engine = Box::new(DebuggedArm32CpuEngine::new()) as Box<dyn ArmEngine>;
             #[cfg(target_arch = "wasm32")]
@@ -85,6 +101,25 @@ impl ArmCore {
             Box::new(Arm32CpuEngine::new())
         };
 
+        let result = Self::with_engine(engine, profile);
+
+        if enable_gdbserver {
+            #[cfg(not(target_arch = "wasm32"))]
+            GdbTarget::start(result.clone())?;
+            #[cfg(target_arch = "wasm32")]
+            panic!("GDB server is not supported on wasm32");
+        }
+
+        Ok(result)
+    }
+
+    /// Runs thumb code from cached decoded blocks instead of interpreting each instruction. There's no gdb server on
+    /// this engine.
+    pub fn with_block_cache(profile: Option<ProfileCallback>) -> Result<Self> {
+        Ok(Self::with_engine(Box::new(CachedArm32CpuEngine::new()), profile))
+    }
+
+    fn with_engine(mut engine: Box<dyn ArmEngine>, profile: Option<ProfileCallback>) -> Self {
         engine.mem_map(FUNCTIONS_BASE, FUNCTIONS_SIZE, MemoryPermission::ReadExecute);
         engine.mem_map(GLOBAL_DATA_BASE, 0x4000, MemoryPermission::ReadWriteExecute);
 
@@ -101,20 +136,15 @@ impl ArmCore {
             svc_handlers: BTreeMap::new(),
             next_stub_address: FUNCTIONS_BASE,
             profile,
+            symbols: None,
+            svc_names: BTreeMap::new(),
+            svc_trace: None,
+            current_thread: None,
         };
 
-        let result = Self {
+        Self {
             inner: Arc::new(Mutex::new(inner)),
-        };
-
-        if enable_gdbserver {
-            #[cfg(not(target_arch = "wasm32"))]
-            GdbTarget::start(result.clone())?;
-            #[cfg(target_arch = "wasm32")]
-            panic!("GDB server is not supported on wasm32");
         }
-
-        Ok(result)
     }
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -207,8 +237,7 @@ impl ArmCore {
         inner.threads.keys().cloned().collect()
     }
 
-    fn sample_profile(&self) {
-        let mut inner = self.inner.lock();
+    fn sample_profile(inner: &mut ArmCoreInner) {
         if inner.profile.is_none() {
             return;
         }
@@ -283,44 +312,160 @@ impl ArmCore {
             inner.engine.reg_write(ArmRegister::Cpsr, new_cpsr);
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        let debug_entry = self.debug_inner().map(|debug| {
+            // svc handlers run with pc past the svc instruction of the stub the guest called
+            let caller = (FUNCTIONS_BASE..FUNCTIONS_BASE + FUNCTIONS_SIZE as u32)
+                .contains(&previous_context.pc)
+                .then(|| DebugCaller {
+                    stub: previous_context.pc - (previous_context.pc - FUNCTIONS_BASE) % SVC_STUB_SIZE,
+                    return_address: previous_context.lr,
+                });
+
+            (debug.push_entry(DebugEntry { address, caller }), debug)
+        });
+
+        let run_result = self.run_until_end().await;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((thread_id, debug)) = debug_entry {
+            debug.pop_entry(thread_id);
+        }
+        run_result?;
+
+        let result = R::get(self);
+        self.restore_context(&previous_context);
+
+        Ok(result)
+    }
+
+    async fn run_until_end(&mut self) -> Result<()> {
         loop {
-            let result = {
+            // the lock is held until the guest code ends or calls into an svc handler, the slices only pace profile samples
+            let (category, function) = {
                 let mut inner = self.inner.lock();
-                inner.engine.run(RUN_FUNCTION_LR, 1000)?
+                let (category, lr, spsr) = loop {
+                    let result = inner.engine.run(RUN_FUNCTION_LR, 1000)?;
+                    Self::sample_profile(&mut inner);
+
+                    match result {
+                        EngineRunResult::End => return Ok(()),
+                        EngineRunResult::CountExhausted => {}
+                        EngineRunResult::Svc { category, lr, spsr } => break (category, lr, spsr),
+                    }
+                };
+
+                // Restore the pre-exception execution state before running the Rust SVC handler.
+                inner.engine.reg_write(ArmRegister::Cpsr, spsr);
+                inner.engine.reg_write(ArmRegister::PC, lr);
+
+                let function = inner
+                    .svc_handlers
+                    .get(&category)
+                    .cloned()
+                    .ok_or_else(|| WieError::FatalError(format!("Unknown SVC handler category: {category}")))?;
+
+                (category, function)
             };
 
-            self.sample_profile();
-
-            match result {
-                EngineRunResult::End => break,
-                EngineRunResult::CountExhausted => {}
-                EngineRunResult::Svc { category, lr, spsr } => {
-                    {
-                        let mut inner = self.inner.lock();
-                        // Restore the pre-exception execution state before running the Rust SVC handler.
-                        inner.engine.reg_write(ArmRegister::Cpsr, spsr);
-                        inner.engine.reg_write(ArmRegister::PC, lr);
-                    }
+            let trace = self.begin_svc_trace(category);
 
-                    let function = {
-                        let inner = self.inner.lock();
-                        inner
-                            .svc_handlers
-                            .get(&category)
-                            .cloned()
-                            .ok_or_else(|| WieError::FatalError(format!("Unknown SVC handler category: {category}")))?
-                    };
-
-                    let mut self1 = self.clone();
-                    function.call(&mut self1).await?;
-                }
+            let mut self1 = self.clone();
+            let call_result = function.call(&mut self1).await;
+
+            // java exceptions unwind through here as errors, so end the trace either way to keep the depth balanced
+            if let Some(thread_id) = trace {
+                self.end_svc_trace(thread_id);
             }
+            call_result?;
         }
+    }
 
-        let result = R::get(self);
-        self.restore_context(&previous_context);
+    fn begin_svc_trace(&self, category: u32) -> Option<Option<ThreadId>> {
+        let mut inner = self.inner.lock();
+        inner.svc_trace.as_ref()?;
 
-        Ok(result)
+        let id = inner.engine.reg_read(ArmRegister::IP);
+        let pc = inner.engine.reg_read(ArmRegister::PC);
+        let name = match inner.svc_names.get(&category) {
+            Some(names) => Some(names(id)),
+            None => inner.symbols.as_ref().and_then(|x| x.resolve(pc)).map(|(name, _)| name),
+        };
+        let caller = inner.engine.reg_read(ArmRegister::LR);
+
+        let thread_id = inner.current_thread;
+        let calls = inner.svc_trace.as_mut().unwrap().calls.entry(thread_id).or_default();
+        calls.push(SvcTrace {
+            category,
+            id,
+            name,
+            args: Vec::new(),
+            arg_strings: Vec::new(),
+            result: 0,
+            caller,
+            depth: calls.len() as u32,
+        });
+
+        Some(thread_id)
+    }
+
+    fn end_svc_trace(&self, thread_id: Option<ThreadId>) {
+        let mut inner = self.inner.lock();
+        let result = inner.engine.reg_read(ArmRegister::R0);
+
+        let state = inner.svc_trace.as_mut().unwrap();
+        let calls = state.calls.get_mut(&thread_id).unwrap();
+        let mut trace = calls.pop().unwrap();
+        if calls.is_empty() {
+            state.calls.remove(&thread_id);
+        }
+
+        trace.result = result;
+        (state.callback)(trace);
+    }
+
+    /// Reads parameter `pos` of the running SVC-dispatched function, adding it to the call's trace.
+    pub(crate) fn read_svc_param(&self, pos: usize) -> Result<u32> {
+        let value = self.read_param(pos)?;
+
+        let mut inner = self.inner.lock();
+        let thread_id = inner.current_thread;
+        let ArmCoreInner { engine, svc_trace, .. } = &mut *inner;
+        if let Some(trace) = svc_trace.as_mut().and_then(|x| x.calls.get_mut(&thread_id)).and_then(|x| x.last_mut()) {
+            if trace.args.len() <= pos {
+                trace.args.resize(pos + 1, 0);
+                trace.arg_strings.resize(pos + 1, None);
+            }
+            trace.args[pos] = value;
+            trace.arg_strings[pos] = Self::read_trace_string(engine.as_mut(), value);
+        }
+
+        Ok(value)
+    }
+
+    // text at `address` if it's a short NUL terminated run of printable ascii
+    fn read_trace_string(engine: &mut dyn ArmEngine, address: u32) -> Option<String> {
+        let mut result = String::new();
+        for i in 0..SVC_TRACE_MAX_STRING {
+            let mut byte = [0];
+            engine.mem_read(address.checked_add(i)?, 1, &mut byte).ok()?;
+
+            match byte[0] {
+                0 => break,
+                b'\n' | b'\r' | b'\t' | 0x20..=0x7e => result.push(byte[0] as char),
+                _ => return None,
+            }
+        }
+
+        (!result.is_empty()).then_some(result)
+    }
+
+    /// Reports every SVC-dispatched call to `callback` from now on.
+    pub fn set_svc_trace(&mut self, callback: SvcTraceCallback) {
+        self.inner.lock().svc_trace = Some(SvcTraceState {
+            callback,
+            calls: BTreeMap::new(),
+        });
     }
 
     pub fn register_svc_handler<F, C, R, P>(&mut self, category: u32, handler: F, context: &C) -> Result<()>
@@ -343,6 +488,48 @@ impl ArmCore {
         Ok(())
     }
 
+    /// Names the SVC stubs of `category` made from now on with `names(id)` in the symbol table.
+    pub fn set_svc_names(&mut self, category: u32, names: fn(u32) -> String) {
+        self.inner.lock().svc_names.insert(category, names);
+    }
+
+    /// Sets the table the loaders add the names of guest code and SVC stubs to.
+    pub fn set_symbols(&mut self, symbols: SymbolResolver) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(debug) = self.debug_inner() {
+            debug.set_symbols(symbols.clone());
+        }
+
+        self.inner.lock().symbols = Some(symbols);
+    }
+
+    /// Reports a guest binary loaded with its first section at `address` to the gdb stub, so gdb can load its symbols.
+    /// Does nothing unless the gdb server is enabled.
+    pub fn add_library(&mut self, name: &str, address: u32) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(debug) = self.debug_inner() {
+            debug.add_library(name, address);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        let _ = (name, address);
+    }
+
+    /// Adds `monitor` commands to the gdb stub. Does nothing unless the gdb server is enabled.
+    pub fn add_debug_monitor(&mut self, monitor: Box<dyn DebugMonitor>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(debug) = self.debug_inner() {
+            debug.add_monitor(monitor);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        drop(monitor);
+    }
+
+    pub fn symbols(&self) -> Option<SymbolResolver> {
+        self.inner.lock().symbols.clone()
+    }
+
     pub fn make_svc_stub(&mut self, category: u32, id: impl Into<u32>) -> Result<u32> {
         let mut inner = self.inner.lock();
         let id = id.into();
@@ -378,6 +565,10 @@ impl ArmCore {
 
         tracing::trace!("Register SVC stub at {address:#x}, category={category}, id={id}");
 
+        if let (Some(symbols), Some(names)) = (&inner.symbols, inner.svc_names.get(&category)) {
+            symbols.add(address, SVC_STUB_SIZE, names(id));
+        }
+
         Ok(address + 1)
     }
 
@@ -391,6 +582,59 @@ impl ArmCore {
         Ok(())
     }
 
+    /// Digest of the current registers, every thread context and the contents of mapped memory.
+    /// Allocator bookkeeping lives in emulated memory, so heap state is included as well.
+    pub fn state_digest(&self) -> Result<[u8; 16]> {
+        let mut writer = SnapshotWriter::new();
+        self.save_context().write_state(&mut writer);
+
+        let mut inner = self.inner.lock();
+        writer.write_u32(inner.next_stub_address);
+        writer.write_u32(inner.last_thread_id as _);
+
+        writer.write_u32(inner.threads.len() as _);
+        for (thread_id, state) in &inner.threads {
+            writer.write_u32(*thread_id as _);
+            state.context.write_state(&mut writer);
+        }
+
+        let regions = inner.engine.mapped_regions();
+        writer.write_u32(regions.len() as _);
+        for (address, size) in &regions {
+            writer.write_u32(*address);
+            writer.write_u64(*size as _);
+        }
+
+        let mut digest = md5::Context::new();
+        digest.consume(writer.into_inner());
+
+        let mut chunk = [0; DIGEST_CHUNK_SIZE];
+        for (address, size) in regions {
+            for offset in (0..size).step_by(DIGEST_CHUNK_SIZE) {
+                let chunk_address = address + offset as u32;
+                inner.engine.mem_read(chunk_address, DIGEST_CHUNK_SIZE, &mut chunk)?;
+                if chunk.iter().all(|&x| x == 0) {
+                    continue;
+                }
+
+                digest.consume(chunk_address.to_le_bytes());
+                digest.consume(chunk);
+            }
+        }
+
+        Ok(digest.finalize().0)
+    }
+
+    /// Checks that this core is in the state a checkpoint took [`ArmCore::state_digest`] of. Threads are owned by
+    /// executor tasks, so checkpoints are reached by replaying the system rather than by restoring the core.
+    pub fn check_state_digest(&self, digest: &[u8]) -> Result<()> {
+        if self.state_digest()? != digest {
+            return Err(WieError::FatalError("Replayed arm core state does not match the checkpoint".into()));
+        }
+
+        Ok(())
+    }
+
     pub fn dump_reg_stack(&self, image_base: u32) -> String {
         format!(
             "\n{}\nPossible call stack:\n{}\nStack:\n{}",
@@ -650,11 +894,17 @@ impl RunFunctionResult<()> for () {
 pub struct ThreadContextGuard {
     core: ArmCore,
     thread_id: ThreadId,
+    previous_thread: Option<ThreadId>,
 }
 
 impl ThreadContextGuard {
     pub fn new(mut core: ArmCore, thread_id: ThreadId) -> Self {
-        let context = core.inner.lock().threads.get(&thread_id).unwrap().context.clone(); // TODO we might not need clone
+        let (context, previous_thread) = {
+            let mut inner = core.inner.lock();
+            let context = inner.threads.get(&thread_id).unwrap().context.clone(); // TODO we might not need clone
+
+            (context, inner.current_thread.replace(thread_id))
+        };
         core.restore_context(&context);
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -662,7 +912,11 @@ impl ThreadContextGuard {
             debug.on_thread_entered(thread_id);
         }
 
-        Self { core, thread_id }
+        Self {
+            core,
+            thread_id,
+            previous_thread,
+        }
     }
 }
 
@@ -672,6 +926,7 @@ impl Drop for ThreadContextGuard {
 
         let mut inner = self.core.inner.lock();
         inner.threads.get_mut(&self.thread_id).unwrap().context = context;
+        inner.current_thread = self.previous_thread;
         drop(inner);
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -724,4 +979,107 @@ mod tests {
             EngineRunResult::CountExhausted => panic!("expected SVC, got count exhausted"),
         }
     }
+
+    #[test]
+    fn test_svc_stub_symbols() {
+        let mut core = ArmCore::new(false, None).unwrap();
+        let symbols = SymbolResolver::new();
+        core.set_symbols(symbols.clone());
+
+        core.register_svc_handler(1, test_svc_handler, &None).unwrap();
+        core.register_svc_handler(2, test_svc_handler, &None).unwrap();
+        core.set_svc_names(1, |id| format!("function{id}"));
+
+        let named = core.make_svc_stub(1, 3u32).unwrap();
+        let unnamed = core.make_svc_stub(2, 0u32).unwrap();
+
+        assert_eq!(symbols.format(named), "function3");
+        assert_eq!(symbols.resolve(unnamed), None);
+    }
+
+    #[test]
+    fn test_read_trace_string() -> Result<()> {
+        let mut core = ArmCore::new(false, None)?;
+        core.map(0x100000, 0x1000)?;
+        core.write_bytes(0x100000, b"%d\n\0")?;
+        core.write_bytes(0x100010, &[0xb0, 0xa1, 0])?;
+
+        let mut inner = core.inner.lock();
+        assert_eq!(ArmCore::read_trace_string(inner.engine.as_mut(), 0x100000), Some("%d\n".into()));
+        assert_eq!(ArmCore::read_trace_string(inner.engine.as_mut(), 0x100010), None);
+        assert_eq!(ArmCore::read_trace_string(inner.engine.as_mut(), 0x100020), None);
+        assert_eq!(ArmCore::read_trace_string(inner.engine.as_mut(), 0x200000), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_svc_trace_args() -> Result<()> {
+        let mut core = ArmCore::new(false, None)?;
+        core.map(0x100000, 0x1000)?;
+        core.write_bytes(0x100000, b"%d\0")?;
+        core.write_bytes(0x100800, &0x44u32.to_le_bytes())?;
+
+        let traces = Arc::new(Mutex::new(Vec::new()));
+        let traces_clone = traces.clone();
+        core.set_svc_trace(Box::new(move |trace| traces_clone.lock().push(trace)));
+
+        let mut context = core.save_context();
+        (context.r0, context.r1, context.r2, context.r3, context.sp) = (0x100000, 1, 2, 3, 0x100800);
+        core.restore_context(&context);
+
+        let outer = core.begin_svc_trace(3).unwrap();
+        for pos in 0..5 {
+            core.read_svc_param(pos)?;
+        }
+
+        // a callback run by the outer call
+        let nested = core.begin_svc_trace(3).unwrap();
+        core.read_svc_param(1)?;
+        core.end_svc_trace(nested);
+
+        // another thread's calls don't nest in this one's
+        let previous_thread = core.inner.lock().current_thread.replace(1);
+        let other = core.begin_svc_trace(3).unwrap();
+        core.end_svc_trace(other);
+        core.inner.lock().current_thread = previous_thread;
+
+        core.end_svc_trace(outer);
+
+        let traces = traces.lock();
+        assert_eq!(
+            traces.iter().map(|x| (x.args.len(), x.depth)).collect::<Vec<_>>(),
+            [(2, 1), (0, 0), (5, 0)]
+        );
+        assert_eq!(traces[2].args, [0x100000, 1, 2, 3, 0x44]);
+        assert_eq!(traces[2].arg_strings[0].as_deref(), Some("%d"));
+        assert_eq!(traces[0].args, [0, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_state_digest() -> Result<()> {
+        let mut core = ArmCore::new(false, None)?;
+        core.map(0x100000, 0x20000)?;
+        core.write_bytes(0x100010, &[1, 2, 3, 4])?;
+
+        let mut context = core.save_context();
+        context.r4 = 0x1234;
+        core.restore_context(&context);
+
+        let digest = core.state_digest()?;
+        core.check_state_digest(&digest)?;
+
+        core.write_bytes(0x110000, &[5; 4])?;
+        assert!(core.check_state_digest(&digest).is_err());
+        core.write_bytes(0x110000, &[0; 4])?;
+        core.check_state_digest(&digest)?;
+
+        context.r4 = 0;
+        core.restore_context(&context);
+        assert!(core.check_state_digest(&digest).is_err());
+
+        Ok(())
+    }
 }
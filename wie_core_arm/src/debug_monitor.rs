@@ -0,0 +1,45 @@
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use wie_backend::SymbolResolver;
+use wie_util::{ByteRead, Result};
+
+use crate::context::ArmCoreContext;
+
+/// State of the stopped thread handed to [`DebugMonitor`] commands.
+pub struct DebugMonitorContext<'a> {
+    /// Guest memory as the debugger sees it, with software breakpoints hidden.
+    pub memory: &'a dyn ByteRead,
+    pub registers: ArmCoreContext,
+    /// Calls the host made into guest code with `run_function` on this thread, outermost first.
+    pub entries: Vec<DebugEntry>,
+    pub symbols: Option<SymbolResolver>,
+}
+
+/// A `run_function` call into guest code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DebugEntry {
+    /// Address the host entered guest code at.
+    pub address: u32,
+    /// The guest call that made the host enter, if it entered from an SVC handler, e.g. a `java_jump` trampoline.
+    pub caller: Option<DebugCaller>,
+}
+
+/// A call from guest code into an SVC stub.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DebugCaller {
+    /// Address of the SVC stub called.
+    pub stub: u32,
+    /// Where the call returns to in the calling guest code.
+    pub return_address: u32,
+}
+
+/// `monitor` commands a loader adds to the gdb stub.
+///
+/// Commands run while the target is stopped in the middle of guest code with the core locked, so they must read
+/// guest memory through the context instead of an `ArmCore`.
+pub trait DebugMonitor: Send + Sync {
+    /// `(command, description)` of each command, listed by `monitor help`.
+    fn commands(&self) -> &'static [(&'static str, &'static str)];
+    fn run(&self, command: &str, args: &[&str], context: &DebugMonitorContext<'_>, out: &mut dyn Write) -> Result<()>;
+}
@@ -1,14 +1,18 @@
 mod arm32_cpu;
+mod cached_arm32_cpu;
 #[cfg(not(target_arch = "wasm32"))]
 mod debugged_arm32_cpu;
 
+use alloc::vec::Vec;
+
 use wie_util::{AsAny, Result};
 
-pub use arm32_cpu::Arm32CpuEngine;
+pub use arm32_cpu::{Arm32CpuEngine, MemoryAccess};
+pub use cached_arm32_cpu::CachedArm32CpuEngine;
 #[cfg(not(target_arch = "wasm32"))]
 pub use debugged_arm32_cpu::DebuggedArm32CpuEngine;
 #[cfg(not(target_arch = "wasm32"))]
-pub(crate) use debugged_arm32_cpu::{DebugBreakpointKind, DebugInner, DebugSignal, DebugStopReason};
+pub(crate) use debugged_arm32_cpu::{DebugBreakpointKind, DebugInner, DebugSignal, DebugStopReason, DebugWatchKind};
 
 pub enum EngineRunResult {
     End,
@@ -24,6 +28,8 @@ pub trait ArmEngine: Send + AsAny {
     fn mem_write(&mut self, address: u32, data: &[u8]) -> Result<()>;
     fn mem_read(&mut self, address: u32, size: usize, result: &mut [u8]) -> Result<usize>;
     fn is_mapped(&self, address: u32, size: usize) -> bool;
+    /// Mapped memory as sorted, coalesced `(address, size)` ranges.
+    fn mapped_regions(&self) -> Vec<(u32, usize)>;
 }
 
 #[allow(clippy::enum_variant_names)]
@@ -1,4 +1,4 @@
-use alloc::{boxed::Box, format};
+use alloc::{boxed::Box, format, vec::Vec};
 use core::{array, cell::RefCell};
 
 use arm32_cpu::{Cpu, Memory, Mode, reg};
@@ -7,9 +7,19 @@ use wie_util::{Result, WieError};
 
 use crate::engine::{ArmEngine, ArmRegister, EngineRunResult, MemoryPermission};
 
+/// A data access made by guest code. Instruction fetches are not recorded.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MemoryAccess {
+    pub address: u32,
+    pub size: u32,
+    pub write: bool,
+}
+
 pub struct Arm32CpuEngine {
     cpu: Cpu,
     mem: EmulatedMemory,
+    accesses: Option<Vec<MemoryAccess>>,
+    record_reads: bool,
 }
 
 impl Arm32CpuEngine {
@@ -17,9 +27,37 @@ impl Arm32CpuEngine {
         Self {
             cpu: Cpu::new(),
             mem: EmulatedMemory::new(),
+            accesses: None,
+            record_reads: false,
         }
     }
 
+    /// Starts or stops recording the memory accesses made by `run`.
+    pub fn set_record_accesses(&mut self, record: bool) {
+        self.accesses = record.then(Vec::new);
+        self.record_reads = true;
+    }
+
+    /// Records only the writes made by `run`, for callers that just need to know what memory changed.
+    pub(super) fn set_record_writes(&mut self) {
+        self.accesses = Some(Vec::new());
+        self.record_reads = false;
+    }
+
+    /// Memory accesses recorded since the last call.
+    pub fn take_accesses(&mut self) -> Vec<MemoryAccess> {
+        self.accesses.as_mut().map(core::mem::take).unwrap_or_default()
+    }
+
+    /// Like `take_accesses`, but keeps the buffer around for the next `run`.
+    pub(super) fn drain_accesses(&mut self) -> impl Iterator<Item = MemoryAccess> + '_ {
+        self.accesses.iter_mut().flat_map(|accesses| accesses.drain(..))
+    }
+
+    pub(super) fn memory_mut(&mut self) -> &mut EmulatedMemory {
+        &mut self.mem
+    }
+
     fn is_svc_exception(&self) -> bool {
         self.cpu.reg_get(Mode::User, reg::PC) == 0x08 && (self.cpu.reg_get(Mode::User, reg::CPSR) & 0x1f) == 0x13
     }
@@ -40,15 +78,18 @@ impl Arm32CpuEngine {
 
         Ok(EngineRunResult::Svc { category, lr, spsr })
     }
-}
 
-impl ArmEngine for Arm32CpuEngine {
-    fn run(&mut self, end: u32, mut count: u32) -> Result<EngineRunResult> {
+    /// Runs like [`ArmEngine::run`], taking the instructions run off `count`, but also returns `None` after any
+    /// instruction that leaves the pc and cpsr where `stop` says to.
+    pub(super) fn run_until<F>(&mut self, end: u32, count: &mut u32, mut stop: F) -> Result<Option<EngineRunResult>>
+    where
+        F: FnMut(u32, u32) -> bool,
+    {
         loop {
             let pc = self.cpu.reg_get(Mode::User, reg::PC);
 
             if self.is_svc_exception() {
-                return self.read_svc_result();
+                return self.read_svc_result().map(Some);
             }
 
             if pc < 0x1000 {
@@ -56,25 +97,41 @@ impl ArmEngine for Arm32CpuEngine {
             }
 
             if pc == end {
-                return Ok(EngineRunResult::End);
+                return Ok(Some(EngineRunResult::End));
             }
 
-            if count == 0 {
-                return Ok(EngineRunResult::CountExhausted);
+            if *count == 0 {
+                return Ok(Some(EngineRunResult::CountExhausted));
             }
 
             let mut arm32cpu_memory = self.mem.as_arm32cpu_memory();
+            if let Some(accesses) = &mut self.accesses {
+                arm32cpu_memory = arm32cpu_memory.record_accesses(accesses, pc, self.record_reads);
+            }
 
             if !(self.cpu.step(&mut arm32cpu_memory)) {
                 return Err(WieError::FatalError("Undefined instruction".into()));
             }
-            count -= 1;
+            *count -= 1;
 
             if let Some(x) = arm32cpu_memory.memory_error() {
                 return Err(WieError::InvalidMemoryAccess(x));
             }
+
+            if stop(self.cpu.reg_get(Mode::User, reg::PC), self.cpu.reg_get(Mode::User, reg::CPSR)) {
+                return Ok(None);
+            }
         }
     }
+}
+
+impl ArmEngine for Arm32CpuEngine {
+    fn run(&mut self, end: u32, mut count: u32) -> Result<EngineRunResult> {
+        // never stopped early, so there's always a result
+        let result = self.run_until(end, &mut count, |_, _| false)?;
+
+        Ok(result.unwrap())
+    }
 
     fn reg_write(&mut self, reg: ArmRegister, value: u32) {
         if reg == ArmRegister::PC && value % 2 == 1 {
@@ -107,6 +164,10 @@ impl ArmEngine for Arm32CpuEngine {
     fn is_mapped(&self, address: u32, size: usize) -> bool {
         self.mem.is_mapped(address, size)
     }
+
+    fn mapped_regions(&self) -> Vec<(u32, usize)> {
+        self.mem.mapped_regions()
+    }
 }
 
 impl ArmRegister {
@@ -137,7 +198,7 @@ const TOTAL_MEMORY: u64 = 0x100000000;
 const PAGE_SIZE: usize = 0x10000;
 const PAGE_MASK: u32 = (PAGE_SIZE - 1) as _;
 
-struct EmulatedMemory {
+pub(super) struct EmulatedMemory {
     pages: [Option<Box<[u8; PAGE_SIZE]>>; (TOTAL_MEMORY / PAGE_SIZE as u64) as usize],
 }
 
@@ -148,7 +209,7 @@ impl EmulatedMemory {
         }
     }
 
-    fn as_arm32cpu_memory(&mut self) -> Arm32CpuMemory<'_> {
+    pub(super) fn as_arm32cpu_memory(&mut self) -> Arm32CpuMemory<'_> {
         Arm32CpuMemory::new(self)
     }
 
@@ -164,7 +225,7 @@ impl EmulatedMemory {
         }
     }
 
-    fn read_range(&self, address: u32, size: usize, result: &mut [u8]) -> Result<usize> {
+    pub(super) fn read_range(&self, address: u32, size: usize, result: &mut [u8]) -> Result<usize> {
         let mut remaining_size = size;
         let mut current_address = address;
 
@@ -220,11 +281,31 @@ impl EmulatedMemory {
 
         true
     }
+
+    fn mapped_regions(&self) -> Vec<(u32, usize)> {
+        let mut result: Vec<(u32, usize)> = Vec::new();
+
+        for (index, page) in self.pages.iter().enumerate() {
+            if page.is_none() {
+                continue;
+            }
+
+            let address = (index * PAGE_SIZE) as u32;
+            match result.last_mut() {
+                Some((start, size)) if *start as u64 + *size as u64 == address as u64 => *size += PAGE_SIZE,
+                _ => result.push((address, PAGE_SIZE)),
+            }
+        }
+
+        result
+    }
 }
 
-struct Arm32CpuMemory<'a> {
+pub(super) struct Arm32CpuMemory<'a> {
     emulated_memory: &'a mut EmulatedMemory,
     memory_error: RefCell<Option<u32>>,
+    // where to record, the pc whose fetch to skip, and whether to record reads
+    accesses: Option<(&'a mut Vec<MemoryAccess>, u32, bool)>,
 }
 
 impl<'a> Arm32CpuMemory<'a> {
@@ -232,13 +313,31 @@ impl<'a> Arm32CpuMemory<'a> {
         Self {
             emulated_memory,
             memory_error: RefCell::new(None),
+            accesses: None,
         }
     }
 
-    fn memory_error(&self) -> Option<u32> {
+    /// Records accesses into `accesses`, except the fetch of the instruction at `pc`, and reads only if `reads` is set.
+    fn record_accesses(mut self, accesses: &'a mut Vec<MemoryAccess>, pc: u32, reads: bool) -> Self {
+        self.accesses = Some((accesses, pc, reads));
+        self
+    }
+
+    pub(super) fn memory_error(&self) -> Option<u32> {
         *self.memory_error.borrow()
     }
 
+    fn record(&mut self, address: u32, size: u32, write: bool) {
+        if let Some((accesses, pc, reads)) = &mut self.accesses {
+            // reads from the instruction being executed are the fetch
+            if !write && (!*reads || (*pc..*pc + 4).contains(&address)) {
+                return;
+            }
+
+            accesses.push(MemoryAccess { address, size, write });
+        }
+    }
+
     fn get_page(&mut self, addr: u32) -> Option<&mut [u8; PAGE_SIZE]> {
         let page_address = addr & !PAGE_MASK;
         let page_data = self.emulated_memory.pages[page_address as usize / PAGE_SIZE].as_mut();
@@ -254,6 +353,8 @@ impl<'a> Arm32CpuMemory<'a> {
 
 impl Memory for Arm32CpuMemory<'_> {
     fn r8(&mut self, addr: u32) -> u8 {
+        self.record(addr, 1, false);
+
         let offset = addr & PAGE_MASK;
 
         let page = self.get_page(addr);
@@ -267,6 +368,8 @@ impl Memory for Arm32CpuMemory<'_> {
     }
 
     fn r16(&mut self, addr: u32) -> u16 {
+        self.record(addr, 2, false);
+
         let offset = addr & PAGE_MASK;
 
         let page = self.get_page(addr);
@@ -280,6 +383,8 @@ impl Memory for Arm32CpuMemory<'_> {
     }
 
     fn r32(&mut self, addr: u32) -> u32 {
+        self.record(addr, 4, false);
+
         let offset = addr & PAGE_MASK;
 
         let page = self.get_page(addr);
@@ -295,6 +400,8 @@ impl Memory for Arm32CpuMemory<'_> {
     }
 
     fn w8(&mut self, addr: u32, val: u8) {
+        self.record(addr, 1, true);
+
         let offset = addr & PAGE_MASK;
 
         let page = self.get_page(addr);
@@ -308,6 +415,8 @@ impl Memory for Arm32CpuMemory<'_> {
     }
 
     fn w16(&mut self, addr: u32, val: u16) {
+        self.record(addr, 2, true);
+
         let offset = addr & PAGE_MASK;
 
         let page = self.get_page(addr);
@@ -322,6 +431,8 @@ impl Memory for Arm32CpuMemory<'_> {
     }
 
     fn w32(&mut self, addr: u32, val: u32) {
+        self.record(addr, 4, true);
+
         let offset = addr & PAGE_MASK;
 
         let page = self.get_page(addr);
@@ -340,9 +451,11 @@ impl Memory for Arm32CpuMemory<'_> {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec::Vec;
+
     use arm32_cpu::Memory;
 
-    use super::EmulatedMemory;
+    use super::{EmulatedMemory, MemoryAccess};
 
     #[test]
     fn test_memory_basic() {
@@ -387,6 +500,18 @@ mod tests {
         assert_eq!(r32, 0x12345678);
     }
 
+    #[test]
+    fn test_memory_mapped_regions() {
+        let mut memory = EmulatedMemory::new();
+
+        memory.map(0x10000, 0x1000);
+        memory.map(0x30000, 0x10000);
+        memory.map(0x40000, 0x10000);
+        memory.map(0x60000, 0x10000);
+
+        assert_eq!(memory.mapped_regions(), [(0x10000, 0x10000), (0x30000, 0x20000), (0x60000, 0x10000)]);
+    }
+
     #[test]
     fn test_memory_unmapped_read() {
         let mut memory = EmulatedMemory::new();
@@ -405,4 +530,54 @@ mod tests {
 
         assert!(memory.write_range(0x1f500, &[12; 0x1000]).is_err());
     }
+
+    #[test]
+    fn test_record_accesses() {
+        let mut memory = EmulatedMemory::new();
+        memory.map(0x10000, 0x1000);
+
+        let mut accesses = Vec::new();
+        let mut arm32cpu_memory = memory.as_arm32cpu_memory().record_accesses(&mut accesses, 0x10000, true);
+
+        arm32cpu_memory.r16(0x10000); // instruction fetch
+        arm32cpu_memory.w32(0x10010, 1);
+        arm32cpu_memory.r8(0x10011);
+
+        assert_eq!(
+            accesses,
+            [
+                MemoryAccess {
+                    address: 0x10010,
+                    size: 4,
+                    write: true
+                },
+                MemoryAccess {
+                    address: 0x10011,
+                    size: 1,
+                    write: false
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_writes_only() {
+        let mut memory = EmulatedMemory::new();
+        memory.map(0x10000, 0x1000);
+
+        let mut accesses = Vec::new();
+        let mut arm32cpu_memory = memory.as_arm32cpu_memory().record_accesses(&mut accesses, 0x10000, false);
+
+        arm32cpu_memory.r32(0x10020);
+        arm32cpu_memory.w16(0x10010, 1);
+
+        assert_eq!(
+            accesses,
+            [MemoryAccess {
+                address: 0x10010,
+                size: 2,
+                write: true
+            }]
+        );
+    }
 }
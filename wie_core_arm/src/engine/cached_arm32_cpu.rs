@@ -0,0 +1,1046 @@
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+use arm32_cpu::Memory;
+
+use wie_util::{Result, WieError};
+
+use super::{
+    Arm32CpuEngine, ArmEngine, ArmRegister, EngineRunResult, MemoryAccess, MemoryPermission,
+    arm32_cpu::{Arm32CpuMemory, EmulatedMemory},
+};
+
+const MAX_BLOCK_OPS: usize = 64;
+// the longest op is 4 bytes, so a block can't start further than this before a page it overlaps
+const MAX_BLOCK_BYTES: u32 = MAX_BLOCK_OPS as u32 * 4;
+const CODE_PAGE_SHIFT: u32 = 12;
+const CODE_PAGE_COUNT: usize = 1 << (32 - CODE_PAGE_SHIFT);
+
+const FLAG_N: u32 = 1 << 31;
+const FLAG_Z: u32 = 1 << 30;
+const FLAG_C: u32 = 1 << 29;
+const FLAG_V: u32 = 1 << 28;
+const FLAG_T: u32 = 1 << 5;
+
+const REGISTERS: [ArmRegister; 16] = [
+    ArmRegister::R0,
+    ArmRegister::R1,
+    ArmRegister::R2,
+    ArmRegister::R3,
+    ArmRegister::R4,
+    ArmRegister::R5,
+    ArmRegister::R6,
+    ArmRegister::R7,
+    ArmRegister::R8,
+    ArmRegister::SB,
+    ArmRegister::SL,
+    ArmRegister::FP,
+    ArmRegister::IP,
+    ArmRegister::SP,
+    ArmRegister::LR,
+    ArmRegister::PC,
+];
+
+/// Runs user mode thumb code from cached, pre-decoded blocks and everything else on an [`Arm32CpuEngine`].
+///
+/// A block runs up to the first branch or the first instruction the fast path doesn't cover. The interpreter then runs
+/// until the pc reaches thumb code a block can start at again. Blocks are dropped when the host or the guest writes to
+/// a page they were decoded from.
+pub struct CachedArm32CpuEngine {
+    interpreter: Arm32CpuEngine,
+    blocks: BTreeMap<u32, Block>,
+    code_pages: CodePages,
+    written: Vec<MemoryAccess>,
+}
+
+impl CachedArm32CpuEngine {
+    pub fn new() -> Self {
+        let mut interpreter = Arm32CpuEngine::new();
+        // to catch code written by instructions the interpreter runs
+        interpreter.set_record_writes();
+
+        Self {
+            interpreter,
+            blocks: BTreeMap::new(),
+            code_pages: CodePages(vec![0; CODE_PAGE_COUNT / 64]),
+            written: Vec::new(),
+        }
+    }
+
+    // returns the number of instructions run, 0 if the interpreter has to step the one at `pc`
+    fn run_block(&mut self, pc: u32, end: u32, count: u32) -> Result<u32> {
+        if !self.blocks.contains_key(&pc) {
+            let block = Block::decode(self.interpreter.memory_mut(), pc);
+            self.code_pages.mark(pc, block.end);
+            self.blocks.insert(pc, block);
+        }
+
+        let mut state = ThumbState::load(&self.interpreter);
+        let block = &self.blocks[&pc];
+        let mut memory = self.interpreter.memory_mut().as_arm32cpu_memory();
+
+        let mut executed = 0;
+        let mut written_code = None;
+        let mut memory_error = None;
+        for op in &block.ops {
+            if state.r[15] == end || executed + op.instructions() > count {
+                break;
+            }
+
+            match op.execute(&mut state, &mut memory) {
+                Step::Next => state.r[15] += op.size(),
+                Step::Jump(target) => state.r[15] = target,
+                Step::Stored(address, size) => {
+                    state.r[15] += op.size();
+                    if self.code_pages.overlaps(address, size) {
+                        written_code = Some((address, size));
+                    }
+                }
+                Step::Fallback => break,
+            }
+            executed += op.instructions();
+
+            memory_error = memory.memory_error();
+            if memory_error.is_some() || written_code.is_some() {
+                break;
+            }
+        }
+
+        state.store(&mut self.interpreter);
+
+        if let Some((address, size)) = written_code {
+            self.invalidate(address, size);
+        }
+        if let Some(address) = memory_error {
+            return Err(WieError::InvalidMemoryAccess(address));
+        }
+
+        Ok(executed)
+    }
+
+    fn invalidate_written_code(&mut self) {
+        self.written.extend(self.interpreter.drain_accesses());
+
+        for i in 0..self.written.len() {
+            let access = self.written[i];
+            self.invalidate(access.address, access.size);
+        }
+        self.written.clear();
+    }
+
+    fn invalidate(&mut self, address: u32, size: u32) {
+        for page in CodePages::pages(address, size) {
+            if !self.code_pages.take(page) {
+                continue;
+            }
+
+            let page_start = page << CODE_PAGE_SHIFT;
+            let page_last = page_start | ((1 << CODE_PAGE_SHIFT) - 1);
+            let stale = self
+                .blocks
+                .range(page_start.saturating_sub(MAX_BLOCK_BYTES)..=page_last)
+                .filter(|(_, block)| block.end > page_start)
+                .map(|(&start, _)| start)
+                .collect::<Vec<_>>();
+            for start in stale {
+                self.blocks.remove(&start);
+            }
+        }
+    }
+}
+
+impl ArmEngine for CachedArm32CpuEngine {
+    fn run(&mut self, end: u32, mut count: u32) -> Result<EngineRunResult> {
+        loop {
+            let pc = self.interpreter.reg_read(ArmRegister::PC);
+            let cpsr = self.interpreter.reg_read(ArmRegister::Cpsr);
+
+            // svc exceptions, the end address and bad pcs are all left to the interpreter's checks
+            if is_user_thumb(cpsr) && count > 0 && pc != end && pc >= 0x1000 {
+                let executed = self.run_block(pc, end, count)?;
+                if executed > 0 {
+                    count -= executed;
+                    continue;
+                }
+            }
+
+            // at least the instruction the block couldn't run, then up to where a block can take over
+            let blocks = &self.blocks;
+            let result = self.interpreter.run_until(end, &mut count, |pc, cpsr| {
+                is_user_thumb(cpsr) && pc >= 0x1000 && blocks.get(&pc).is_none_or(|block| !block.ops.is_empty())
+            });
+            self.invalidate_written_code();
+
+            if let Some(result) = result? {
+                return Ok(result);
+            }
+        }
+    }
+
+    fn reg_write(&mut self, reg: ArmRegister, value: u32) {
+        self.interpreter.reg_write(reg, value)
+    }
+
+    fn reg_read(&self, reg: ArmRegister) -> u32 {
+        self.interpreter.reg_read(reg)
+    }
+
+    fn mem_map(&mut self, address: u32, size: usize, permission: MemoryPermission) {
+        self.interpreter.mem_map(address, size, permission)
+    }
+
+    fn mem_write(&mut self, address: u32, data: &[u8]) -> Result<()> {
+        self.interpreter.mem_write(address, data)?;
+        self.invalidate(address, data.len() as u32);
+
+        Ok(())
+    }
+
+    fn mem_read(&mut self, address: u32, size: usize, result: &mut [u8]) -> Result<usize> {
+        self.interpreter.mem_read(address, size, result)
+    }
+
+    fn is_mapped(&self, address: u32, size: usize) -> bool {
+        self.interpreter.is_mapped(address, size)
+    }
+
+    fn mapped_regions(&self) -> Vec<(u32, usize)> {
+        self.interpreter.mapped_regions()
+    }
+}
+
+fn is_user_thumb(cpsr: u32) -> bool {
+    cpsr & FLAG_T != 0 && matches!(cpsr & 0x1f, 0x10 | 0x1f)
+}
+
+// one bit per page blocks were decoded from
+struct CodePages(Vec<u64>);
+
+impl CodePages {
+    fn mark(&mut self, start: u32, end: u32) {
+        for page in Self::pages(start, end - start) {
+            self.0[page as usize / 64] |= 1 << (page % 64);
+        }
+    }
+
+    fn contains(&self, page: u32) -> bool {
+        self.0[page as usize / 64] & (1 << (page % 64)) != 0
+    }
+
+    // clears the page, returning whether it was marked
+    fn take(&mut self, page: u32) -> bool {
+        let marked = self.contains(page);
+        self.0[page as usize / 64] &= !(1 << (page % 64));
+
+        marked
+    }
+
+    fn overlaps(&self, address: u32, size: u32) -> bool {
+        Self::pages(address, size).any(|page| self.contains(page))
+    }
+
+    fn pages(address: u32, size: u32) -> impl Iterator<Item = u32> {
+        let last = (address as u64 + size.max(1) as u64 - 1).min(u32::MAX as u64) as u32;
+
+        address >> CODE_PAGE_SHIFT..=last >> CODE_PAGE_SHIFT
+    }
+}
+
+struct Block {
+    ops: Vec<Op>,
+    // exclusive; at least past the first instruction, so blocks the interpreter runs are invalidated too
+    end: u32,
+}
+
+impl Block {
+    fn decode(memory: &EmulatedMemory, start: u32) -> Self {
+        let read_halfword = |address: u32| {
+            let mut buf = [0; 2];
+            memory.read_range(address, 2, &mut buf).ok().map(|_| u16::from_le_bytes(buf))
+        };
+
+        let mut ops = Vec::new();
+        let mut pc = start;
+        while ops.len() < MAX_BLOCK_OPS {
+            let Some(instruction) = read_halfword(pc) else {
+                break;
+            };
+            let Some(op) = Op::decode(instruction, pc.checked_add(2).and_then(read_halfword), pc) else {
+                break;
+            };
+
+            ops.push(op);
+            pc += op.size();
+
+            if op.is_branch() {
+                break;
+            }
+        }
+
+        Self {
+            ops,
+            end: pc.max(start.saturating_add(2)),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Shift {
+    Lsl,
+    Lsr,
+    Asr,
+    Ror,
+}
+
+#[derive(Clone, Copy)]
+enum AluOp {
+    And,
+    Eor,
+    Shift(Shift),
+    Adc,
+    Sbc,
+    Tst,
+    Neg,
+    Cmp,
+    Cmn,
+    Orr,
+    Mul,
+    Bic,
+    Mvn,
+}
+
+#[derive(Clone, Copy)]
+enum Width {
+    Word,
+    Half,
+    Byte,
+    SignedHalf,
+    SignedByte,
+}
+
+#[derive(Clone, Copy)]
+enum Operand {
+    Reg(u8),
+    Imm(u32),
+}
+
+/// A decoded thumb instruction. Addresses relative to the pc are resolved at decode time.
+#[derive(Clone, Copy)]
+enum Op {
+    ShiftImm { shift: Shift, rd: u8, rm: u8, amount: u32 },
+    Add { rd: u8, rn: u8, operand: Operand },
+    Sub { rd: u8, rn: u8, operand: Operand },
+    Cmp { rn: u8, operand: Operand },
+    MovImm { rd: u8, imm: u32 },
+    Alu { op: AluOp, rd: u8, rm: u8 },
+    // high register forms, which leave the flags alone
+    AddHi { rd: u8, rm: u8 },
+    MovHi { rd: u8, rm: u8 },
+    AddImm { rd: u8, rn: u8, imm: u32 },
+    SetReg { rd: u8, value: u32 },
+    Load { width: Width, rd: u8, rb: u8, offset: Operand },
+    Store { width: Width, rd: u8, rb: u8, offset: Operand },
+    LoadLiteral { rd: u8, address: u32 },
+    Push { registers: u8, lr: bool },
+    Pop { registers: u8 },
+    Stmia { rb: u8, registers: u8 },
+    Ldmia { rb: u8, registers: u8 },
+    Bx { rm: u8 },
+    BranchCond { cond: u8, target: u32 },
+    Branch { target: u32 },
+    // both halves of the bl pair
+    BranchLink { target: u32, link: u32 },
+}
+
+enum Step {
+    Next,
+    Jump(u32),
+    Stored(u32, u32),
+    // not run, the interpreter has to handle this one
+    Fallback,
+}
+
+impl Op {
+    fn decode(instruction: u16, next: Option<u16>, pc: u32) -> Option<Self> {
+        let low = (instruction & 7) as u8;
+        let mid = ((instruction >> 3) & 7) as u8;
+        let high = ((instruction >> 8) & 7) as u8;
+        let imm5 = ((instruction >> 6) & 0x1f) as u32;
+        let imm8 = (instruction & 0xff) as u32;
+        let literal_base = (pc + 4) & !3;
+
+        let op = match instruction >> 13 {
+            0b000 if (instruction >> 11) & 3 == 3 => {
+                let operand = if instruction & (1 << 10) != 0 {
+                    Operand::Imm(imm5 & 7)
+                } else {
+                    Operand::Reg((imm5 & 7) as u8)
+                };
+
+                if instruction & (1 << 9) != 0 {
+                    Op::Sub { rd: low, rn: mid, operand }
+                } else {
+                    Op::Add { rd: low, rn: mid, operand }
+                }
+            }
+            0b000 => {
+                let (shift, amount) = match (instruction >> 11) & 3 {
+                    0 => (Shift::Lsl, imm5),
+                    1 => (Shift::Lsr, if imm5 == 0 { 32 } else { imm5 }),
+                    _ => (Shift::Asr, if imm5 == 0 { 32 } else { imm5 }),
+                };
+
+                Op::ShiftImm {
+                    shift,
+                    rd: low,
+                    rm: mid,
+                    amount,
+                }
+            }
+            0b001 => match (instruction >> 11) & 3 {
+                0 => Op::MovImm { rd: high, imm: imm8 },
+                1 => Op::Cmp {
+                    rn: high,
+                    operand: Operand::Imm(imm8),
+                },
+                2 => Op::Add {
+                    rd: high,
+                    rn: high,
+                    operand: Operand::Imm(imm8),
+                },
+                _ => Op::Sub {
+                    rd: high,
+                    rn: high,
+                    operand: Operand::Imm(imm8),
+                },
+            },
+            0b010 if instruction & (1 << 12) == 0 => match (instruction >> 10) & 3 {
+                0 => {
+                    let op = match (instruction >> 6) & 0xf {
+                        0x0 => AluOp::And,
+                        0x1 => AluOp::Eor,
+                        0x2 => AluOp::Shift(Shift::Lsl),
+                        0x3 => AluOp::Shift(Shift::Lsr),
+                        0x4 => AluOp::Shift(Shift::Asr),
+                        0x5 => AluOp::Adc,
+                        0x6 => AluOp::Sbc,
+                        0x7 => AluOp::Shift(Shift::Ror),
+                        0x8 => AluOp::Tst,
+                        0x9 => AluOp::Neg,
+                        0xa => AluOp::Cmp,
+                        0xb => AluOp::Cmn,
+                        0xc => AluOp::Orr,
+                        0xd => AluOp::Mul,
+                        0xe => AluOp::Bic,
+                        _ => AluOp::Mvn,
+                    };
+
+                    Op::Alu { op, rd: low, rm: mid }
+                }
+                1 => {
+                    let rd = low | (((instruction >> 7) & 1) as u8) << 3;
+                    let rm = mid | (((instruction >> 6) & 1) as u8) << 3;
+
+                    // writes to the pc and blx are left to the interpreter, for its interworking rules
+                    match (instruction >> 8) & 3 {
+                        0 if rd != 15 => Op::AddHi { rd, rm },
+                        1 => Op::Cmp {
+                            rn: rd,
+                            operand: Operand::Reg(rm),
+                        },
+                        2 if rd != 15 => Op::MovHi { rd, rm },
+                        3 if rd < 8 && rm != 15 => Op::Bx { rm },
+                        _ => return None,
+                    }
+                }
+                _ => Op::LoadLiteral {
+                    rd: high,
+                    address: literal_base + imm8 * 4,
+                },
+            },
+            0b010 => {
+                let offset = Operand::Reg(((instruction >> 6) & 7) as u8);
+                let (load, width) = match (instruction >> 9) & 7 {
+                    0b000 => (false, Width::Word),
+                    0b010 => (false, Width::Byte),
+                    0b100 => (true, Width::Word),
+                    0b110 => (true, Width::Byte),
+                    0b001 => (false, Width::Half),
+                    0b011 => (true, Width::SignedByte),
+                    0b101 => (true, Width::Half),
+                    _ => (true, Width::SignedHalf),
+                };
+
+                if load {
+                    Op::Load {
+                        width,
+                        rd: low,
+                        rb: mid,
+                        offset,
+                    }
+                } else {
+                    Op::Store {
+                        width,
+                        rd: low,
+                        rb: mid,
+                        offset,
+                    }
+                }
+            }
+            0b011 | 0b100 => {
+                let (width, rb, rd, offset) = match (instruction >> 12) & 0xf {
+                    0b0110 => (Width::Word, mid, low, imm5 * 4),
+                    0b0111 => (Width::Byte, mid, low, imm5),
+                    0b1000 => (Width::Half, mid, low, imm5 * 2),
+                    _ => (Width::Word, 13, high, imm8 * 4),
+                };
+                let offset = Operand::Imm(offset);
+
+                if instruction & (1 << 11) != 0 {
+                    Op::Load { width, rd, rb, offset }
+                } else {
+                    Op::Store { width, rd, rb, offset }
+                }
+            }
+            0b101 if instruction & (1 << 12) == 0 => {
+                if instruction & (1 << 11) != 0 {
+                    Op::AddImm {
+                        rd: high,
+                        rn: 13,
+                        imm: imm8 * 4,
+                    }
+                } else {
+                    Op::SetReg {
+                        rd: high,
+                        value: literal_base + imm8 * 4,
+                    }
+                }
+            }
+            0b101 => match (instruction >> 8) & 0xf {
+                0b0000 => {
+                    let offset = (instruction & 0x7f) as u32 * 4;
+                    let imm = if instruction & (1 << 7) != 0 { offset.wrapping_neg() } else { offset };
+
+                    Op::AddImm { rd: 13, rn: 13, imm }
+                }
+                0b0100 | 0b0101 if instruction & 0x1ff != 0 => Op::Push {
+                    registers: imm8 as u8,
+                    lr: instruction & (1 << 8) != 0,
+                },
+                // pop {pc} interworks from armv5 on, so it's left to the interpreter
+                0b1100 if imm8 != 0 => Op::Pop { registers: imm8 as u8 },
+                _ => return None,
+            },
+            0b110 if instruction & (1 << 12) == 0 => {
+                // writeback of a base that's also in the list is unpredictable
+                if imm8 == 0 || imm8 & (1 << high) != 0 {
+                    return None;
+                }
+
+                if instruction & (1 << 11) != 0 {
+                    Op::Ldmia {
+                        rb: high,
+                        registers: imm8 as u8,
+                    }
+                } else {
+                    Op::Stmia {
+                        rb: high,
+                        registers: imm8 as u8,
+                    }
+                }
+            }
+            0b110 => {
+                let cond = ((instruction >> 8) & 0xf) as u8;
+                if cond >= 0xe {
+                    return None;
+                }
+
+                Op::BranchCond {
+                    cond,
+                    target: (pc + 4).wrapping_add(((imm8 as u8 as i8 as i32) << 1) as u32),
+                }
+            }
+            _ => match (instruction >> 11) & 3 {
+                0 => Op::Branch {
+                    target: (pc + 4).wrapping_add(sign_extend(instruction as u32 & 0x7ff, 11) << 1),
+                },
+                2 => {
+                    let next = next.filter(|next| next >> 11 == 0b11111)?;
+                    let upper = (pc + 4).wrapping_add(sign_extend(instruction as u32 & 0x7ff, 11) << 12);
+
+                    Op::BranchLink {
+                        target: upper.wrapping_add((next as u32 & 0x7ff) << 1),
+                        link: (pc + 4) | 1,
+                    }
+                }
+                _ => return None,
+            },
+        };
+
+        Some(op)
+    }
+
+    fn size(&self) -> u32 {
+        match self {
+            Op::BranchLink { .. } => 4,
+            _ => 2,
+        }
+    }
+
+    // the interpreter steps the bl halves separately
+    fn instructions(&self) -> u32 {
+        self.size() / 2
+    }
+
+    fn is_branch(&self) -> bool {
+        matches!(self, Op::Bx { .. } | Op::BranchCond { .. } | Op::Branch { .. } | Op::BranchLink { .. })
+    }
+
+    fn execute(&self, state: &mut ThumbState, memory: &mut Arm32CpuMemory<'_>) -> Step {
+        match *self {
+            Op::ShiftImm { shift, rd, rm, amount } => {
+                let result = state.shift(shift, state.r[rm as usize], amount);
+                state.set_nz(result);
+                state.r[rd as usize] = result;
+            }
+            Op::Add { rd, rn, operand } => {
+                let operand = state.operand(operand);
+                state.r[rd as usize] = state.add_with_carry(state.r[rn as usize], operand, false);
+            }
+            Op::Sub { rd, rn, operand } => {
+                let operand = state.operand(operand);
+                state.r[rd as usize] = state.add_with_carry(state.r[rn as usize], !operand, true);
+            }
+            Op::Cmp { rn, operand } => {
+                let (rn, operand) = (state.read(rn), state.operand(operand));
+                state.add_with_carry(rn, !operand, true);
+            }
+            Op::MovImm { rd, imm } => {
+                state.set_nz(imm);
+                state.r[rd as usize] = imm;
+            }
+            Op::Alu { op, rd, rm } => {
+                let (a, b) = (state.r[rd as usize], state.r[rm as usize]);
+                let result = match op {
+                    AluOp::And | AluOp::Tst => a & b,
+                    AluOp::Eor => a ^ b,
+                    AluOp::Orr => a | b,
+                    AluOp::Bic => a & !b,
+                    AluOp::Mvn => !b,
+                    AluOp::Mul => a.wrapping_mul(b),
+                    AluOp::Shift(shift) => state.shift(shift, a, b & 0xff),
+                    AluOp::Adc => state.add_with_carry(a, b, state.flag(FLAG_C)),
+                    AluOp::Sbc => state.add_with_carry(a, !b, state.flag(FLAG_C)),
+                    AluOp::Neg => state.add_with_carry(0, !b, true),
+                    AluOp::Cmp => state.add_with_carry(a, !b, true),
+                    AluOp::Cmn => state.add_with_carry(a, b, false),
+                };
+
+                if !matches!(op, AluOp::Adc | AluOp::Sbc | AluOp::Neg | AluOp::Cmp | AluOp::Cmn) {
+                    state.set_nz(result);
+                }
+                if !matches!(op, AluOp::Tst | AluOp::Cmp | AluOp::Cmn) {
+                    state.r[rd as usize] = result;
+                }
+            }
+            Op::AddHi { rd, rm } => state.r[rd as usize] = state.read(rd).wrapping_add(state.read(rm)),
+            Op::MovHi { rd, rm } => state.r[rd as usize] = state.read(rm),
+            Op::AddImm { rd, rn, imm } => state.r[rd as usize] = state.r[rn as usize].wrapping_add(imm),
+            Op::SetReg { rd, value } => state.r[rd as usize] = value,
+            Op::Load { width, rd, rb, offset } => {
+                let address = state.r[rb as usize].wrapping_add(state.operand(offset));
+                if !width.is_aligned(address) {
+                    return Step::Fallback;
+                }
+
+                state.r[rd as usize] = width.load(memory, address);
+            }
+            Op::Store { width, rd, rb, offset } => {
+                let address = state.r[rb as usize].wrapping_add(state.operand(offset));
+                if !width.is_aligned(address) {
+                    return Step::Fallback;
+                }
+
+                return Step::Stored(address, width.store(memory, address, state.r[rd as usize]));
+            }
+            Op::LoadLiteral { rd, address } => state.r[rd as usize] = memory.r32(address),
+            Op::Push { registers, lr } => {
+                let size = (registers.count_ones() + lr as u32) * 4;
+                let start = state.r[13].wrapping_sub(size);
+                if !start.is_multiple_of(4) {
+                    return Step::Fallback;
+                }
+
+                let mut address = start;
+                for reg in (0..8).filter(|reg| registers & (1 << reg) != 0).chain(lr.then_some(14)) {
+                    memory.w32(address, state.r[reg]);
+                    address += 4;
+                }
+                state.r[13] = start;
+
+                return Step::Stored(start, size);
+            }
+            Op::Pop { registers } => {
+                let mut address = state.r[13];
+                if !address.is_multiple_of(4) {
+                    return Step::Fallback;
+                }
+
+                for reg in (0..8).filter(|reg| registers & (1 << reg) != 0) {
+                    state.r[reg] = memory.r32(address);
+                    address += 4;
+                }
+                state.r[13] = address;
+            }
+            Op::Stmia { rb, registers } => {
+                let start = state.r[rb as usize];
+                if !start.is_multiple_of(4) {
+                    return Step::Fallback;
+                }
+
+                let mut address = start;
+                for reg in (0..8).filter(|reg| registers & (1 << reg) != 0) {
+                    memory.w32(address, state.r[reg]);
+                    address += 4;
+                }
+                state.r[rb as usize] = address;
+
+                return Step::Stored(start, address - start);
+            }
+            Op::Ldmia { rb, registers } => {
+                let mut address = state.r[rb as usize];
+                if !address.is_multiple_of(4) {
+                    return Step::Fallback;
+                }
+
+                for reg in (0..8).filter(|reg| registers & (1 << reg) != 0) {
+                    state.r[reg] = memory.r32(address);
+                    address += 4;
+                }
+                state.r[rb as usize] = address;
+            }
+            Op::Bx { rm } => {
+                let target = state.r[rm as usize];
+                if target & 1 != 0 {
+                    return Step::Jump(target & !1);
+                }
+
+                state.cpsr &= !FLAG_T;
+                return Step::Jump(target & !3);
+            }
+            Op::BranchCond { cond, target } => {
+                if state.condition_passed(cond) {
+                    return Step::Jump(target);
+                }
+            }
+            Op::Branch { target } => return Step::Jump(target),
+            Op::BranchLink { target, link } => {
+                state.r[14] = link;
+                return Step::Jump(target);
+            }
+        }
+
+        Step::Next
+    }
+}
+
+impl Width {
+    fn is_aligned(&self, address: u32) -> bool {
+        match self {
+            Width::Word => address.is_multiple_of(4),
+            Width::Half | Width::SignedHalf => address.is_multiple_of(2),
+            Width::Byte | Width::SignedByte => true,
+        }
+    }
+
+    fn load(&self, memory: &mut Arm32CpuMemory<'_>, address: u32) -> u32 {
+        match self {
+            Width::Word => memory.r32(address),
+            Width::Half => memory.r16(address) as u32,
+            Width::Byte => memory.r8(address) as u32,
+            Width::SignedHalf => memory.r16(address) as i16 as u32,
+            Width::SignedByte => memory.r8(address) as i8 as u32,
+        }
+    }
+
+    // returns the number of bytes written
+    fn store(&self, memory: &mut Arm32CpuMemory<'_>, address: u32, value: u32) -> u32 {
+        match self {
+            Width::Word => {
+                memory.w32(address, value);
+                4
+            }
+            Width::Half | Width::SignedHalf => {
+                memory.w16(address, value as u16);
+                2
+            }
+            Width::Byte | Width::SignedByte => {
+                memory.w8(address, value as u8);
+                1
+            }
+        }
+    }
+}
+
+// registers of a block being run; r[15] is the address of the current instruction
+struct ThumbState {
+    r: [u32; 16],
+    cpsr: u32,
+}
+
+impl ThumbState {
+    fn load(engine: &Arm32CpuEngine) -> Self {
+        Self {
+            r: REGISTERS.map(|reg| engine.reg_read(reg)),
+            cpsr: engine.reg_read(ArmRegister::Cpsr),
+        }
+    }
+
+    fn store(&self, engine: &mut Arm32CpuEngine) {
+        engine.reg_write(ArmRegister::Cpsr, self.cpsr);
+        for (reg, value) in REGISTERS.into_iter().zip(self.r) {
+            engine.reg_write(reg, value);
+        }
+    }
+
+    fn read(&self, reg: u8) -> u32 {
+        if reg == 15 { self.r[15] + 4 } else { self.r[reg as usize] }
+    }
+
+    fn operand(&self, operand: Operand) -> u32 {
+        match operand {
+            Operand::Reg(reg) => self.read(reg),
+            Operand::Imm(imm) => imm,
+        }
+    }
+
+    fn flag(&self, flag: u32) -> bool {
+        self.cpsr & flag != 0
+    }
+
+    fn set_flag(&mut self, flag: u32, value: bool) {
+        if value {
+            self.cpsr |= flag;
+        } else {
+            self.cpsr &= !flag;
+        }
+    }
+
+    fn set_nz(&mut self, value: u32) {
+        self.set_flag(FLAG_N, value & (1 << 31) != 0);
+        self.set_flag(FLAG_Z, value == 0);
+    }
+
+    // subtraction is `a + !b + 1`, which leaves the carry set when nothing was borrowed
+    fn add_with_carry(&mut self, a: u32, b: u32, carry: bool) -> u32 {
+        let wide = a as u64 + b as u64 + carry as u64;
+        let result = wide as u32;
+
+        self.set_nz(result);
+        self.set_flag(FLAG_C, wide > u32::MAX as u64);
+        self.set_flag(FLAG_V, ((a ^ result) & (b ^ result)) & (1 << 31) != 0);
+
+        result
+    }
+
+    // sets the carry out; a zero amount leaves the value and carry as they are
+    fn shift(&mut self, shift: Shift, value: u32, amount: u32) -> u32 {
+        if amount == 0 {
+            return value;
+        }
+
+        let bit = |n: u32| (value >> n) & 1 != 0;
+        let (result, carry) = match shift {
+            Shift::Lsl if amount < 32 => (value << amount, bit(32 - amount)),
+            Shift::Lsl => (0, amount == 32 && bit(0)),
+            Shift::Lsr if amount < 32 => (value >> amount, bit(amount - 1)),
+            Shift::Lsr => (0, amount == 32 && bit(31)),
+            Shift::Asr if amount < 32 => (((value as i32) >> amount) as u32, bit(amount - 1)),
+            Shift::Asr => (((value as i32) >> 31) as u32, bit(31)),
+            Shift::Ror => {
+                let result = value.rotate_right(amount % 32);
+                (result, result & (1 << 31) != 0)
+            }
+        };
+        self.set_flag(FLAG_C, carry);
+
+        result
+    }
+
+    fn condition_passed(&self, cond: u8) -> bool {
+        let (n, z, c, v) = (self.flag(FLAG_N), self.flag(FLAG_Z), self.flag(FLAG_C), self.flag(FLAG_V));
+
+        match cond {
+            0x0 => z,
+            0x1 => !z,
+            0x2 => c,
+            0x3 => !c,
+            0x4 => n,
+            0x5 => !n,
+            0x6 => v,
+            0x7 => !v,
+            0x8 => c && !z,
+            0x9 => !c || z,
+            0xa => n == v,
+            0xb => n != v,
+            0xc => !z && n == v,
+            0xd => z || n != v,
+            _ => true,
+        }
+    }
+}
+
+fn sign_extend(value: u32, bits: u32) -> u32 {
+    (((value << (32 - bits)) as i32) >> (32 - bits)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::engine::{Arm32CpuEngine, ArmEngine, ArmRegister, EngineRunResult, MemoryPermission};
+
+    use super::{CachedArm32CpuEngine, REGISTERS};
+
+    const CODE: u32 = 0x10000;
+    const END: u32 = 0x20000;
+
+    fn load_code(engine: &mut dyn ArmEngine, code: &[u16]) {
+        engine.mem_map(CODE, 0x1000, MemoryPermission::ReadWriteExecute);
+        engine
+            .mem_write(CODE, &code.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<_>>())
+            .unwrap();
+
+        engine.reg_write(ArmRegister::Cpsr, 0x10);
+        engine.reg_write(ArmRegister::SP, CODE + 0x800);
+        engine.reg_write(ArmRegister::LR, END | 1);
+        engine.reg_write(ArmRegister::PC, CODE | 1);
+    }
+
+    fn engine_with_code(code: &[u16]) -> CachedArm32CpuEngine {
+        let mut engine = CachedArm32CpuEngine::new();
+        load_code(&mut engine, code);
+
+        engine
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // movs r0, #5; movs r1, #7; adds r2, r0, r1; subs r3, r0, r1; bx lr
+        let mut engine = engine_with_code(&[0x2005, 0x2107, 0x1842, 0x1a43, 0x4770]);
+
+        assert!(matches!(engine.run(END, 1000).unwrap(), EngineRunResult::End));
+        assert_eq!(engine.reg_read(ArmRegister::R2), 12);
+        assert_eq!(engine.reg_read(ArmRegister::R3), -2i32 as u32);
+        // n set, borrowed so c clear
+        assert_eq!(engine.reg_read(ArmRegister::Cpsr) >> 28, 0b1000);
+    }
+
+    #[test]
+    fn test_count() {
+        let mut engine = engine_with_code(&[0x2005, 0x2107, 0x1842, 0x1a43, 0x4770]);
+
+        assert!(matches!(engine.run(END, 2).unwrap(), EngineRunResult::CountExhausted));
+        assert_eq!(engine.reg_read(ArmRegister::PC), CODE + 4);
+        assert_eq!(engine.reg_read(ArmRegister::R1), 7);
+        assert_eq!(engine.reg_read(ArmRegister::R2), 0);
+    }
+
+    #[test]
+    fn test_loop() {
+        // movs r0, #0; loop: adds r0, #1; cmp r0, #10; bne loop; bx lr
+        let mut engine = engine_with_code(&[0x2000, 0x3001, 0x280a, 0xd1fc, 0x4770]);
+
+        assert!(matches!(engine.run(END, 1000).unwrap(), EngineRunResult::End));
+        assert_eq!(engine.reg_read(ArmRegister::R0), 10);
+    }
+
+    #[test]
+    fn test_call() {
+        // push {r4, lr}; bl func; pop {r4}; pop {r3}; bx r3; func: movs r0, #42; bx lr
+        let mut engine = engine_with_code(&[0xb510, 0xf000, 0xf803, 0xbc10, 0xbc08, 0x4718, 0x202a, 0x4770]);
+
+        assert!(matches!(engine.run(END, 1000).unwrap(), EngineRunResult::End));
+        assert_eq!(engine.reg_read(ArmRegister::R0), 42);
+        assert_eq!(engine.reg_read(ArmRegister::R3), END | 1);
+        assert_eq!(engine.reg_read(ArmRegister::SP), CODE + 0x800);
+    }
+
+    #[test]
+    fn test_host_write_invalidates() {
+        // movs r0, #1; bx lr
+        let mut engine = engine_with_code(&[0x2001, 0x4770]);
+        engine.run(END, 1000).unwrap();
+        assert_eq!(engine.reg_read(ArmRegister::R0), 1);
+
+        // movs r0, #2
+        engine.mem_write(CODE, &0x2002u16.to_le_bytes()).unwrap();
+        engine.reg_write(ArmRegister::PC, CODE | 1);
+        engine.run(END, 1000).unwrap();
+        assert_eq!(engine.reg_read(ArmRegister::R0), 2);
+    }
+
+    #[test]
+    fn test_guest_write_invalidates() {
+        // strh r1, [r2]; movs r0, #1; bx lr, with the strh replacing the movs by movs r0, #2
+        let mut engine = engine_with_code(&[0x8011, 0x2001, 0x4770]);
+        engine.reg_write(ArmRegister::R1, 0x2002);
+        engine.reg_write(ArmRegister::R2, CODE + 2);
+
+        engine.run(END, 1000).unwrap();
+        assert_eq!(engine.reg_read(ArmRegister::R0), 2);
+    }
+
+    #[test]
+    fn test_lockstep_with_interpreter() {
+        // a thumb loop of alu ops, loads, stores and push/pop, a call, then arm code that patches the called function
+        // to movs r0, #99 before thumb code calls it again
+        let program = [
+            0xb5f0, 0x2000, 0x2101, // push {r4-r7, lr}; movs r0, #0; movs r1, #1
+            0x1840, 0x0082, 0x404a, 0x603a, 0x7178,
+            0x883b, // loop: adds r0, r0, r1; lsls r2, r0, #2; eors r2, r1; str r2, [r7]; strb r0, [r7, #5]; ldrh r3, [r7]
+            0xb40f, 0xbc78, 0x3101, 0x2914, 0xd1f4, // push {r0-r3}; pop {r3-r6}; adds r1, #1; cmp r1, #20; bne loop
+            0xf000, 0xf824, 0x6138, 0xa301, 0x4718, 0x46c0, // bl patched; str r0, [r7, #16]; adr r3, arm_code; bx r3; nop
+            0x8003, 0xe3a0, // arm_code: mov r8, #3
+            0x0008, 0xe080, 0x0890, 0xe009, 0x9008, 0xe587, // arm_loop: add r0, r0, r8; mul r9, r0, r8; str r9, [r7, #8]
+            0x8001, 0xe258, 0xfffa, 0x1aff, // subs r8, r8, #1; bne arm_loop
+            0x2a02, 0xe3a0, 0x2063, 0xe382, 0x6018, 0xe28f, 0x20b0,
+            0xe1c6, // mov r2, #0x2000; orr r2, r2, #0x63; adr r6, patched; strh r2, [r6]
+            0x3004, 0xe28f, 0x3001, 0xe283, 0xff13, 0xe12f, // adr r3, back; add r3, r3, #1; bx r3
+            0xf000, 0xf804, 0x6178, 0xbcf0, 0xbc08, 0x4718, // back: bl patched; str r0, [r7, #20]; pop {r4-r7}; pop {r3}; bx r3
+            0x2001, 0x4770, // patched: movs r0, #1; bx lr
+        ];
+        let data = CODE + 0xc00;
+
+        let mut cached = CachedArm32CpuEngine::new();
+        let mut interpreter = Arm32CpuEngine::new();
+        for engine in [&mut cached as &mut dyn ArmEngine, &mut interpreter] {
+            load_code(engine, &program);
+            engine.reg_write(ArmRegister::R7, data);
+        }
+
+        // short slices, so the engines are compared in the middle of blocks and interpreter runs too
+        loop {
+            let cached_result = cached.run(END, 3).unwrap();
+            let interpreter_result = interpreter.run(END, 3).unwrap();
+
+            for reg in REGISTERS.into_iter().chain([ArmRegister::Cpsr]) {
+                assert_eq!(cached.reg_read(reg), interpreter.reg_read(reg), "{reg:?}");
+            }
+
+            let (mut cached_memory, mut interpreter_memory) = ([0; 0x1000], [0; 0x1000]);
+            cached.mem_read(CODE, 0x1000, &mut cached_memory).unwrap();
+            interpreter.mem_read(CODE, 0x1000, &mut interpreter_memory).unwrap();
+            assert_eq!(cached_memory, interpreter_memory);
+
+            match (cached_result, interpreter_result) {
+                (EngineRunResult::End, EngineRunResult::End) => break,
+                (EngineRunResult::CountExhausted, EngineRunResult::CountExhausted) => {}
+                _ => panic!("engines stopped for different reasons"),
+            }
+        }
+
+        let mut results = [0; 8];
+        cached.mem_read(data + 16, 8, &mut results).unwrap();
+        assert_eq!(results, [1, 0, 0, 0, 99, 0, 0, 0]);
+    }
+}
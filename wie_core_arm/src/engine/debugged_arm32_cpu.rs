@@ -1,13 +1,14 @@
-use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use alloc::{borrow::ToOwned, boxed::Box, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
 use core::time::Duration;
 
 use crossbeam::channel;
-use spin::Mutex;
-use wie_util::WieError;
+use spin::{Mutex, MutexGuard};
+use wie_backend::SymbolResolver;
+use wie_util::{ByteRead, WieError};
 
-use crate::{ThreadId, context::ArmCoreContext};
+use crate::{DebugEntry, DebugMonitor, ThreadId, context::ArmCoreContext};
 
-use super::{Arm32CpuEngine, ArmEngine, ArmRegister, EngineRunResult, MemoryPermission};
+use super::{Arm32CpuEngine, ArmEngine, ArmRegister, EngineRunResult, MemoryAccess, MemoryPermission};
 
 #[derive(Copy, Clone)]
 enum ResumeMode {
@@ -34,6 +35,7 @@ pub(crate) enum DebugSignal {
 pub(crate) enum DebugStopReason {
     Signal(DebugSignal),
     SwBreak(ThreadId),
+    Watch { thread_id: ThreadId, kind: DebugWatchKind, addr: u32 },
 }
 
 #[derive(Copy, Clone)]
@@ -43,6 +45,32 @@ pub(crate) enum DebugBreakpointKind {
     Arm32,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum DebugWatchKind {
+    Write,
+    Read,
+    Access,
+}
+
+#[derive(Copy, Clone)]
+struct Watchpoint {
+    addr: u32,
+    len: u32,
+    kind: DebugWatchKind,
+}
+
+impl Watchpoint {
+    fn hit_by(&self, access: &MemoryAccess) -> bool {
+        let kind_matches = match self.kind {
+            DebugWatchKind::Write => access.write,
+            DebugWatchKind::Read => !access.write,
+            DebugWatchKind::Access => true,
+        };
+
+        kind_matches && access.address < self.addr + self.len && access.address + access.size > self.addr
+    }
+}
+
 #[derive(Copy, Clone)]
 struct SoftwareBreakpoint {
     kind: DebugBreakpointKind,
@@ -118,6 +146,11 @@ pub(crate) struct DebugInner {
     resume_rx: channel::Receiver<ResumeMode>,
     interrupt_pending: Mutex<bool>,
     breakpoints: Mutex<BTreeMap<u32, SoftwareBreakpoint>>,
+    watchpoints: Mutex<Vec<Watchpoint>>,
+    symbols: Mutex<Option<SymbolResolver>>,
+    monitors: Mutex<Vec<Box<dyn DebugMonitor>>>,
+    libraries: Mutex<Vec<(String, u32)>>,
+    entries: Mutex<BTreeMap<Option<ThreadId>, Vec<DebugEntry>>>,
     active_threads: Mutex<Vec<ThreadId>>,
     current_thread: Mutex<Option<ThreadId>>,
     thread_ready_tx: channel::Sender<()>,
@@ -138,6 +171,11 @@ impl DebugInner {
             resume_rx,
             interrupt_pending: Mutex::new(false),
             breakpoints: Mutex::new(BTreeMap::new()),
+            watchpoints: Mutex::new(Vec::new()),
+            symbols: Mutex::new(None),
+            monitors: Mutex::new(Vec::new()),
+            libraries: Mutex::new(Vec::new()),
+            entries: Mutex::new(BTreeMap::new()),
             active_threads: Mutex::new(Vec::new()),
             current_thread: Mutex::new(None),
             thread_ready_tx,
@@ -280,6 +318,78 @@ impl DebugInner {
         Ok(())
     }
 
+    pub(crate) fn add_watchpoint(&self, addr: u32, len: u32, kind: DebugWatchKind) {
+        let mut watchpoints = self.watchpoints.lock();
+        watchpoints.push(Watchpoint { addr, len, kind });
+
+        self.cpu.lock().set_record_accesses(true);
+    }
+
+    pub(crate) fn remove_watchpoint(&self, addr: u32, len: u32, kind: DebugWatchKind) -> bool {
+        let mut watchpoints = self.watchpoints.lock();
+        let Some(index) = watchpoints.iter().position(|x| x.addr == addr && x.len == len && x.kind == kind) else {
+            return false;
+        };
+        watchpoints.remove(index);
+
+        if watchpoints.is_empty() {
+            self.cpu.lock().set_record_accesses(false);
+        }
+
+        true
+    }
+
+    pub(crate) fn has_watchpoints(&self) -> bool {
+        !self.watchpoints.lock().is_empty()
+    }
+
+    pub(crate) fn set_symbols(&self, symbols: SymbolResolver) {
+        *self.symbols.lock() = Some(symbols);
+    }
+
+    pub(crate) fn symbols(&self) -> Option<SymbolResolver> {
+        self.symbols.lock().clone()
+    }
+
+    pub(crate) fn add_monitor(&self, monitor: Box<dyn DebugMonitor>) {
+        self.monitors.lock().push(monitor);
+    }
+
+    pub(crate) fn monitors(&self) -> MutexGuard<'_, Vec<Box<dyn DebugMonitor>>> {
+        self.monitors.lock()
+    }
+
+    pub(crate) fn add_library(&self, name: &str, address: u32) {
+        self.libraries.lock().push((name.to_owned(), address));
+    }
+
+    /// `(name, address of the first section)` of the loaded guest binaries.
+    pub(crate) fn libraries(&self) -> Vec<(String, u32)> {
+        self.libraries.lock().clone()
+    }
+
+    /// Records that the host entered guest code on the current thread. Returns the thread to pass to `pop_entry`.
+    pub(crate) fn push_entry(&self, entry: DebugEntry) -> Option<ThreadId> {
+        let thread_id = self.current_thread();
+        self.entries.lock().entry(thread_id).or_default().push(entry);
+
+        thread_id
+    }
+
+    pub(crate) fn pop_entry(&self, thread_id: Option<ThreadId>) {
+        let mut entries = self.entries.lock();
+        if let Some(thread_entries) = entries.get_mut(&thread_id) {
+            thread_entries.pop();
+            if thread_entries.is_empty() {
+                entries.remove(&thread_id);
+            }
+        }
+    }
+
+    pub(crate) fn entries(&self, thread_id: Option<ThreadId>) -> Vec<DebugEntry> {
+        self.entries.lock().get(&thread_id).cloned().unwrap_or_default()
+    }
+
     pub(crate) fn active_threads(&self) -> Vec<ThreadId> {
         self.active_threads.lock().clone()
     }
@@ -305,6 +415,7 @@ impl DebugInner {
 
     pub(crate) fn on_thread_deleted(&self, thread_id: ThreadId) {
         self.active_threads.lock().retain(|&x| x != thread_id);
+        self.entries.lock().remove(&Some(thread_id));
 
         let mut current_thread = self.current_thread.lock();
         if *current_thread == Some(thread_id) {
@@ -356,6 +467,18 @@ impl DebugInner {
         Ok(())
     }
 
+    /// The first watchpoint `accesses` hit, as the kind and address to report.
+    fn find_watchpoint_hit(&self, accesses: &[MemoryAccess]) -> Option<(DebugWatchKind, u32)> {
+        let watchpoints = self.watchpoints.lock();
+
+        accesses.iter().find_map(|access| {
+            watchpoints
+                .iter()
+                .find(|watchpoint| watchpoint.hit_by(access))
+                .map(|watchpoint| (watchpoint.kind, access.address.max(watchpoint.addr)))
+        })
+    }
+
     fn take_interrupt(&self) -> bool {
         let mut interrupt_pending = self.interrupt_pending.lock();
         let result = *interrupt_pending;
@@ -380,10 +503,18 @@ impl DebugInner {
     }
 }
 
+impl ByteRead for DebugInner {
+    fn read_bytes(&self, address: u32, result: &mut [u8]) -> wie_util::Result<usize> {
+        self.read_memory(address, result)
+    }
+}
+
 pub struct DebuggedArm32CpuEngine {
     debug: Arc<DebugInner>,
     run_state: RunState,
     pending_breakpoint_reinsert: Option<u32>,
+    // watchpoint hit by a write from an emulated function, reported once the guest resumes
+    pending_watch_hit: Option<DebugStopReason>,
 }
 
 impl DebuggedArm32CpuEngine {
@@ -394,6 +525,7 @@ impl DebuggedArm32CpuEngine {
             debug,
             run_state: RunState::Paused,
             pending_breakpoint_reinsert: None,
+            pending_watch_hit: None,
         }
     }
 
@@ -421,6 +553,16 @@ impl DebuggedArm32CpuEngine {
         self.debug.current_thread().unwrap_or(1)
     }
 
+    fn watch_stop_reason(&mut self) -> Option<DebugStopReason> {
+        let accesses = self.debug.cpu.lock().take_accesses();
+
+        self.debug.find_watchpoint_hit(&accesses).map(|(kind, addr)| DebugStopReason::Watch {
+            thread_id: self.stop_thread_id(),
+            kind,
+            addr,
+        })
+    }
+
     fn handle_breakpoint_reinsert(&mut self, addr: u32, end: u32, resume_mode: ResumeMode) -> wie_util::Result<()> {
         let result = self.debug.cpu.lock().run(end, 1);
         if let Err(error) = self.debug.reinsert_breakpoint(addr) {
@@ -428,6 +570,11 @@ impl DebuggedArm32CpuEngine {
             return Err(error);
         }
 
+        if let (Ok(_), Some(reason)) = (&result, self.watch_stop_reason()) {
+            self.stop(reason);
+            return Ok(());
+        }
+
         match result {
             Ok(EngineRunResult::Svc { .. }) => {}
             Ok(_) if matches!(resume_mode, ResumeMode::Continue) => {}
@@ -447,6 +594,11 @@ impl ArmEngine for DebuggedArm32CpuEngine {
                 continue;
             }
 
+            if let Some(reason) = self.pending_watch_hit.take() {
+                self.stop(reason);
+                continue;
+            }
+
             let resume_mode = self.wait_for_resume_mode();
 
             if let Some(addr) = self.pending_breakpoint_reinsert.take() {
@@ -471,7 +623,7 @@ impl ArmEngine for DebuggedArm32CpuEngine {
 
             let run_count = match resume_mode {
                 ResumeMode::Continue => {
-                    if self.debug.has_breakpoints() {
+                    if self.debug.has_breakpoints() || self.debug.has_watchpoints() {
                         1
                     } else {
                         count
@@ -482,6 +634,12 @@ impl ArmEngine for DebuggedArm32CpuEngine {
 
             let result = self.debug.cpu.lock().run(end, run_count);
 
+            // the cpu stopped right after the access, so running again resumes where it left off
+            if let (Ok(_), Some(reason)) = (&result, self.watch_stop_reason()) {
+                self.stop(reason);
+                continue;
+            }
+
             match result {
                 Ok(result @ EngineRunResult::Svc { .. }) => return Ok(result),
                 Ok(result) => match resume_mode {
@@ -506,7 +664,22 @@ impl ArmEngine for DebuggedArm32CpuEngine {
     }
 
     fn mem_write(&mut self, address: u32, data: &[u8]) -> wie_util::Result<()> {
-        self.debug.cpu.lock().mem_write(address, data)
+        self.debug.cpu.lock().mem_write(address, data)?;
+
+        if self.pending_watch_hit.is_none() && self.debug.has_watchpoints() {
+            let access = MemoryAccess {
+                address,
+                size: data.len() as u32,
+                write: true,
+            };
+            self.pending_watch_hit = self.debug.find_watchpoint_hit(&[access]).map(|(kind, addr)| DebugStopReason::Watch {
+                thread_id: self.stop_thread_id(),
+                kind,
+                addr,
+            });
+        }
+
+        Ok(())
     }
 
     fn mem_read(&mut self, address: u32, size: usize, result: &mut [u8]) -> wie_util::Result<usize> {
@@ -516,6 +689,10 @@ impl ArmEngine for DebuggedArm32CpuEngine {
     fn is_mapped(&self, address: u32, size: usize) -> bool {
         self.debug.cpu.lock().is_mapped(address, size)
     }
+
+    fn mapped_regions(&self) -> Vec<(u32, usize)> {
+        self.debug.cpu.lock().mapped_regions()
+    }
 }
 
 #[cfg(test)]
@@ -596,4 +773,59 @@ mod tests {
         debug.cpu.lock().mem_read(0x3000, 2, &mut final_data).unwrap();
         assert_eq!(final_data, [0x11, 0x22]);
     }
+
+    #[test]
+    fn test_watchpoint_hits() {
+        let debug = DebugInner::new();
+
+        let write = |address, size| MemoryAccess { address, size, write: true };
+        let read = |address, size| MemoryAccess { address, size, write: false };
+
+        debug.add_watchpoint(0x4102, 2, DebugWatchKind::Write);
+        debug.add_watchpoint(0x4100, 1, DebugWatchKind::Read);
+        debug.add_watchpoint(0x5000, 4, DebugWatchKind::Access);
+
+        assert_eq!(debug.find_watchpoint_hit(&[write(0x4100, 4)]), Some((DebugWatchKind::Write, 0x4102)));
+        assert_eq!(debug.find_watchpoint_hit(&[write(0x4100, 2)]), None);
+        assert_eq!(debug.find_watchpoint_hit(&[read(0x4102, 2)]), None);
+        assert_eq!(
+            debug.find_watchpoint_hit(&[read(0x4200, 4), read(0x4100, 4)]),
+            Some((DebugWatchKind::Read, 0x4100))
+        );
+        assert_eq!(debug.find_watchpoint_hit(&[read(0x5002, 1)]), Some((DebugWatchKind::Access, 0x5002)));
+        assert_eq!(debug.find_watchpoint_hit(&[write(0x4ffc, 8)]), Some((DebugWatchKind::Access, 0x5000)));
+
+        assert!(debug.remove_watchpoint(0x4102, 2, DebugWatchKind::Write));
+        assert!(!debug.remove_watchpoint(0x4100, 1, DebugWatchKind::Write));
+        assert!(debug.remove_watchpoint(0x4100, 1, DebugWatchKind::Read));
+        assert!(debug.has_watchpoints());
+        assert!(debug.remove_watchpoint(0x5000, 4, DebugWatchKind::Access));
+        assert!(!debug.has_watchpoints());
+        assert_eq!(debug.find_watchpoint_hit(&[write(0x4100, 4)]), None);
+    }
+
+    #[test]
+    fn test_entries_per_thread() {
+        let debug = DebugInner::new();
+        let entry = |address| DebugEntry { address, caller: None };
+
+        let outer = debug.push_entry(entry(0x1000));
+        assert_eq!(outer, None);
+
+        debug.on_thread_entered(1);
+        let thread = debug.push_entry(entry(0x2000));
+        debug.push_entry(entry(0x3000));
+        assert_eq!(thread, Some(1));
+        assert_eq!(debug.entries(Some(1)), [entry(0x2000), entry(0x3000)]);
+        assert_eq!(debug.entries(None), [entry(0x1000)]);
+
+        debug.pop_entry(thread);
+        assert_eq!(debug.entries(Some(1)), [entry(0x2000)]);
+
+        debug.on_thread_deleted(1);
+        assert!(debug.entries(Some(1)).is_empty());
+
+        debug.pop_entry(outer);
+        assert!(debug.entries(None).is_empty());
+    }
 }
@@ -141,7 +141,7 @@ pub trait EmulatedFunctionParam<T> {
     fn get(core: &mut ArmCore, pos: usize) -> T;
 
     fn read(core: &mut ArmCore, pos: usize) -> u32 {
-        core.read_param(pos).unwrap()
+        core.read_svc_param(pos).unwrap()
     }
 }
 
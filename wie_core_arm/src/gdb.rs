@@ -1,6 +1,6 @@
 extern crate std; // we need thread
 
-use alloc::{format, sync::Arc};
+use alloc::{format, string::String, sync::Arc, vec::Vec};
 use std::{
     io,
     net::{TcpListener, TcpStream},
@@ -23,16 +23,19 @@ use gdbstub::{
             BaseOps,
             multithread::{MultiThreadBase, MultiThreadResume, MultiThreadResumeOps, MultiThreadSingleStep, MultiThreadSingleStepOps},
         },
-        ext::breakpoints::{Breakpoints, BreakpointsOps, SwBreakpoint, SwBreakpointOps},
+        ext::breakpoints::{Breakpoints, BreakpointsOps, HwWatchpoint, HwWatchpointOps, SwBreakpoint, SwBreakpointOps, WatchKind},
         ext::extended_mode::{Args, AttachKind, CurrentActivePid, CurrentActivePidOps, ExtendedMode, ExtendedModeOps, ShouldTerminate},
+        ext::libraries::{Libraries, LibrariesOps},
+        ext::monitor_cmd::{ConsoleOutput, MonitorCmd, MonitorCmdOps, outputln},
     },
 };
 use gdbstub_arch::arm::{ArmBreakpointKind, Armv4t, reg::ArmCoreRegs};
 
 use crate::{
-    ArmCore,
+    Allocator, ArmCore, DebugMonitorContext,
     context::ArmCoreContext,
-    engine::{DebugBreakpointKind, DebugInner, DebugSignal, DebugStopReason},
+    core::{FUNCTIONS_BASE, FUNCTIONS_SIZE, SVC_STUB_SIZE},
+    engine::{DebugBreakpointKind, DebugInner, DebugSignal, DebugStopReason, DebugWatchKind},
 };
 
 type GdbTargetError = &'static str;
@@ -51,6 +54,23 @@ fn to_gdb_stop_reason(reason: DebugStopReason) -> MultiThreadStopReason<u32> {
     match reason {
         DebugStopReason::Signal(signal) => MultiThreadStopReason::Signal(to_gdb_signal(signal)),
         DebugStopReason::SwBreak(thread_id) => MultiThreadStopReason::SwBreak(Tid::try_from(thread_id).unwrap()),
+        DebugStopReason::Watch { thread_id, kind, addr } => MultiThreadStopReason::Watch {
+            tid: Tid::try_from(thread_id).unwrap(),
+            kind: match kind {
+                DebugWatchKind::Write => WatchKind::Write,
+                DebugWatchKind::Read => WatchKind::Read,
+                DebugWatchKind::Access => WatchKind::ReadWrite,
+            },
+            addr,
+        },
+    }
+}
+
+fn to_debug_watch_kind(kind: WatchKind) -> DebugWatchKind {
+    match kind {
+        WatchKind::Write => DebugWatchKind::Write,
+        WatchKind::Read => DebugWatchKind::Read,
+        WatchKind::ReadWrite => DebugWatchKind::Access,
     }
 }
 
@@ -195,6 +215,16 @@ impl Target for GdbTarget {
     fn support_extended_mode(&mut self) -> Option<ExtendedModeOps<'_, Self>> {
         Some(self)
     }
+
+    #[inline(always)]
+    fn support_monitor_cmd(&mut self) -> Option<MonitorCmdOps<'_, Self>> {
+        Some(self)
+    }
+
+    #[inline(always)]
+    fn support_libraries(&mut self) -> Option<LibrariesOps<'_, Self>> {
+        Some(self)
+    }
 }
 
 impl MultiThreadBase for GdbTarget {
@@ -288,6 +318,10 @@ impl Breakpoints for GdbTarget {
     fn support_sw_breakpoint(&mut self) -> Option<SwBreakpointOps<'_, Self>> {
         Some(self)
     }
+
+    fn support_hw_watchpoint(&mut self) -> Option<HwWatchpointOps<'_, Self>> {
+        Some(self)
+    }
 }
 
 impl SwBreakpoint for GdbTarget {
@@ -310,6 +344,143 @@ impl SwBreakpoint for GdbTarget {
     }
 }
 
+impl HwWatchpoint for GdbTarget {
+    fn add_hw_watchpoint(&mut self, addr: u32, len: u32, kind: WatchKind) -> TargetResult<bool, Self> {
+        self.debug.add_watchpoint(addr, len, to_debug_watch_kind(kind));
+
+        Ok(true)
+    }
+
+    fn remove_hw_watchpoint(&mut self, addr: u32, len: u32, kind: WatchKind) -> TargetResult<bool, Self> {
+        Ok(self.debug.remove_watchpoint(addr, len, to_debug_watch_kind(kind)))
+    }
+}
+
+impl MonitorCmd for GdbTarget {
+    fn handle_monitor_cmd(&mut self, cmd: &[u8], mut out: ConsoleOutput<'_>) -> Result<(), Self::Error> {
+        let cmd = String::from_utf8_lossy(cmd);
+        let mut args = cmd.split_whitespace();
+        let command = args.next().unwrap_or_default();
+        let args = args.collect::<Vec<_>>();
+
+        match command {
+            "heap" => self.monitor_heap(&mut out),
+            "svc" => self.monitor_svc(args.first().copied(), &mut out),
+            command => {
+                if !self.run_debug_monitor(command, &args, &mut out) {
+                    self.monitor_help(&mut out);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl GdbTarget {
+    // false if no monitor has `command`
+    fn run_debug_monitor(&self, command: &str, args: &[&str], out: &mut ConsoleOutput<'_>) -> bool {
+        let monitors = self.debug.monitors();
+        let Some(monitor) = monitors.iter().find(|x| x.commands().iter().any(|&(name, _)| name == command)) else {
+            return false;
+        };
+
+        let context = DebugMonitorContext {
+            memory: self.debug.as_ref(),
+            registers: self.debug.read_registers(),
+            entries: self.debug.entries(self.debug.current_thread()),
+            symbols: self.debug.symbols(),
+        };
+
+        if let Err(err) = monitor.run(command, args, &context, out) {
+            outputln!(out, "{command} failed: {err}");
+        }
+
+        true
+    }
+
+    fn monitor_help(&self, out: &mut ConsoleOutput<'_>) {
+        outputln!(out, "monitor commands:");
+        outputln!(out, "  heap          list live heap allocations");
+        outputln!(out, "  svc [filter]  list SVC stubs, optionally only those whose name contains filter");
+        for monitor in self.debug.monitors().iter() {
+            for (command, description) in monitor.commands() {
+                outputln!(out, "  {command:<13} {description}");
+            }
+        }
+    }
+
+    fn monitor_heap(&self, out: &mut ConsoleOutput<'_>) {
+        let allocations = match Allocator::allocations(self.debug.as_ref()) {
+            Ok(allocations) => allocations,
+            Err(err) => {
+                outputln!(out, "failed to read heap: {err}");
+                return;
+            }
+        };
+
+        for (address, size) in &allocations {
+            outputln!(out, "{address:#010x} {size:#x}");
+        }
+
+        let total = allocations.iter().map(|&(_, size)| size as u64).sum::<u64>();
+        outputln!(out, "{} allocations, {total:#x} bytes", allocations.len());
+    }
+
+    fn monitor_svc(&self, filter: Option<&str>, out: &mut ConsoleOutput<'_>) {
+        let symbols = self.debug.symbols();
+
+        for address in (FUNCTIONS_BASE..FUNCTIONS_BASE + FUNCTIONS_SIZE as u32).step_by(SVC_STUB_SIZE as usize) {
+            let mut stub = [0; SVC_STUB_SIZE as usize];
+            if self.debug.read_memory(address, &mut stub).is_err() {
+                break;
+            }
+
+            // stubs are made contiguously; the first one without an svc instruction ends the list
+            if stub[9] != 0xdf {
+                break;
+            }
+
+            let category = stub[8];
+            let id = u32::from_le_bytes([stub[12], stub[13], stub[14], stub[15]]);
+            let name = symbols
+                .as_ref()
+                .and_then(|x| x.resolve(address))
+                .map(|(name, _)| name)
+                .unwrap_or_default();
+
+            if filter.is_some_and(|filter| !name.contains(filter)) {
+                continue;
+            }
+
+            outputln!(out, "{:#010x} category={category} id={id:#x} {name}", address + 1);
+        }
+    }
+}
+
+impl Libraries for GdbTarget {
+    fn get_libraries(&self, offset: u64, length: usize, buf: &mut [u8]) -> TargetResult<usize, Self> {
+        let xml = library_list_xml(&self.debug.libraries());
+
+        let start = xml.len().min(offset as usize);
+        let end = xml.len().min(start + length.min(buf.len()));
+        buf[..end - start].copy_from_slice(&xml.as_bytes()[start..end]);
+
+        Ok(end - start)
+    }
+}
+
+fn library_list_xml(libraries: &[(String, u32)]) -> String {
+    let mut xml = String::from("<library-list version=\"1.0\">");
+    for (name, address) in libraries {
+        let name = name.replace('&', "&amp;").replace('<', "&lt;").replace('"', "&quot;");
+        xml += &format!("<library name=\"{name}\"><segment address=\"{address:#x}\"/></library>");
+    }
+    xml += "</library-list>";
+
+    xml
+}
+
 impl ExtendedMode for GdbTarget {
     fn run(&mut self, _filename: Option<&[u8]>, _args: Args<'_, '_>) -> TargetResult<Pid, Self> {
         if self.debug.active_threads().is_empty() {
@@ -379,3 +550,16 @@ impl BlockingEventLoop for GdbBlockingEventLoop {
         Ok(None::<MultiThreadStopReason<u32>>)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::library_list_xml;
+
+    #[test]
+    fn test_library_list_xml() {
+        assert_eq!(
+            library_list_xml(&[("client.bin".into(), 0x100000), ("a&b".into(), 0x200000)]),
+            r#"<library-list version="1.0"><library name="client.bin"><segment address="0x100000"/></library><library name="a&amp;b"><segment address="0x200000"/></library></library-list>"#
+        );
+    }
+}
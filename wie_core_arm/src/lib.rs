@@ -5,6 +5,7 @@ mod allocator;
 mod binary_patches;
 mod context;
 mod core;
+mod debug_monitor;
 mod engine;
 mod function;
 pub mod stdlib;
@@ -19,6 +20,8 @@ pub type ThreadId = usize;
 pub use self::{
     allocator::Allocator,
     binary_patches::install_binary_patches,
+    context::ArmCoreContext,
     core::{ArmCore, RUN_FUNCTION_LR, RunFunctionResult},
+    debug_monitor::{DebugCaller, DebugEntry, DebugMonitor, DebugMonitorContext},
     function::{EmulatedFunction, EmulatedFunctionParam, RegisteredFunction, RegisteredFunctionHolder, ResultWriter, SvcId},
 };
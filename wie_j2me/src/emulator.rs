@@ -12,7 +12,7 @@ use jvm::{
     runtime::{JavaIoInputStream, JavaLangString},
 };
 
-use wie_backend::{DefaultTaskRunner, Emulator, Event, Platform, System};
+use wie_backend::{DefaultTaskRunner, Emulator, Event, HandsetProfile, Platform, System};
 use wie_jvm_support::{JvmSupport, RustJavaJvmImplementation};
 use wie_util::{Result, WieError};
 
@@ -21,12 +21,13 @@ pub struct J2MEEmulator {
 }
 
 impl J2MEEmulator {
-    pub fn from_jad_jar(platform: Box<dyn Platform>, jad: Vec<u8>, jar_filename: String, jar: Vec<u8>) -> Result<Self> {
+    pub fn from_jad_jar(platform: Box<dyn Platform>, handset: HandsetProfile, jad: Vec<u8>, jar_filename: String, jar: Vec<u8>) -> Result<Self> {
         let descriptor = J2MEDescriptor::parse(&jad);
 
         let files = [(jar_filename.to_owned(), jar)].into_iter().collect();
         Self::load(
             platform,
+            handset,
             &jar_filename,
             &descriptor.name,
             Some(descriptor.main_class_name),
@@ -35,21 +36,24 @@ impl J2MEEmulator {
         )
     }
 
-    pub fn from_jar(platform: Box<dyn Platform>, jar_filename: &str, jar: Vec<u8>) -> Result<Self> {
+    pub fn from_jar(platform: Box<dyn Platform>, handset: HandsetProfile, jar_filename: &str, jar: Vec<u8>) -> Result<Self> {
         let files = [(jar_filename.to_owned(), jar)].into_iter().collect();
 
-        Self::load(platform, jar_filename, jar_filename, None, BTreeMap::new(), &files)
+        Self::load(platform, handset, jar_filename, jar_filename, None, BTreeMap::new(), &files)
     }
 
     fn load(
         platform: Box<dyn Platform>,
+        handset: HandsetProfile,
         jar_filename: &str,
         id: &str,
         main_class_name: Option<String>,
         properties: BTreeMap<String, String>,
         files: &BTreeMap<String, Vec<u8>>,
     ) -> Result<Self> {
-        let system = System::new(platform, id, id, DefaultTaskRunner);
+        let mut system = System::new(platform, handset, id, id, DefaultTaskRunner);
+        system.load_game_config(files)?;
+        let main_class_name = system.game_config().main_class.clone().or(main_class_name);
 
         for (path, data) in files {
             system.filesystem().add_virtual(path, data.clone());
@@ -140,7 +144,7 @@ impl J2MEEmulator {
 
 impl Emulator for J2MEEmulator {
     fn handle_event(&mut self, event: Event) {
-        self.system.event_queue().push(event)
+        self.system.handle_event(event)
     }
 
     fn tick(&mut self) -> Result<()> {
@@ -40,12 +40,15 @@ impl JvmSupport {
             format!("{RT_RUSTJAR}:{WIE_RUSTJAR}")
         };
 
+        let handset = system.handset();
         let properties = [
             ("file.encoding", "EUC-KR"),
             ("java.class.path", &class_path),
+            ("microedition.platform", &handset.model),
             //("rustjava.disable_explicit_gc", "true"),
         ]
         .iter()
+        .chain(handset.carrier.as_deref().map(|x| ("CARRIER", x)).iter())
         .chain(properties.iter())
         .copied()
         .collect();
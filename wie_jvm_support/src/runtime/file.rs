@@ -90,12 +90,18 @@ mod tests {
 
     use java_runtime::{File, FileType, IOError};
     use test_utils::TestPlatform;
-    use wie_backend::{DefaultTaskRunner, System};
+    use wie_backend::{DefaultTaskRunner, HandsetProfile, System};
 
     use super::FileImpl;
 
     fn new_system() -> System {
-        System::new(Box::new(TestPlatform::new()), "test", "test-aid", DefaultTaskRunner)
+        System::new(
+            Box::new(TestPlatform::new()),
+            HandsetProfile::default(),
+            "test",
+            "test-aid",
+            DefaultTaskRunner,
+        )
     }
 
     #[futures_test::test]
@@ -4,14 +4,14 @@ use alloc::{borrow::ToOwned, boxed::Box, collections::BTreeMap, format, string::
 
 use jvm::{ClassInstance, Result as JvmResult, runtime::JavaLangString};
 
-use wie_backend::{Emulator, Event, Options, Platform, System, TaskRunner};
+use wie_backend::{Emulator, Event, HandsetProfile, Options, Platform, Snapshot, System, TaskRunner};
 use wie_core_arm::{Allocator, ArmCore};
 use wie_jvm_support::JvmSupport;
 use wie_util::{Result, WieError};
 
 use crate::{
     adf::{KtfAdf, find_client_bin},
-    runtime::KtfJvmSupport,
+    runtime::{KtfDebugMonitor, KtfJvmSupport},
 };
 
 pub const IMAGE_BASE: u32 = 0x100000;
@@ -33,7 +33,7 @@ pub struct KtfEmulator {
 }
 
 impl KtfEmulator {
-    pub fn from_archive(platform: Box<dyn Platform>, files: BTreeMap<String, Vec<u8>>, options: Options) -> Result<Self> {
+    pub fn from_archive(platform: Box<dyn Platform>, handset: HandsetProfile, files: BTreeMap<String, Vec<u8>>, options: Options) -> Result<Self> {
         let adf = files
             .get("__adf__")
             .ok_or_else(|| WieError::FatalError("Missing __adf__ in KTF archive".into()))?;
@@ -43,11 +43,13 @@ impl KtfEmulator {
 
         let jar_filename = format!("{}.jar", adf.aid);
 
-        Self::load(platform, &jar_filename, &adf.pid, &adf.aid, Some(adf.mclass), &files, options)
+        Self::load(platform, handset, &jar_filename, &adf.pid, &adf.aid, Some(adf.mclass), &files, options)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn from_jar(
         platform: Box<dyn Platform>,
+        handset: HandsetProfile,
         jar_filename: &str,
         jar: Vec<u8>,
         pid: &str,
@@ -57,7 +59,7 @@ impl KtfEmulator {
     ) -> Result<Self> {
         let files = [(jar_filename.to_owned(), jar)].into_iter().collect();
 
-        Self::load(platform, jar_filename, pid, aid, main_class_name, &files, options)
+        Self::load(platform, handset, jar_filename, pid, aid, main_class_name, &files, options)
     }
 
     pub fn loadable_archive(files: &BTreeMap<String, Vec<u8>>) -> bool {
@@ -68,8 +70,10 @@ impl KtfEmulator {
         find_client_bin(jar).is_ok()
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn load(
         platform: Box<dyn Platform>,
+        handset: HandsetProfile,
         jar_filename: &str,
         pid: &str,
         aid: &str,
@@ -77,8 +81,23 @@ impl KtfEmulator {
         files: &BTreeMap<String, Vec<u8>>,
         mut options: Options,
     ) -> Result<Self> {
-        let mut core = ArmCore::new(options.enable_gdbserver, options.profile.take())?;
-        let system = System::new(platform, pid, aid, KtfTaskRunner { core: core.clone() });
+        // the gdb server needs the plain interpreter, so it wins over the block cache
+        let profile = options.profile.take();
+        let mut core = if options.enable_block_cache && !options.enable_gdbserver {
+            ArmCore::with_block_cache(profile)?
+        } else {
+            ArmCore::new(options.enable_gdbserver, profile)?
+        };
+        if let Some(symbols) = options.symbols.take() {
+            core.set_symbols(symbols);
+        }
+        if let Some(svc_trace) = options.svc_trace.take() {
+            core.set_svc_trace(svc_trace);
+        }
+        core.add_debug_monitor(Box::new(KtfDebugMonitor));
+        let mut system = System::new(platform, handset, pid, aid, KtfTaskRunner { core: core.clone() });
+        system.load_game_config(files)?;
+        let main_class_name = system.game_config().main_class.clone().or(main_class_name);
 
         for (path, data) in files {
             let path = path.trim_start_matches("P/");
@@ -135,7 +154,7 @@ impl KtfEmulator {
 
 impl Emulator for KtfEmulator {
     fn handle_event(&mut self, event: Event) {
-        self.system.event_queue().push(event)
+        self.system.handle_event(event)
     }
 
     fn tick(&mut self) -> Result<()> {
@@ -147,4 +166,19 @@ impl Emulator for KtfEmulator {
             }
         })
     }
+
+    fn save_checkpoint(&self) -> Result<Vec<u8>> {
+        let mut checkpoint = Snapshot::new();
+        checkpoint.insert("system", self.system.save_checkpoint()?);
+        checkpoint.insert("arm", self.core.state_digest()?.to_vec());
+
+        Ok(checkpoint.to_bytes())
+    }
+
+    fn replay_checkpoint(&mut self, data: &[u8]) -> Result<()> {
+        let checkpoint = Snapshot::from_bytes(data)?;
+
+        self.system.replay_checkpoint(checkpoint.get("system")?)?;
+        self.core.check_state_digest(checkpoint.get("arm")?)
+    }
 }
@@ -8,4 +8,4 @@ const SVC_CATEGORY_JAVA_INTERFACE: u32 = 2;
 const SVC_CATEGORY_WIPIC: u32 = 3;
 const SVC_CATEGORY_JAVA: u32 = 4;
 
-pub use self::java::jvm_support::KtfJvmSupport;
+pub use self::java::jvm_support::{KtfDebugMonitor, KtfJvmSupport};
@@ -2,7 +2,7 @@ use alloc::{format, string::String};
 use core::mem::size_of;
 use jvm::Jvm;
 
-use wie_backend::System;
+use wie_backend::{Quirk, System};
 use wie_core_arm::{Allocator, ArmCore, EmulatedFunction, ResultWriter, SvcId};
 use wie_util::{Result, WieError, read_generic, read_null_terminated_string_bytes, write_generic};
 
@@ -20,7 +20,13 @@ use crate::{
 };
 
 pub fn register_init_svc_handler(core: &mut ArmCore, jvm: &Jvm) -> Result<()> {
-    core.register_svc_handler(SVC_CATEGORY_INIT, handle_init_svc, jvm)
+    core.register_svc_handler(SVC_CATEGORY_INIT, handle_init_svc, jvm)?;
+    core.set_svc_names(SVC_CATEGORY_INIT, |id| match InitSvcId::try_from(SvcId(id)) {
+        Ok(x) => format!("Init::{x:?}"),
+        Err(_) => format!("Init::{id:#x}"),
+    });
+
+    Ok(())
 }
 
 async fn handle_init_svc(core: &mut ArmCore, jvm: &mut Jvm, id: SvcId) -> Result<()> {
@@ -49,6 +55,7 @@ pub async fn load_native(
     let bss_size = parse_bss_size(filename)?;
 
     core.load(data, IMAGE_BASE, data.len() + bss_size as usize)?;
+    core.add_library(filename, IMAGE_BASE);
 
     // Patterns target instruction encodings, which the guest self-rebase at
     // IMAGE_BASE+1 doesn't rewrite — so installing here is sound and skips a
@@ -61,7 +68,9 @@ pub async fn load_native(
     // patterns being long enough (and `{exit_b}` strict enough) that a
     // metadata-region collision is implausible; tighten patterns rather than
     // narrow the range if that ever becomes false.
-    wie_core_arm::install_binary_patches(core, data, &[(IMAGE_BASE, data.len() as u32)])?;
+    if !system.game_config().has_quirk(Quirk::NoBinaryPatches) {
+        wie_core_arm::install_binary_patches(core, data, &[(IMAGE_BASE, data.len() as u32)])?;
+    }
 
     register_wipic_svc_handler(core, system, jvm)?;
     register_init_svc_handler(core, jvm)?;
@@ -21,7 +21,13 @@ use crate::runtime::java::jvm_support::{
 use crate::runtime::{SVC_CATEGORY_JAVA_INTERFACE, svc_ids::JavaSvcId};
 
 pub fn register_java_interface_svc_handler(core: &mut ArmCore, jvm: &Jvm) -> Result<()> {
-    core.register_svc_handler(SVC_CATEGORY_JAVA_INTERFACE, handle_java_interface_svc, jvm)
+    core.register_svc_handler(SVC_CATEGORY_JAVA_INTERFACE, handle_java_interface_svc, jvm)?;
+    core.set_svc_names(SVC_CATEGORY_JAVA_INTERFACE, |id| match JavaSvcId::try_from(SvcId(id)) {
+        Ok(x) => format!("JavaInterface::{x:?}"),
+        Err(_) => format!("JavaInterface::{id:#x}"),
+    });
+
+    Ok(())
 }
 
 async fn handle_java_interface_svc(core: &mut ArmCore, jvm: &mut Jvm, id: SvcId) -> Result<()> {
@@ -3,6 +3,7 @@ mod array_class_instance;
 mod class_definition;
 mod class_instance;
 mod classes;
+mod debug_monitor;
 mod field;
 mod jvm_implementation;
 mod method;
@@ -22,7 +23,7 @@ use jvm::{ClassDefinition, ClassInstance, ClassInstanceRef, Jvm, runtime::JavaLa
 use wie_backend::System;
 use wie_core_arm::{Allocator, ArmCore};
 use wie_jvm_support::JvmSupport;
-use wie_util::{Result, WieError, read_generic, read_null_terminated_table, write_generic};
+use wie_util::{ByteRead, Result, WieError, read_generic, read_null_terminated_table, write_generic};
 
 use wipi_types::ktf::InitParam2;
 
@@ -37,6 +38,7 @@ pub use self::{
     array_class_definition::JavaArrayClassDefinition,
     class_definition::JavaClassDefinition,
     class_instance::JavaClassInstance,
+    debug_monitor::KtfDebugMonitor,
     method::{JavaMethod, JavaMethodResult},
     vtable::JavaVtable,
 };
@@ -197,9 +199,12 @@ impl KtfJvmSupport {
         Ok(index as _)
     }
 
-    pub fn current_java_exception_handler(core: &mut ArmCore) -> Result<u32> {
-        let context_data: KtfJvmSupportContext = read_generic(core, SUPPORT_CONTEXT_BASE)?;
-        let exception_context: KtfJvmExceptionContext = read_generic(core, context_data.ptr_jvm_exception_context)?;
+    pub fn current_java_exception_handler<R>(reader: &R) -> Result<u32>
+    where
+        R: ?Sized + ByteRead,
+    {
+        let context_data: KtfJvmSupportContext = read_generic(reader, SUPPORT_CONTEXT_BASE)?;
+        let exception_context: KtfJvmExceptionContext = read_generic(reader, context_data.ptr_jvm_exception_context)?;
 
         Ok(exception_context.current_java_exception_handler)
     }
@@ -212,7 +217,7 @@ mod test {
 
     use jvm::{Jvm, runtime::JavaLangString};
 
-    use wie_backend::{DefaultTaskRunner, System};
+    use wie_backend::{DefaultTaskRunner, HandsetProfile, System};
     use wie_core_arm::{Allocator, ArmCore};
     use wie_util::Result;
 
@@ -236,7 +241,7 @@ mod test {
 
     #[test]
     fn test_jvm_support() -> Result<()> {
-        let mut system = System::new(Box::new(TestPlatform::new()), "", "", DefaultTaskRunner);
+        let mut system = System::new(Box::new(TestPlatform::new()), HandsetProfile::default(), "", "", DefaultTaskRunner);
 
         let done = Arc::new(AtomicBool::new(false));
 
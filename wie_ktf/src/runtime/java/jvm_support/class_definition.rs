@@ -10,6 +10,7 @@ use java_constants::{ClassAccessFlags, FieldAccessFlags, MethodAccessFlags};
 use jvm::{ClassDefinition, ClassInstance, Field, JavaType, JavaValue, Jvm, Method, Result as JvmResult};
 use wipi_types::ktf::java::{JavaClass as RawJavaClass, JavaClassDescriptor as RawJavaClassDescriptor};
 
+use wie_backend::SymbolResolver;
 use wie_core_arm::{Allocator, ArmCore};
 use wie_util::{
     read_generic, read_null_terminated_string_bytes, read_null_terminated_table, write_generic, write_null_terminated_string_bytes,
@@ -130,6 +131,10 @@ impl JavaClassDefinition {
 
         tracing::trace!("Wrote definition {} at {ptr_raw:#x}", proto.name);
 
+        if let Some(symbols) = core.symbols() {
+            result.add_symbols(&symbols)?;
+        }
+
         Ok(result)
     }
 
@@ -188,6 +193,24 @@ impl JavaClassDefinition {
         Ok(result)
     }
 
+    /// Names the code of the class's methods `Class.method(descriptor)` in `symbols`.
+    pub fn add_symbols(&self, symbols: &SymbolResolver) -> Result<()> {
+        let class_name = self.name()?.replace('/', ".");
+
+        for method in self.methods()? {
+            let address = method.code_address()?;
+            if address == 0 {
+                continue;
+            }
+
+            // method sizes aren't recorded, so each symbol extends up to the next one
+            let name = method.name()?;
+            symbols.add(address, 0, format!("{class_name}.{}{}", name.name, name.descriptor));
+        }
+
+        Ok(())
+    }
+
     pub fn fields(&self) -> Result<Vec<JavaField>> {
         let raw: RawJavaClass = read_generic(&self.core, self.ptr_raw)?;
         let descriptor: RawJavaClassDescriptor = read_generic(&self.core, raw.ptr_descriptor)?;
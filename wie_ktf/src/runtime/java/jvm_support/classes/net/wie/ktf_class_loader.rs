@@ -118,6 +118,9 @@ impl KtfClassLoader {
 
         if ptr_raw != 0 {
             let class = JavaClassDefinition::from_raw(ptr_raw, &context.core);
+            if let Some(symbols) = context.core.symbols() {
+                class.add_symbols(&symbols).unwrap();
+            }
             jvm.register_class(Box::new(class), Some(this.into())).await?;
 
             Ok(jvm.resolve_class(&name).await?.java_class().into())
@@ -0,0 +1,171 @@
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use core::fmt::Write;
+
+use wipi_types::ktf::java::{
+    JavaClass as RawJavaClass, JavaClassDescriptor as RawJavaClassDescriptor, JavaExceptionHandler as RawJavaExceptionHandler,
+    JavaMethodDefinition as RawJavaMethod,
+};
+
+use wie_core_arm::{DebugMonitor, DebugMonitorContext};
+use wie_util::{ByteRead, Result, read_generic, read_null_terminated_string_bytes};
+
+use super::{KtfJvmSupport, method::read_exception_table, name::JavaFullName};
+
+/// `monitor` commands for the java layer of KTF apps.
+///
+/// Java methods are found through the symbols the class loaders add, so these need a symbol table to be set.
+pub struct KtfDebugMonitor;
+
+impl DebugMonitor for KtfDebugMonitor {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("java-classes", "list java classes whose name contains the filter, with method addresses"),
+            (
+                "java-stack",
+                "list the java call stack of the stopped thread from its java_jump trampoline calls, innermost first",
+            ),
+            ("java-handler", "show the current java exception handler and its catch entries"),
+        ]
+    }
+
+    fn run(&self, command: &str, args: &[&str], context: &DebugMonitorContext<'_>, out: &mut dyn Write) -> Result<()> {
+        match command {
+            "java-classes" => write_classes(context, args.first().copied(), out),
+            "java-stack" => write_stack(context, out),
+            "java-handler" => write_exception_handler(context, out),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn write_classes(context: &DebugMonitorContext<'_>, filter: Option<&str>, out: &mut dyn Write) -> Result<()> {
+    let Some(symbols) = &context.symbols else {
+        writeln!(out, "no symbol table").unwrap();
+        return Ok(());
+    };
+
+    let mut classes = BTreeMap::<String, Vec<(u32, String)>>::new();
+    for (address, _, name) in symbols.entries() {
+        if let Some((class, method)) = parse_method_symbol(&name)
+            && filter.is_none_or(|filter| class.contains(filter))
+        {
+            classes.entry(class.into()).or_default().push((address, method.into()));
+        }
+    }
+
+    for (class, methods) in classes {
+        writeln!(out, "{class}").unwrap();
+        for (address, method) in methods {
+            writeln!(out, "  {address:#010x} {method}").unwrap();
+        }
+    }
+
+    Ok(())
+}
+
+fn write_stack(context: &DebugMonitorContext<'_>, out: &mut dyn Write) -> Result<()> {
+    let Some(symbols) = &context.symbols else {
+        writeln!(out, "no symbol table").unwrap();
+        return Ok(());
+    };
+    let describe = |address: u32| match symbols.resolve(address) {
+        Some(_) => format!("{address:#010x} in {}", symbols.format(address)),
+        None => format!("{address:#010x}"),
+    };
+
+    // each java_jump trampoline call left an entry with the return address into the calling method. java methods
+    // calling each other directly leave no trace, so those frames are missing
+    writeln!(out, "#0 {}", describe(context.registers.pc)).unwrap();
+    let mut frame = 1;
+    for entry in context.entries.iter().rev() {
+        match entry.caller {
+            Some(caller) => {
+                writeln!(
+                    out,
+                    "#{frame} {} through {}",
+                    describe(caller.return_address),
+                    symbols.format(caller.stub)
+                )
+                .unwrap();
+                frame += 1;
+            }
+            None => writeln!(out, "   called from the jvm at {}", describe(entry.address)).unwrap(),
+        }
+    }
+
+    Ok(())
+}
+
+fn write_exception_handler(context: &DebugMonitorContext<'_>, out: &mut dyn Write) -> Result<()> {
+    let memory = context.memory;
+
+    let ptr_handler = KtfJvmSupport::current_java_exception_handler(memory)?;
+    if ptr_handler == 0 {
+        writeln!(out, "no java exception handler").unwrap();
+        return Ok(());
+    }
+
+    let handler: RawJavaExceptionHandler = read_generic(memory, ptr_handler)?;
+    let method: RawJavaMethod = read_generic(memory, handler.ptr_method)?;
+    let name = JavaFullName::from_ptr(memory, method.ptr_name)?;
+    writeln!(
+        out,
+        "handler {ptr_handler:#x} in {}.{}{} at java pc {:#x}",
+        read_class_name(memory, method.ptr_class)?,
+        name.name,
+        name.descriptor,
+        handler.current_pc
+    )
+    .unwrap();
+
+    for entry in read_exception_table(memory, &method)? {
+        let class = if entry.ptr_class == 0 {
+            "any".into()
+        } else {
+            read_class_name(memory, entry.ptr_class)?
+        };
+        let active = if entry.from_pc <= handler.current_pc && handler.current_pc < entry.to_pc {
+            '*'
+        } else {
+            ' '
+        };
+
+        writeln!(
+            out,
+            "{active} {:#x}..{:#x} catch {class} at {:#x}",
+            entry.from_pc, entry.to_pc, entry.target
+        )
+        .unwrap();
+    }
+
+    Ok(())
+}
+
+fn read_class_name(memory: &dyn ByteRead, ptr_class: u32) -> Result<String> {
+    let raw: RawJavaClass = read_generic(memory, ptr_class)?;
+    let descriptor: RawJavaClassDescriptor = read_generic(memory, raw.ptr_descriptor)?;
+    let name = read_null_terminated_string_bytes(memory, descriptor.ptr_name)?;
+
+    Ok(String::from_utf8_lossy(&name).replace('/', "."))
+}
+
+// splits `Class.method(descriptor)`, as named by `JavaClassDefinition::add_symbols`, into class and method
+fn parse_method_symbol(name: &str) -> Option<(&str, &str)> {
+    let (path, _) = name.split_at(name.find('(')?);
+    let (class, _) = path.rsplit_once('.')?;
+
+    Some((class, &name[class.len() + 1..]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_method_symbol;
+
+    #[test]
+    fn test_parse_method_symbol() {
+        assert_eq!(parse_method_symbol("net.wie.Main.run(I)V"), Some(("net.wie.Main", "run(I)V")));
+        assert_eq!(parse_method_symbol("Main.<init>()V"), Some(("Main", "<init>()V")));
+        assert_eq!(parse_method_symbol("WIPIC::Kernel::Printk"), None);
+        assert_eq!(parse_method_symbol("thumb_function"), None);
+    }
+}
@@ -24,7 +24,7 @@ use alloc::sync::Arc;
 use wie_core_arm::{
     Allocator, ArmCore, EmulatedFunction, EmulatedFunctionParam, RUN_FUNCTION_LR, RegisteredFunction, RegisteredFunctionHolder, ResultWriter,
 };
-use wie_util::{ByteWrite, Result, WieError, read_generic, write_generic};
+use wie_util::{ByteRead, ByteWrite, Result, WieError, read_generic, write_generic};
 
 use crate::runtime::java::jvm_support::JavaClassDefinition;
 use crate::runtime::{SVC_CATEGORY_JAVA, java::JavaSvcFunctions};
@@ -117,6 +117,17 @@ impl JavaMethod {
         JavaFullName::from_ptr(&self.core, raw.ptr_name)
     }
 
+    /// Address of the compiled body, or of the native function for native methods. 0 if the method has no code.
+    pub fn code_address(&self) -> Result<u32> {
+        let raw: RawJavaMethod = read_generic(&self.core, self.ptr_raw)?;
+
+        if MethodAccessFlags::from_bits_truncate(raw.access_flags).contains(MethodAccessFlags::NATIVE) {
+            Ok(raw.fn_body_native_or_exception_table)
+        } else {
+            Ok(raw.fn_body)
+        }
+    }
+
     pub async fn run(&self, args: Box<[JavaValue]>) -> Result<JavaValue> {
         let raw: RawJavaMethod = read_generic(&self.core, self.ptr_raw)?;
         let return_type = JavaType::parse(&self.descriptor()).as_method().1.clone();
@@ -201,21 +212,7 @@ impl JavaMethod {
     fn exception_table(&self) -> Result<Vec<RawJavaMethodExceptionTableEntry>> {
         let raw: RawJavaMethod = read_generic(&self.core, self.ptr_raw)?;
 
-        let mut result = Vec::with_capacity(raw.exception_table_count as _);
-
-        if raw.exception_table_count == 0 {
-            return Ok(result);
-        }
-
-        let mut cursor = raw.fn_body_native_or_exception_table;
-        for _ in 0..raw.exception_table_count {
-            let address = read_generic(&self.core, cursor)?;
-            cursor += 4;
-
-            result.push(read_generic(&self.core, address)?);
-        }
-
-        Ok(result)
+        read_exception_table(&self.core, &raw)
     }
 
     pub async fn handle_exception(core: &mut ArmCore, jvm: &Jvm, exception: Box<dyn ClassInstance>) -> Result<JavaMethodResult> {
@@ -439,3 +436,24 @@ impl ResultWriter<JavaMethodResult> for JavaMethodResult {
         Ok(())
     }
 }
+
+pub(super) fn read_exception_table<R>(reader: &R, raw: &RawJavaMethod) -> Result<Vec<RawJavaMethodExceptionTableEntry>>
+where
+    R: ?Sized + ByteRead,
+{
+    let mut result = Vec::with_capacity(raw.exception_table_count as _);
+
+    if raw.exception_table_count == 0 {
+        return Ok(result);
+    }
+
+    let mut cursor = raw.fn_body_native_or_exception_table;
+    for _ in 0..raw.exception_table_count {
+        let address = read_generic(reader, cursor)?;
+        cursor += 4;
+
+        result.push(read_generic(reader, address)?);
+    }
+
+    Ok(result)
+}
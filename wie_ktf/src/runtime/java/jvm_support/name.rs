@@ -1,8 +1,7 @@
 use alloc::{string::String, vec::Vec};
 use core::fmt::Display;
 
-use wie_core_arm::ArmCore;
-use wie_util::{read_generic, read_null_terminated_string_bytes};
+use wie_util::{ByteRead, read_generic, read_null_terminated_string_bytes};
 
 use super::Result;
 
@@ -14,10 +13,13 @@ pub struct JavaFullName {
 }
 
 impl JavaFullName {
-    pub fn from_ptr(core: &ArmCore, ptr: u32) -> Result<Self> {
-        let tag = read_generic(core, ptr)?;
+    pub fn from_ptr<R>(reader: &R, ptr: u32) -> Result<Self>
+    where
+        R: ?Sized + ByteRead,
+    {
+        let tag = read_generic(reader, ptr)?;
 
-        let value = read_null_terminated_string_bytes(core, ptr + 1)?;
+        let value = read_null_terminated_string_bytes(reader, ptr + 1)?;
         let value = String::from_utf8(value).unwrap();
         let mut values = value.split('+');
 
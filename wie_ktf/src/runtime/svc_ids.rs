@@ -1,6 +1,6 @@
 use wie_core_arm::SvcId;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(u32)]
 pub enum InitSvcId {
     GetInterface = 0,
@@ -35,7 +35,7 @@ impl From<InitSvcId> for u32 {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(u32)]
 pub enum JavaSvcId {
     JavaJump1 = 7,
@@ -80,7 +80,7 @@ impl From<JavaSvcId> for u32 {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(u16)]
 pub enum WIPICKernelMethodId {
     Printk = 0,
@@ -235,7 +235,7 @@ impl TryFrom<u16> for WIPICKernelMethodId {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(u16)]
 pub enum WIPICGraphicsMethodId {
     GetImageProperty = 0,
@@ -380,7 +380,7 @@ impl TryFrom<u16> for WIPICGraphicsMethodId {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(u16)]
 pub enum WIPICDatabaseMethodId {
     OpenDatabase = 0,
@@ -443,7 +443,7 @@ impl TryFrom<u16> for WIPICDatabaseMethodId {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[repr(u32)]
 pub enum WIPICTableId {
     Kernel = 0,
@@ -1,4 +1,4 @@
-use alloc::{boxed::Box, vec};
+use alloc::{boxed::Box, format, string::String};
 
 use jvm::Jvm;
 use wie_backend::System;
@@ -7,7 +7,7 @@ use wie_util::{Result, WieError};
 use wie_wipi_c::{WIPICMethodBody, WIPICResult};
 
 use crate::runtime::SVC_CATEGORY_WIPIC;
-use crate::runtime::svc_ids::{WIPICKernelMethodId, WIPICTableId};
+use crate::runtime::svc_ids::{WIPICDatabaseMethodId, WIPICGraphicsMethodId, WIPICKernelMethodId, WIPICTableId};
 
 mod context;
 pub mod interface;
@@ -36,20 +36,9 @@ struct CMethodProxy {
 #[async_trait::async_trait]
 impl EmulatedFunction<(), WIPICMethodResult, ()> for CMethodProxy {
     async fn call(&self, core: &mut ArmCore, _: &mut ()) -> Result<WIPICMethodResult> {
-        let a0 = u32::get(core, 0);
-        let a1 = u32::get(core, 1);
-        let a2 = u32::get(core, 2);
-        let a3 = u32::get(core, 3);
-        let a4 = u32::get(core, 4);
-        let a5 = u32::get(core, 5);
-        let a6 = u32::get(core, 6);
-        let a7 = u32::get(core, 7);
-        let a8 = u32::get(core, 8);
-
-        let result = self
-            .body
-            .call(&mut self.context.clone(), vec![a0, a1, a2, a3, a4, a5, a6, a7, a8].into_boxed_slice())
-            .await?;
+        let args = (0..self.body.param_count()).map(|x| u32::get(core, x)).collect();
+
+        let result = self.body.call(&mut self.context.clone(), args).await?;
 
         Ok(WIPICMethodResult { result })
     }
@@ -81,5 +70,24 @@ async fn handle_wipic_svc(core: &mut ArmCore, (system, jvm): &mut (System, Jvm),
 }
 
 pub fn register_wipic_svc_handler(core: &mut ArmCore, system: &System, jvm: &Jvm) -> Result<()> {
-    core.register_svc_handler(SVC_CATEGORY_WIPIC, handle_wipic_svc, &(system.clone(), jvm.clone()))
+    core.register_svc_handler(SVC_CATEGORY_WIPIC, handle_wipic_svc, &(system.clone(), jvm.clone()))?;
+    core.set_svc_names(SVC_CATEGORY_WIPIC, wipic_svc_name);
+
+    Ok(())
+}
+
+fn wipic_svc_name(id: u32) -> String {
+    let Ok(table_id) = WIPICTableId::try_from(id >> 16) else {
+        return format!("WIPIC::{id:#x}");
+    };
+    let function_id = id as u16;
+
+    let function = match table_id {
+        WIPICTableId::Kernel => WIPICKernelMethodId::try_from(function_id).ok().map(|x| format!("{x:?}")),
+        WIPICTableId::Graphics => WIPICGraphicsMethodId::try_from(function_id).ok().map(|x| format!("{x:?}")),
+        WIPICTableId::Database => WIPICDatabaseMethodId::try_from(function_id).ok().map(|x| format!("{x:?}")),
+        _ => None,
+    };
+
+    format!("WIPIC::{table_id:?}::{}", function.unwrap_or_else(|| format!("{function_id}")))
 }
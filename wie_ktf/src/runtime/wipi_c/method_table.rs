@@ -301,34 +301,34 @@ pub fn get_net_method_table() -> Vec<WIPICMethodBody> {
     vec![
         net::connect.into_body(),
         net::close.into_body(),
-        gen_stub(2, "MC_netSocket"),
-        gen_stub(3, "MC_netSocketConnect"),
-        gen_stub(4, "MC_netSocketWrite"),
-        gen_stub(5, "MC_netSocketRead"),
+        net::socket.into_body(),
+        net::socket_connect.into_body(),
+        net::socket_write.into_body(),
+        net::socket_read.into_body(),
         net::socket_close.into_body(),
         gen_stub(7, "MC_netSocketBind"),
         gen_stub(8, "MC_netGetMaxPacketLength"),
         gen_stub(9, "MC_netSocketSendTo"),
         gen_stub(10, "MC_netSocketRcvFrom"),
-        gen_stub(11, "MC_netGetHostAddr"),
+        net::get_host_addr.into_body(),
         gen_stub(12, "MC_netSocketAccept"),
-        gen_stub(13, "MC_netSetReadCB"),
+        net::set_read_cb.into_body(),
         gen_stub(14, "MC_netSetWriteCB"),
-        gen_stub(15, "MC_netHttpOpen"),
-        gen_stub(16, "MC_netHttpConnect"),
-        gen_stub(17, "MC_netHttpSetRequestMethod"),
-        gen_stub(18, "MC_netHttpGetRequestMethod"),
-        gen_stub(19, "MC_netHttpSetRequestProperty"),
-        gen_stub(20, "MC_netHttpGetRequestProperty"),
+        net::http_open.into_body(),
+        net::http_connect.into_body(),
+        net::http_set_request_method.into_body(),
+        net::http_get_request_method.into_body(),
+        net::http_set_request_property.into_body(),
+        net::http_get_request_property.into_body(),
         gen_stub(21, "MC_netHttpSetProxy"),
         gen_stub(22, "MC_netHttpGetProxy"),
-        gen_stub(23, "MC_netHttpGetResponseCode"),
-        gen_stub(24, "MC_netHttpGetResponseMessage"),
-        gen_stub(25, "MC_netHttpGetHeaderField"),
-        gen_stub(26, "MC_netHttpGetLength"),
-        gen_stub(27, "MC_netHttpGetType"),
-        gen_stub(28, "MC_netHttpGetEncoding"),
-        gen_stub(29, "MC_netHttpClose"),
+        net::http_get_response_code.into_body(),
+        net::http_get_response_message.into_body(),
+        net::http_get_header_field.into_body(),
+        net::http_get_length.into_body(),
+        net::http_get_type.into_body(),
+        net::http_get_encoding.into_body(),
+        net::http_close.into_body(),
     ]
 }
 
@@ -4,7 +4,7 @@ use alloc::{borrow::ToOwned, boxed::Box, collections::BTreeMap, format, string::
 
 use jvm::runtime::{JavaIoInputStream, JavaLangClassLoader};
 
-use wie_backend::{Emulator, Event, Options, Platform, System, TaskRunner, extract_zip};
+use wie_backend::{Emulator, Event, HandsetProfile, Options, Platform, Snapshot, System, TaskRunner, extract_zip};
 use wie_core_arm::{Allocator, ArmCore};
 use wie_jvm_support::{JvmSupport, RustJavaJvmImplementation};
 use wie_util::{Result, WieError};
@@ -28,7 +28,7 @@ pub struct LgtEmulator {
 }
 
 impl LgtEmulator {
-    pub fn from_archive(platform: Box<dyn Platform>, files: BTreeMap<String, Vec<u8>>, options: Options) -> Result<Self> {
+    pub fn from_archive(platform: Box<dyn Platform>, handset: HandsetProfile, files: BTreeMap<String, Vec<u8>>, options: Options) -> Result<Self> {
         let app_info = files
             .get("app_info")
             .ok_or_else(|| WieError::FatalError("Missing app_info in LGT archive".into()))?;
@@ -40,6 +40,7 @@ impl LgtEmulator {
 
         Self::load(
             platform,
+            handset,
             &jar_filename,
             &app_info.pid,
             &app_info.aid,
@@ -49,8 +50,10 @@ impl LgtEmulator {
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn from_jar(
         platform: Box<dyn Platform>,
+        handset: HandsetProfile,
         jar_filename: &str,
         jar: Vec<u8>,
         pid: &str,
@@ -60,7 +63,7 @@ impl LgtEmulator {
     ) -> Result<Self> {
         let files = [(jar_filename.to_owned(), jar)].into_iter().collect();
 
-        Self::load(platform, jar_filename, pid, aid, main_class_name, &files, options)
+        Self::load(platform, handset, jar_filename, pid, aid, main_class_name, &files, options)
     }
 
     pub fn loadable_archive(files: &BTreeMap<String, Vec<u8>>) -> bool {
@@ -75,8 +78,10 @@ impl LgtEmulator {
         files.contains_key("binary.mod")
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn load(
         platform: Box<dyn Platform>,
+        handset: HandsetProfile,
         jar_filename: &str,
         pid: &str,
         aid: &str,
@@ -84,8 +89,22 @@ impl LgtEmulator {
         files: &BTreeMap<String, Vec<u8>>,
         mut options: Options,
     ) -> Result<Self> {
-        let mut core = ArmCore::new(options.enable_gdbserver, options.profile.take())?;
-        let system = System::new(platform, pid, aid, LgtTaskRunner { core: core.clone() });
+        // the gdb server needs the plain interpreter, so it wins over the block cache
+        let profile = options.profile.take();
+        let mut core = if options.enable_block_cache && !options.enable_gdbserver {
+            ArmCore::with_block_cache(profile)?
+        } else {
+            ArmCore::new(options.enable_gdbserver, profile)?
+        };
+        if let Some(symbols) = options.symbols.take() {
+            core.set_symbols(symbols);
+        }
+        if let Some(svc_trace) = options.svc_trace.take() {
+            core.set_svc_trace(svc_trace);
+        }
+        let mut system = System::new(platform, handset, pid, aid, LgtTaskRunner { core: core.clone() });
+        system.load_game_config(files)?;
+        let main_class_name = system.game_config().main_class.clone().or(main_class_name);
 
         for (filename, data) in files {
             let filename = filename.trim_start_matches("P/");
@@ -127,7 +146,7 @@ impl LgtEmulator {
 
 impl Emulator for LgtEmulator {
     fn handle_event(&mut self, event: Event) {
-        self.system.event_queue().push(event)
+        self.system.handle_event(event)
     }
 
     fn tick(&mut self) -> Result<()> {
@@ -139,6 +158,21 @@ impl Emulator for LgtEmulator {
             }
         })
     }
+
+    fn save_checkpoint(&self) -> Result<Vec<u8>> {
+        let mut checkpoint = Snapshot::new();
+        checkpoint.insert("system", self.system.save_checkpoint()?);
+        checkpoint.insert("arm", self.core.state_digest()?.to_vec());
+
+        Ok(checkpoint.to_bytes())
+    }
+
+    fn replay_checkpoint(&mut self, data: &[u8]) -> Result<()> {
+        let checkpoint = Snapshot::from_bytes(data)?;
+
+        self.system.replay_checkpoint(checkpoint.get("system")?)?;
+        self.core.check_state_digest(checkpoint.get("arm")?)
+    }
 }
 
 // almost similar to KtfAdf.. can we merge these?
@@ -1,4 +1,4 @@
-use alloc::format;
+use alloc::{format, vec::Vec};
 use core::mem::size_of;
 
 use elf::{ElfBytes, endian::AnyEndian};
@@ -6,7 +6,7 @@ use elf::{ElfBytes, endian::AnyEndian};
 use jvm::Jvm;
 use wipi_types::lgt::{InitParam1, InitParam2, InitStruct};
 
-use wie_backend::System;
+use wie_backend::{Quirk, System};
 use wie_core_arm::{Allocator, ArmCore, EmulatedFunction, ResultWriter, SvcId};
 use wie_util::{Result, WieError, read_generic, write_generic};
 
@@ -22,7 +22,13 @@ use super::{
 };
 
 fn register_init_svc_handler(core: &mut ArmCore) -> Result<()> {
-    core.register_svc_handler(SVC_CATEGORY_INIT, handle_init_svc, &(SVC_CATEGORY_WIPIC, SVC_CATEGORY_STDLIB))
+    core.register_svc_handler(SVC_CATEGORY_INIT, handle_init_svc, &(SVC_CATEGORY_WIPIC, SVC_CATEGORY_STDLIB))?;
+    core.set_svc_names(SVC_CATEGORY_INIT, |id| match InitSvcId::try_from(SvcId(id)) {
+        Ok(x) => format!("Init::{x:?}"),
+        Err(_) => format!("Init::{id:#x}"),
+    });
+
+    Ok(())
 }
 
 async fn handle_init_svc(core: &mut ArmCore, (wipic_category, stdlib_category): &mut (u32, u32), id: SvcId) -> Result<()> {
@@ -48,7 +54,16 @@ async fn handle_init_svc(core: &mut ArmCore, (wipic_category, stdlib_category):
 }
 
 pub async fn load_native(core: &mut ArmCore, system: &mut System, jvm: &Jvm, data: &[u8]) -> Result<()> {
-    let entrypoint = load_executable(core, data)?;
+    let (entrypoint, code_ranges) = load_executable(core, data)?;
+    if let Some(&(address, _)) = code_ranges.first() {
+        core.add_library("binary.mod", address);
+    }
+
+    // binary.mod is linked at fixed addresses, so the patterns are matched where its code sections were loaded.
+    if !system.game_config().has_quirk(Quirk::NoBinaryPatches) {
+        wie_core_arm::install_binary_patches(core, data, &code_ranges)?;
+    }
+
     register_wipic_svc_handler(core, system, jvm)?;
     register_stdlib_svc_handler(core, system)?;
     register_init_svc_handler(core)?;
@@ -121,7 +136,8 @@ async fn get_import_function(core: &mut ArmCore, wipic_category: u32, stdlib_cat
     })
 }
 
-fn load_executable(core: &mut ArmCore, data: &[u8]) -> Result<u32> {
+/// Loads the sections of `binary.mod`, returning the entrypoint and the `(base, size)` ranges of the code sections.
+fn load_executable(core: &mut ArmCore, data: &[u8]) -> Result<(u32, Vec<(u32, u32)>)> {
     let elf = ElfBytes::<AnyEndian>::minimal_parse(data).map_err(|x| WieError::FatalError(format!("Failed to parse ELF binary.mod: {x}")))?;
 
     if elf.ehdr.e_machine != elf::abi::EM_ARM {
@@ -140,6 +156,7 @@ fn load_executable(core: &mut ArmCore, data: &[u8]) -> Result<u32> {
     let shdrs = shdrs_opt.ok_or_else(|| WieError::FatalError("ELF is missing section headers".into()))?;
     let strtab = strtab_opt.ok_or_else(|| WieError::FatalError("ELF is missing section name string table".into()))?;
 
+    let mut code_ranges = Vec::new();
     for shdr in shdrs {
         let section_name = strtab
             .get(shdr.sh_name as usize)
@@ -154,12 +171,32 @@ fn load_executable(core: &mut ArmCore, data: &[u8]) -> Result<u32> {
                 .0;
 
             core.load(data, shdr.sh_addr as u32, shdr.sh_size as usize)?;
+            if shdr.sh_flags & elf::abi::SHF_EXECINSTR as u64 != 0 {
+                code_ranges.push((shdr.sh_addr as u32, data.len() as u32));
+            }
+        }
+    }
+
+    if let Some(symbols) = core.symbols() {
+        let symbol_table = elf
+            .symbol_table()
+            .map_err(|x| WieError::FatalError(format!("Failed to read ELF symbol table: {x}")))?;
+
+        // stripped binaries have no symbol table
+        if let Some((symbol_table, string_table)) = symbol_table {
+            for symbol in symbol_table.iter().filter(|x| x.st_symtype() == elf::abi::STT_FUNC && x.st_name != 0) {
+                let name = string_table
+                    .get(symbol.st_name as usize)
+                    .map_err(|x| WieError::FatalError(format!("Invalid ELF symbol name index {}: {x}", symbol.st_name)))?;
+
+                symbols.add(symbol.st_value as u32, symbol.st_size as u32, name);
+            }
         }
     }
 
     tracing::debug!("Entrypoint: {:#x}", elf.ehdr.e_entry);
 
-    Ok(elf.ehdr.e_entry as u32)
+    Ok((elf.ehdr.e_entry as u32, code_ranges))
 }
 
 async fn unk0(_core: &mut ArmCore, _: &mut (), a0: u32, a1: u32, a2: u32, a3: u32) -> Result<()> {
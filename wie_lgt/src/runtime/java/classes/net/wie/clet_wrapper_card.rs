@@ -21,6 +21,7 @@ impl CletWrapperCard {
                 JavaMethodProto::new("<init>", "(II)V", Self::init, Default::default()),
                 JavaMethodProto::new("paint", "(Lorg/kwis/msp/lcdui/Graphics;)V", Self::paint, Default::default()),
                 JavaMethodProto::new("keyNotify", "(II)Z", Self::key_notify, Default::default()),
+                JavaMethodProto::new("pointerNotify", "(III)Z", Self::pointer_notify, Default::default()),
                 JavaMethodProto::new("notifyEvent", "(III)V", Self::notify_event, Default::default()),
             ],
             fields: vec![
@@ -79,6 +80,20 @@ impl CletWrapperCard {
         Ok(true)
     }
 
+    // clets get pointer input as MV_POINTER_EVENT, whose code isn't confirmed against the wipi c headers yet
+    async fn pointer_notify(
+        _jvm: &Jvm,
+        _context: &mut CletWrapperContext,
+        this: ClassInstanceRef<Self>,
+        r#type: i32,
+        x: i32,
+        y: i32,
+    ) -> JvmResult<bool> {
+        tracing::warn!("stub net.wie.CletWrapperCard::pointerNotify({this:?}, {type}, {x}, {y})");
+
+        Ok(false)
+    }
+
     async fn notify_event(
         jvm: &Jvm,
         context: &mut CletWrapperContext,
@@ -31,7 +31,13 @@ pub fn register_stdlib_svc_handler(core: &mut ArmCore, system: &System) -> Resul
         }
     }
 
-    core.register_svc_handler(SVC_CATEGORY_STDLIB, handle_stdlib_svc, system)
+    core.register_svc_handler(SVC_CATEGORY_STDLIB, handle_stdlib_svc, system)?;
+    core.set_svc_names(SVC_CATEGORY_STDLIB, |id| match StdlibSvcId::try_from(SvcId(id)) {
+        Ok(x) => format!("Stdlib::{x:?}"),
+        Err(_) => format!("Stdlib::{id:#x}"),
+    });
+
+    Ok(())
 }
 
 async fn strncpy(core: &mut ArmCore, _: &mut (), ptr_dst: u32, ptr_src: u32, size: u32) -> Result<()> {
@@ -1,6 +1,6 @@
 use wie_core_arm::SvcId;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(u32)]
 pub enum InitSvcId {
     GetImportTable = 0,
@@ -47,7 +47,7 @@ impl From<InitSvcId> for u32 {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(u32)]
 pub enum WIPICSvcId {
     CletRegister = 0x03,
@@ -258,7 +258,7 @@ impl From<WIPICSvcId> for u32 {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 #[repr(u32)]
 pub enum StdlibSvcId {
     Unk2 = 0x3f6,
@@ -277,6 +277,30 @@ pub enum StdlibSvcId {
     Unk3 = 0x424,
 }
 
+impl TryFrom<SvcId> for StdlibSvcId {
+    type Error = wie_util::WieError;
+
+    fn try_from(value: SvcId) -> Result<Self, Self::Error> {
+        Ok(match value.0 {
+            0x3f6 => Self::Unk2,
+            0x3fb => Self::Atoi,
+            0x405 => Self::Strcpy,
+            0x406 => Self::Strncpy,
+            0x407 => Self::Strcat,
+            0x409 => Self::Strcmp,
+            0x40a => Self::Unk4,
+            0x410 => Self::Unk5,
+            0x411 => Self::Strlen,
+            0x414 => Self::Memcpy,
+            0x418 => Self::Memset,
+            0x41a => Self::Time,
+            0x420 => Self::Localtime,
+            0x424 => Self::Unk3,
+            _ => return Err(wie_util::WieError::FatalError(alloc::format!("Unknown LGT stdlib SVC id {}", value.0))),
+        })
+    }
+}
+
 impl From<StdlibSvcId> for u32 {
     fn from(value: StdlibSvcId) -> Self {
         value as u32
@@ -1,4 +1,9 @@
-use alloc::{boxed::Box, string::ToString, vec};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+};
 
 mod context;
 
@@ -159,27 +164,26 @@ async fn handle_wipic_svc(core: &mut ArmCore, (system, jvm): &mut (System, Jvm),
 #[async_trait::async_trait]
 impl EmulatedFunction<(), WIPICMethodResult, ()> for CMethodProxy {
     async fn call(&self, core: &mut ArmCore, _: &mut ()) -> Result<WIPICMethodResult> {
-        let a0 = u32::get(core, 0);
-        let a1 = u32::get(core, 1);
-        let a2 = u32::get(core, 2);
-        let a3 = u32::get(core, 3);
-        let a4 = u32::get(core, 4);
-        let a5 = u32::get(core, 5);
-        let a6 = u32::get(core, 6);
-        let a7 = u32::get(core, 7);
-        let a8 = u32::get(core, 8);
-
-        let result = self
-            .body
-            .call(&mut self.context.clone(), vec![a0, a1, a2, a3, a4, a5, a6, a7, a8].into_boxed_slice())
-            .await?;
+        let args = (0..self.body.param_count()).map(|x| u32::get(core, x)).collect();
+
+        let result = self.body.call(&mut self.context.clone(), args).await?;
 
         Ok(WIPICMethodResult { result })
     }
 }
 
 pub fn register_wipic_svc_handler(core: &mut ArmCore, system: &System, jvm: &Jvm) -> Result<()> {
-    core.register_svc_handler(SVC_CATEGORY_WIPIC, handle_wipic_svc, &(system.clone(), jvm.clone()))
+    core.register_svc_handler(SVC_CATEGORY_WIPIC, handle_wipic_svc, &(system.clone(), jvm.clone()))?;
+    core.set_svc_names(SVC_CATEGORY_WIPIC, wipic_svc_name);
+
+    Ok(())
+}
+
+fn wipic_svc_name(id: u32) -> String {
+    match WIPICSvcId::try_from(SvcId(id)) {
+        Ok(x) => format!("WIPIC::{x:?}"),
+        Err(_) => format!("WIPIC::{id:#x}"),
+    }
 }
 
 async fn clet_register(core: &mut ArmCore, jvm: &mut Jvm, function_table: u32, a1: u32) -> Result<()> {
@@ -7,44 +7,94 @@ use std::{
 };
 
 use test_utils::{TestPlatform, TestPlatformEvent};
-use wie_backend::{Emulator, Options, extract_zip};
+use wie_backend::{Emulator, Event, HandsetProfile, Instant, Options, VirtualClock, extract_zip};
 use wie_lgt::LgtEmulator;
 use wie_util::Result;
 
-#[test]
-pub fn test_helloworld() -> Result<()> {
-    let stdout = Arc::new(Mutex::new(Vec::new()));
-    let exited = Arc::new(AtomicBool::new(false));
-
-    let stdout_clone = stdout.clone();
-    let exited_clone = exited.clone();
-    let event_handler = move |event| match event {
-        TestPlatformEvent::Stdout(buf) => {
-            stdout_clone.lock().unwrap().extend(buf);
+struct Helloworld {
+    emulator: LgtEmulator,
+    stdout: Arc<Mutex<Vec<u8>>>,
+    exited: Arc<AtomicBool>,
+}
+
+impl Helloworld {
+    fn load(enable_block_cache: bool, clock: Option<VirtualClock>) -> Result<Self> {
+        let stdout = Arc::new(Mutex::new(Vec::new()));
+        let exited = Arc::new(AtomicBool::new(false));
+
+        let stdout_clone = stdout.clone();
+        let exited_clone = exited.clone();
+        let event_handler = move |event| match event {
+            TestPlatformEvent::Stdout(buf) => {
+                stdout_clone.lock().unwrap().extend(buf);
+            }
+            TestPlatformEvent::Exit => {
+                exited_clone.store(true, Ordering::SeqCst);
+            }
+        };
+
+        let mut platform = TestPlatform::with_event_handler(event_handler);
+        if let Some(clock) = clock {
+            platform = platform.with_virtual_clock(clock);
         }
-        TestPlatformEvent::Exit => {
-            exited_clone.store(true, Ordering::SeqCst);
+
+        let archive = extract_zip(include_bytes!("../../test_data/helloworld_lgt.zip"))?;
+        let emulator = LgtEmulator::from_archive(
+            Box::new(platform),
+            HandsetProfile::default(),
+            archive,
+            Options {
+                enable_gdbserver: false,
+                enable_block_cache,
+                profile: None,
+                symbols: None,
+                svc_trace: None,
+            },
+        )?;
+
+        Ok(Self { emulator, stdout, exited })
+    }
+
+    fn run_until_exit(&mut self) -> Result<String> {
+        while !self.exited.load(Ordering::SeqCst) {
+            self.emulator.tick()?;
         }
-    };
-
-    let platform = Box::new(TestPlatform::with_event_handler(event_handler));
-
-    let archive = extract_zip(include_bytes!("../../test_data/helloworld_lgt.zip"))?;
-    let mut emulator = LgtEmulator::from_archive(
-        platform,
-        archive,
-        Options {
-            enable_gdbserver: false,
-            profile: None,
-        },
-    )?;
-
-    while !exited.load(Ordering::SeqCst) {
-        emulator.tick()?;
+
+        Ok(String::from_utf8(self.stdout.lock().unwrap().clone()).unwrap())
     }
+}
+
+#[test]
+pub fn test_helloworld() -> Result<()> {
+    let mut helloworld = Helloworld::load(false, None)?;
+    assert_eq!(helloworld.run_until_exit()?, "Hello, world!");
+
+    Ok(())
+}
+
+#[test]
+pub fn test_helloworld_block_cache() -> Result<()> {
+    let mut helloworld = Helloworld::load(true, None)?;
+    assert_eq!(helloworld.run_until_exit()?, "Hello, world!");
+
+    Ok(())
+}
+
+#[test]
+pub fn test_helloworld_checkpoint() -> Result<()> {
+    let epoch = Instant::from_epoch_millis(1_000_000);
+
+    let clock = VirtualClock::new(epoch);
+    let mut helloworld = Helloworld::load(false, Some(clock.clone()))?;
+    helloworld.emulator.handle_event(Event::Redraw);
+    helloworld.emulator.tick()?;
+    clock.advance(16);
+    let checkpoint = helloworld.emulator.save_checkpoint()?;
 
-    let stdout_str = String::from_utf8(stdout.lock().unwrap().clone()).unwrap();
-    assert_eq!(stdout_str, "Hello, world!");
+    let mut restored = Helloworld::load(false, Some(VirtualClock::new(epoch)))?;
+    restored.emulator.replay_checkpoint(&checkpoint)?;
+    assert_eq!(restored.emulator.save_checkpoint()?, checkpoint);
+    assert_eq!(restored.run_until_exit()?, "Hello, world!");
 
     Ok(())
 }
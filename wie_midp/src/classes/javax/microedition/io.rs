@@ -0,0 +1,15 @@
+mod connection;
+mod connection_not_found_exception;
+mod connector;
+mod content_connection;
+mod http_connection;
+mod input_connection;
+mod output_connection;
+mod socket_connection;
+mod stream_connection;
+
+pub use self::{
+    connection::Connection, connection_not_found_exception::ConnectionNotFoundException, connector::Connector, content_connection::ContentConnection,
+    http_connection::HttpConnection, input_connection::InputConnection, output_connection::OutputConnection, socket_connection::SocketConnection,
+    stream_connection::StreamConnection,
+};
@@ -0,0 +1,233 @@
+use alloc::vec;
+
+use java_class_proto::JavaMethodProto;
+use java_constants::MethodAccessFlags;
+use java_runtime::classes::java::{
+    io::{DataInputStream, DataOutputStream, InputStream, OutputStream},
+    lang::String,
+};
+use jvm::{ClassInstanceRef, Jvm, Result as JvmResult, runtime::JavaLangString};
+
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use crate::classes::javax::microedition::io::Connection;
+
+// class javax.microedition.io.Connector
+pub struct Connector;
+
+impl Connector {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "javax/microedition/io/Connector",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec![],
+            methods: vec![
+                JavaMethodProto::new(
+                    "open",
+                    "(Ljava/lang/String;)Ljavax/microedition/io/Connection;",
+                    Self::open,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "open",
+                    "(Ljava/lang/String;I)Ljavax/microedition/io/Connection;",
+                    Self::open_with_mode,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "open",
+                    "(Ljava/lang/String;IZ)Ljavax/microedition/io/Connection;",
+                    Self::open_with_mode_timeouts,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "openInputStream",
+                    "(Ljava/lang/String;)Ljava/io/InputStream;",
+                    Self::open_input_stream,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "openDataInputStream",
+                    "(Ljava/lang/String;)Ljava/io/DataInputStream;",
+                    Self::open_data_input_stream,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "openOutputStream",
+                    "(Ljava/lang/String;)Ljava/io/OutputStream;",
+                    Self::open_output_stream,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "openDataOutputStream",
+                    "(Ljava/lang/String;)Ljava/io/DataOutputStream;",
+                    Self::open_data_output_stream,
+                    MethodAccessFlags::STATIC,
+                ),
+            ],
+            fields: vec![],
+            access_flags: Default::default(),
+        }
+    }
+
+    async fn open(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<Connection>> {
+        tracing::debug!("javax.microedition.io.Connector::open({name:?})");
+
+        Self::open_with_mode_timeouts(jvm, context, name, 3, false).await // READ_WRITE
+    }
+
+    async fn open_with_mode(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        name: ClassInstanceRef<String>,
+        mode: i32,
+    ) -> JvmResult<ClassInstanceRef<Connection>> {
+        tracing::debug!("javax.microedition.io.Connector::open({name:?}, {mode})");
+
+        Self::open_with_mode_timeouts(jvm, context, name, mode, false).await
+    }
+
+    async fn open_with_mode_timeouts(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        name: ClassInstanceRef<String>,
+        mode: i32,
+        timeouts: bool,
+    ) -> JvmResult<ClassInstanceRef<Connection>> {
+        tracing::debug!("javax.microedition.io.Connector::open({name:?}, {mode}, {timeouts})");
+
+        let url = JavaLangString::to_rust_string(jvm, &name).await?;
+        let (scheme, address) = url.split_once("://").unwrap_or_default();
+
+        let connection = match scheme.to_ascii_lowercase().as_str() {
+            "http" => jvm.new_class("net/wie/HttpConnectionImpl", "(Ljava/lang/String;)V", (name,)).await?,
+            "socket" => {
+                let address = JavaLangString::from_rust_string(jvm, address).await?;
+
+                jvm.new_class("net/wie/SocketConnectionImpl", "(Ljava/lang/String;)V", (address,)).await?
+            }
+            _ => {
+                tracing::warn!("Unsupported connection {url}");
+
+                return Err(jvm.exception("javax/microedition/io/ConnectionNotFoundException", &url).await);
+            }
+        };
+
+        Ok(connection.into())
+    }
+
+    async fn open_input_stream(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<InputStream>> {
+        tracing::debug!("javax.microedition.io.Connector::openInputStream({name:?})");
+
+        let connection = Self::open_with_mode_timeouts(jvm, context, name, 1, false).await?; // READ
+        let mut stream: ClassInstanceRef<InputStream> = jvm.invoke_virtual(&connection, "openInputStream", "()Ljava/io/InputStream;", ()).await?;
+
+        // nobody else holds the connection, so closing the stream has to release it
+        jvm.put_field(&mut stream, "ownsConnection", "Z", true).await?;
+
+        Ok(stream)
+    }
+
+    async fn open_data_input_stream(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        name: ClassInstanceRef<String>,
+    ) -> JvmResult<ClassInstanceRef<DataInputStream>> {
+        tracing::debug!("javax.microedition.io.Connector::openDataInputStream({name:?})");
+
+        let input_stream = Self::open_input_stream(jvm, context, name).await?;
+
+        Ok(jvm
+            .new_class("java/io/DataInputStream", "(Ljava/io/InputStream;)V", (input_stream,))
+            .await?
+            .into())
+    }
+
+    async fn open_output_stream(jvm: &Jvm, context: &mut WieJvmContext, name: ClassInstanceRef<String>) -> JvmResult<ClassInstanceRef<OutputStream>> {
+        tracing::debug!("javax.microedition.io.Connector::openOutputStream({name:?})");
+
+        let connection = Self::open_with_mode_timeouts(jvm, context, name, 2, false).await?; // WRITE
+        let mut stream: ClassInstanceRef<OutputStream> = jvm
+            .invoke_virtual(&connection, "openOutputStream", "()Ljava/io/OutputStream;", ())
+            .await?;
+
+        // nobody else holds the connection, so closing the stream has to release it
+        jvm.put_field(&mut stream, "ownsConnection", "Z", true).await?;
+
+        Ok(stream)
+    }
+
+    async fn open_data_output_stream(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        name: ClassInstanceRef<String>,
+    ) -> JvmResult<ClassInstanceRef<DataOutputStream>> {
+        tracing::debug!("javax.microedition.io.Connector::openDataOutputStream({name:?})");
+
+        let output_stream = Self::open_output_stream(jvm, context, name).await?;
+
+        Ok(jvm
+            .new_class("java/io/DataOutputStream", "(Ljava/io/OutputStream;)V", (output_stream,))
+            .await?
+            .into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::boxed::Box;
+
+    use java_runtime::classes::java::{
+        io::{InputStream, OutputStream},
+        lang::String,
+    };
+    use jvm::{ClassInstanceRef, runtime::JavaLangString};
+
+    use test_utils::run_jvm_test;
+    use wie_util::Result;
+
+    use crate::{classes::javax::microedition::io::Connection, get_protos};
+
+    #[test]
+    fn test_http_connection() -> Result<()> {
+        run_jvm_test(Box::new([get_protos().into()]), |jvm| async move {
+            let url = JavaLangString::from_rust_string(&jvm, "http://localhost/shop").await?;
+            let connection: ClassInstanceRef<Connection> = jvm
+                .invoke_static(
+                    "javax/microedition/io/Connector",
+                    "open",
+                    "(Ljava/lang/String;)Ljavax/microedition/io/Connection;",
+                    (url,),
+                )
+                .await?;
+
+            let method = JavaLangString::from_rust_string(&jvm, "POST").await?;
+            let _: () = jvm
+                .invoke_virtual(&connection, "setRequestMethod", "(Ljava/lang/String;)V", (method,))
+                .await?;
+
+            let output: ClassInstanceRef<OutputStream> = jvm
+                .invoke_virtual(&connection, "openOutputStream", "()Ljava/io/OutputStream;", ())
+                .await?;
+            let _: () = jvm.invoke_virtual(&output, "write", "(I)V", (0x41,)).await?;
+
+            let code: i32 = jvm.invoke_virtual(&connection, "getResponseCode", "()I", ()).await?;
+            assert_eq!(code, 200);
+
+            let name = JavaLangString::from_rust_string(&jvm, "x-method").await?;
+            let value: ClassInstanceRef<String> = jvm
+                .invoke_virtual(&connection, "getHeaderField", "(Ljava/lang/String;)Ljava/lang/String;", (name,))
+                .await?;
+            assert_eq!(JavaLangString::to_rust_string(&jvm, &value).await?, "POST");
+
+            let input: ClassInstanceRef<InputStream> = jvm.invoke_virtual(&connection, "openInputStream", "()Ljava/io/InputStream;", ()).await?;
+            let first: i32 = jvm.invoke_virtual(&input, "read", "()I", ()).await?;
+            let second: i32 = jvm.invoke_virtual(&input, "read", "()I", ()).await?;
+            assert_eq!((first, second), (0x41, -1));
+
+            let _: () = jvm.invoke_virtual(&connection, "close", "()V", ()).await?;
+
+            Ok(())
+        })
+    }
+}
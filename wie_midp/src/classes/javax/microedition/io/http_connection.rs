@@ -0,0 +1,34 @@
+use alloc::vec;
+
+use java_class_proto::JavaMethodProto;
+
+use java_constants::ClassAccessFlags;
+use wie_jvm_support::WieJavaClassProto;
+
+// interface javax.microedition.io.HttpConnection
+pub struct HttpConnection;
+
+impl HttpConnection {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "javax/microedition/io/HttpConnection",
+            parent_class: None,
+            interfaces: vec!["javax/microedition/io/ContentConnection"],
+            methods: vec![
+                JavaMethodProto::new_abstract("getURL", "()Ljava/lang/String;", Default::default()),
+                JavaMethodProto::new_abstract("getRequestMethod", "()Ljava/lang/String;", Default::default()),
+                JavaMethodProto::new_abstract("setRequestMethod", "(Ljava/lang/String;)V", Default::default()),
+                JavaMethodProto::new_abstract("getRequestProperty", "(Ljava/lang/String;)Ljava/lang/String;", Default::default()),
+                JavaMethodProto::new_abstract("setRequestProperty", "(Ljava/lang/String;Ljava/lang/String;)V", Default::default()),
+                JavaMethodProto::new_abstract("getResponseCode", "()I", Default::default()),
+                JavaMethodProto::new_abstract("getResponseMessage", "()Ljava/lang/String;", Default::default()),
+                JavaMethodProto::new_abstract("getHeaderField", "(Ljava/lang/String;)Ljava/lang/String;", Default::default()),
+                JavaMethodProto::new_abstract("getHeaderFieldInt", "(Ljava/lang/String;I)I", Default::default()),
+                JavaMethodProto::new_abstract("getHeaderField", "(I)Ljava/lang/String;", Default::default()),
+                JavaMethodProto::new_abstract("getHeaderFieldKey", "(I)Ljava/lang/String;", Default::default()),
+            ],
+            fields: vec![],
+            access_flags: ClassAccessFlags::INTERFACE,
+        }
+    }
+}
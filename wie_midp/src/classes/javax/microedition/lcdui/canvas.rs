@@ -8,7 +8,7 @@ use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 use crate::classes::{
     javax::microedition::lcdui::{Display, Graphics},
-    net::wie::{KeyboardEventType, MIDPKeyCode},
+    net::wie::{KeyboardEventType, MIDPKeyCode, PointerEventType},
 };
 
 // abstract class javax.microedition.lcdui.Canvas
@@ -30,10 +30,16 @@ impl Canvas {
                 JavaMethodProto::new("keyPressed", "(I)V", Self::key_pressed, Default::default()),
                 JavaMethodProto::new("keyRepeated", "(I)V", Self::key_repeated, Default::default()),
                 JavaMethodProto::new("keyReleased", "(I)V", Self::key_released, Default::default()),
+                JavaMethodProto::new("pointerPressed", "(II)V", Self::pointer_pressed, Default::default()),
+                JavaMethodProto::new("pointerDragged", "(II)V", Self::pointer_dragged, Default::default()),
+                JavaMethodProto::new("pointerReleased", "(II)V", Self::pointer_released, Default::default()),
+                JavaMethodProto::new("hasPointerEvents", "()Z", Self::has_pointer_events, Default::default()),
+                JavaMethodProto::new("hasPointerMotionEvents", "()Z", Self::has_pointer_motion_events, Default::default()),
                 JavaMethodProto::new("setFullScreenMode", "(Z)V", Self::set_full_screen_mode, Default::default()),
                 JavaMethodProto::new("isDoubleBuffered", "()Z", Self::is_double_buffered, Default::default()),
                 // wie private methods
                 JavaMethodProto::new("handleKeyEvent", "(II)V", Self::handle_key_event, Default::default()),
+                JavaMethodProto::new("handlePointerEvent", "(III)V", Self::handle_pointer_event, Default::default()),
                 JavaMethodProto::new(
                     "handlePaintEvent",
                     "(Ljavax/microedition/lcdui/Graphics;)V",
@@ -121,6 +127,36 @@ impl Canvas {
         Ok(())
     }
 
+    async fn pointer_pressed(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>, x: i32, y: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::pointerPressed({this:?}, {x}, {y})");
+
+        Ok(())
+    }
+
+    async fn pointer_dragged(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>, x: i32, y: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::pointerDragged({this:?}, {x}, {y})");
+
+        Ok(())
+    }
+
+    async fn pointer_released(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>, x: i32, y: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::pointerReleased({this:?}, {x}, {y})");
+
+        Ok(())
+    }
+
+    async fn has_pointer_events(_: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::hasPointerEvents({this:?})");
+
+        Ok(context.system().handset().touch_screen)
+    }
+
+    async fn has_pointer_motion_events(_: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::hasPointerMotionEvents({this:?})");
+
+        Ok(context.system().handset().touch_screen)
+    }
+
     async fn set_full_screen_mode(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, mode: bool) -> JvmResult<()> {
         tracing::debug!("javax.microedition.lcdui.Canvas::setFullScreenMode({this:?}, {mode})");
 
@@ -164,6 +200,31 @@ impl Canvas {
         Ok(())
     }
 
+    async fn handle_pointer_event(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        event_type: i32,
+        x: i32,
+        y: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Canvas::handlePointerEvent({this:?}, {event_type}, {x}, {y})");
+
+        let event_type = if let Some(event_type) = PointerEventType::from_raw(event_type) {
+            event_type
+        } else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid pointer event type").await);
+        };
+
+        let _: () = match event_type {
+            PointerEventType::PointerPressed => jvm.invoke_virtual(&this, "pointerPressed", "(II)V", (x, y)).await,
+            PointerEventType::PointerDragged => jvm.invoke_virtual(&this, "pointerDragged", "(II)V", (x, y)).await,
+            PointerEventType::PointerReleased => jvm.invoke_virtual(&this, "pointerReleased", "(II)V", (x, y)).await,
+        }?;
+
+        Ok(())
+    }
+
     async fn handle_paint_event(
         jvm: &Jvm,
         _context: &mut WieJvmContext,
@@ -37,6 +37,8 @@ impl Display {
                 ),
                 JavaMethodProto::new("getWidth", "()I", Self::get_width, Default::default()),
                 JavaMethodProto::new("getHeight", "()I", Self::get_height, Default::default()),
+                JavaMethodProto::new("isColor", "()Z", Self::is_color, Default::default()),
+                JavaMethodProto::new("numColors", "()I", Self::num_colors, Default::default()),
                 JavaMethodProto::new("callSerially", "(Ljava/lang/Runnable;)V", Self::call_serially, Default::default()),
                 JavaMethodProto::new("vibrate", "(I)Z", Self::vibrate, Default::default()),
                 JavaMethodProto::new(
@@ -48,6 +50,7 @@ impl Display {
                 // wie private methods...
                 JavaMethodProto::new("handlePaintEvent", "()V", Self::handle_paint_event, Default::default()),
                 JavaMethodProto::new("handleKeyEvent", "(II)V", Self::handle_key_event, Default::default()),
+                JavaMethodProto::new("handlePointerEvent", "(III)V", Self::handle_pointer_event, Default::default()),
                 JavaMethodProto::new("handleNotifyEvent", "(III)V", Self::handle_notify_event, Default::default()),
                 JavaMethodProto::new("setFullscreen", "(Z)V", Self::set_fullscreen, Default::default()),
                 JavaMethodProto::new("repaint", "(IIII)V", Self::repaint, Default::default()),
@@ -116,6 +119,20 @@ impl Display {
         Ok(height)
     }
 
+    async fn is_color(_jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Display::isColor({this:?})");
+
+        Ok(context.system().handset().color_depth > 1)
+    }
+
+    async fn num_colors(_jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Display::numColors({this:?})");
+
+        let color_depth = context.system().handset().color_depth;
+
+        Ok(if color_depth >= 31 { i32::MAX } else { 1 << color_depth })
+    }
+
     async fn call_serially(
         jvm: &Jvm,
         _context: &mut WieJvmContext,
@@ -239,6 +256,33 @@ impl Display {
         Ok(())
     }
 
+    async fn handle_pointer_event(
+        jvm: &Jvm,
+        _context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        event_type: i32,
+        x: i32,
+        y: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Display::handlePointerEvent({this:?}, {event_type}, {x}, {y})");
+
+        let current_displayable: ClassInstanceRef<Displayable> = jvm
+            .get_field(&this, "currentDisplayable", "Ljavax/microedition/lcdui/Displayable;")
+            .await?;
+
+        if !current_displayable.is_null() {
+            let result: JvmResult<()> = jvm
+                .invoke_virtual(&current_displayable, "handlePointerEvent", "(III)V", (event_type, x, y))
+                .await;
+
+            if let Err(x) = result {
+                Self::handle_exception(jvm, x).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn handle_paint_event(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
         tracing::debug!("javax.microedition.lcdui.Display::handlePaintEvent({this:?})");
 
@@ -5,9 +5,20 @@ use java_constants::{FieldAccessFlags, MethodAccessFlags};
 use java_runtime::classes::java::lang::String;
 use jvm::{Array, ClassInstanceRef, JavaChar, Jvm, Result as JvmResult, runtime::JavaLangString};
 
-use wie_backend::canvas;
+use wie_backend::canvas::{self, FontSize, FontStyle};
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
+const FACE_SYSTEM: i32 = 0;
+const FACE_MONOSPACE: i32 = 32;
+const FACE_PROPORTIONAL: i32 = 64;
+const STYLE_PLAIN: i32 = 0;
+const STYLE_BOLD: i32 = 1;
+const STYLE_ITALIC: i32 = 2;
+const STYLE_UNDERLINED: i32 = 4;
+const SIZE_MEDIUM: i32 = 0;
+const SIZE_SMALL: i32 = 8;
+const SIZE_LARGE: i32 = 16;
+
 // class javax.microedition.lcdui.Font
 pub struct Font;
 
@@ -19,8 +30,16 @@ impl Font {
             interfaces: vec![],
             methods: vec![
                 JavaMethodProto::new("<clinit>", "()V", Self::cl_init, MethodAccessFlags::STATIC),
-                JavaMethodProto::new("<init>", "()V", Self::init, Default::default()),
+                JavaMethodProto::new("<init>", "(III)V", Self::init, Default::default()),
+                JavaMethodProto::new("getFace", "()I", Self::get_face, Default::default()),
+                JavaMethodProto::new("getStyle", "()I", Self::get_style, Default::default()),
+                JavaMethodProto::new("getSize", "()I", Self::get_size, Default::default()),
+                JavaMethodProto::new("isPlain", "()Z", Self::is_plain, Default::default()),
+                JavaMethodProto::new("isBold", "()Z", Self::is_bold, Default::default()),
+                JavaMethodProto::new("isItalic", "()Z", Self::is_italic, Default::default()),
+                JavaMethodProto::new("isUnderlined", "()Z", Self::is_underlined, Default::default()),
                 JavaMethodProto::new("getHeight", "()I", Self::get_height, Default::default()),
+                JavaMethodProto::new("getBaselinePosition", "()I", Self::get_baseline_position, Default::default()),
                 JavaMethodProto::new("stringWidth", "(Ljava/lang/String;)I", Self::string_width, Default::default()),
                 JavaMethodProto::new("substringWidth", "(Ljava/lang/String;II)I", Self::substring_width, Default::default()),
                 JavaMethodProto::new("charWidth", "(C)I", Self::char_width, Default::default()),
@@ -39,6 +58,9 @@ impl Font {
                 ),
             ],
             fields: vec![
+                JavaFieldProto::new("face", "I", Default::default()),
+                JavaFieldProto::new("style", "I", Default::default()),
+                JavaFieldProto::new("size", "I", Default::default()),
                 JavaFieldProto::new("FACE_SYSTEM", "I", FieldAccessFlags::STATIC),
                 JavaFieldProto::new("FACE_MONOSPACE", "I", FieldAccessFlags::STATIC),
                 JavaFieldProto::new("FACE_PROPORTIONAL", "I", FieldAccessFlags::STATIC),
@@ -57,94 +79,209 @@ impl Font {
     async fn cl_init(jvm: &Jvm, _: &mut WieJvmContext) -> JvmResult<()> {
         tracing::debug!("javax.microedition.lcdui.Font::<clinit>");
 
-        jvm.put_static_field("javax/microedition/lcdui/Font", "FACE_SYSTEM", "I", 0).await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "FACE_MONOSPACE", "I", 32).await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "FACE_PROPORTIONAL", "I", 64)
+        jvm.put_static_field("javax/microedition/lcdui/Font", "FACE_SYSTEM", "I", FACE_SYSTEM)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "FACE_MONOSPACE", "I", FACE_MONOSPACE)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "FACE_PROPORTIONAL", "I", FACE_PROPORTIONAL)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "STYLE_PLAIN", "I", STYLE_PLAIN)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "STYLE_BOLD", "I", STYLE_BOLD)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "STYLE_ITALIC", "I", STYLE_ITALIC)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "STYLE_UNDERLINED", "I", STYLE_UNDERLINED)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "SIZE_MEDIUM", "I", SIZE_MEDIUM)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "SIZE_SMALL", "I", SIZE_SMALL)
+            .await?;
+        jvm.put_static_field("javax/microedition/lcdui/Font", "SIZE_LARGE", "I", SIZE_LARGE)
             .await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "STYLE_PLAIN", "I", 0).await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "STYLE_BOLD", "I", 1).await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "STYLE_ITALIC", "I", 2).await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "STYLE_UNDERLINED", "I", 4).await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "SIZE_MEDIUM", "I", 0).await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "SIZE_SMALL", "I", 8).await?;
-        jvm.put_static_field("javax/microedition/lcdui/Font", "SIZE_LARGE", "I", 16).await?;
 
         Ok(())
     }
 
-    async fn init(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Font>) -> JvmResult<()> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::<init>({this:?})");
+    async fn init(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Font>, face: i32, style: i32, size: i32) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Font::<init>({this:?}, {face}, {style}, {size})");
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "face", "I", face).await?;
+        jvm.put_field(&mut this, "style", "I", style).await?;
+        jvm.put_field(&mut this, "size", "I", size).await?;
 
         Ok(())
     }
 
-    async fn get_height(_: &Jvm, _: &mut WieJvmContext) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::getHeight");
+    async fn get_face(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getFace({this:?})");
+
+        jvm.get_field(&this, "face", "I").await
+    }
+
+    async fn get_style(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getStyle({this:?})");
+
+        jvm.get_field(&this, "style", "I").await
+    }
+
+    async fn get_size(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getSize({this:?})");
+
+        jvm.get_field(&this, "size", "I").await
+    }
+
+    async fn is_plain(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Font::isPlain({this:?})");
 
-        Ok(12) // TODO: hardcoded
+        let style: i32 = jvm.get_field(&this, "style", "I").await?;
+
+        Ok(style == STYLE_PLAIN)
+    }
+
+    async fn is_bold(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Font::isBold({this:?})");
+
+        let style: i32 = jvm.get_field(&this, "style", "I").await?;
+
+        Ok(style & STYLE_BOLD != 0)
+    }
+
+    async fn is_italic(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Font::isItalic({this:?})");
+
+        let style: i32 = jvm.get_field(&this, "style", "I").await?;
+
+        Ok(style & STYLE_ITALIC != 0)
+    }
+
+    async fn is_underlined(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<bool> {
+        tracing::debug!("javax.microedition.lcdui.Font::isUnderlined({this:?})");
+
+        let style: i32 = jvm.get_field(&this, "style", "I").await?;
+
+        Ok(style & STYLE_UNDERLINED != 0)
+    }
+
+    async fn get_height(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getHeight({this:?})");
+
+        let font = Self::font(jvm, &this).await?;
+
+        Ok(context.system().font_pack().get(font).height() as _)
+    }
+
+    async fn get_baseline_position(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::getBaselinePosition({this:?})");
+
+        let font = Self::font(jvm, &this).await?;
+
+        Ok(context.system().font_pack().get(font).ascent() as _)
     }
 
     async fn get_default_font(jvm: &Jvm, _: &mut WieJvmContext) -> JvmResult<ClassInstanceRef<Self>> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::getDefaultFont");
+        tracing::debug!("javax.microedition.lcdui.Font::getDefaultFont");
 
-        let instance = jvm.new_class("javax/microedition/lcdui/Font", "()V", []).await?;
+        let instance = jvm
+            .new_class("javax/microedition/lcdui/Font", "(III)V", (FACE_SYSTEM, STYLE_PLAIN, SIZE_MEDIUM))
+            .await?;
 
         Ok(instance.into())
     }
 
     async fn get_font(jvm: &Jvm, _: &mut WieJvmContext, face: i32, style: i32, size: i32) -> JvmResult<ClassInstanceRef<Font>> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::getFont({face:?}, {style:?}, {size:?})");
+        tracing::debug!("javax.microedition.lcdui.Font::getFont({face:?}, {style:?}, {size:?})");
+
+        if ![FACE_SYSTEM, FACE_MONOSPACE, FACE_PROPORTIONAL].contains(&face)
+            || style & !(STYLE_BOLD | STYLE_ITALIC | STYLE_UNDERLINED) != 0
+            || ![SIZE_SMALL, SIZE_MEDIUM, SIZE_LARGE].contains(&size)
+        {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", "invalid font").await);
+        }
 
-        let instance = jvm.new_class("javax/microedition/lcdui/Font", "()V", []).await?;
+        let instance = jvm.new_class("javax/microedition/lcdui/Font", "(III)V", (face, style, size)).await?;
 
         Ok(instance.into())
     }
 
-    async fn string_width(jvm: &Jvm, _: &mut WieJvmContext, _: ClassInstanceRef<Self>, string: ClassInstanceRef<String>) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::stringWidth({string:?})");
+    async fn string_width(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, string: ClassInstanceRef<String>) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::stringWidth({this:?}, {string:?})");
 
         let string = JavaLangString::to_rust_string(jvm, &string).await?;
 
-        Ok(canvas::string_width(&string, 10.0) as _)
+        let font = Self::font(jvm, &this).await?;
+
+        Ok(context.system().font_pack().get(font).string_width(&string) as _)
     }
 
     async fn substring_width(
         jvm: &Jvm,
-        _: &mut WieJvmContext,
-        _: ClassInstanceRef<Self>,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
         string: ClassInstanceRef<String>,
         offset: i32,
         len: i32,
     ) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::substringWidth({string:?}, {offset:?}, {len:?})");
+        tracing::debug!("javax.microedition.lcdui.Font::substringWidth({this:?}, {string:?}, {offset:?}, {len:?})");
 
         let string = JavaLangString::to_rust_string(jvm, &string).await?;
         let substring = string.chars().skip(offset as usize).take(len as usize).collect::<RustString>();
 
-        Ok(canvas::string_width(&substring, 10.0) as _)
+        let font = Self::font(jvm, &this).await?;
+
+        Ok(context.system().font_pack().get(font).string_width(&substring) as _)
     }
 
-    async fn char_width(_: &Jvm, _: &mut WieJvmContext, _: ClassInstanceRef<Self>, char: JavaChar) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::charWidth({char:?})");
+    async fn char_width(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, char: JavaChar) -> JvmResult<i32> {
+        tracing::debug!("javax.microedition.lcdui.Font::charWidth({this:?}, {char:?})");
+
+        let string = RustString::from_utf16_lossy(&[char]);
 
-        let string = RustString::from_utf16(&[char]).unwrap();
+        let font = Self::font(jvm, &this).await?;
 
-        Ok(canvas::string_width(&string, 10.0) as _)
+        Ok(context.system().font_pack().get(font).string_width(&string) as _)
     }
 
     async fn chars_width(
         jvm: &Jvm,
-        _: &mut WieJvmContext,
-        _: ClassInstanceRef<Self>,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
         chars: ClassInstanceRef<Array<JavaChar>>,
         offset: i32,
         len: i32,
     ) -> JvmResult<i32> {
-        tracing::warn!("stub javax.microedition.lcdui.Font::charsWidth({chars:?}, {offset:?}, {len:?})");
+        tracing::debug!("javax.microedition.lcdui.Font::charsWidth({this:?}, {chars:?}, {offset:?}, {len:?})");
 
         let chars = jvm.load_array(&chars, offset as _, len as _).await?;
-        let string = RustString::from_utf16(&chars).unwrap();
+        let string = RustString::from_utf16_lossy(&chars);
+
+        let font = Self::font(jvm, &this).await?;
+
+        Ok(context.system().font_pack().get(font).string_width(&string) as _)
+    }
+
+    /// The backend font to measure and draw with; a null font is the default font.
+    pub async fn font(jvm: &Jvm, this: &ClassInstanceRef<Self>) -> JvmResult<canvas::Font> {
+        if this.is_null() {
+            return Ok(canvas::Font::default());
+        }
+
+        let style: i32 = jvm.get_field(this, "style", "I").await?;
+        let size: i32 = jvm.get_field(this, "size", "I").await?;
+
+        let size = match size {
+            SIZE_SMALL => FontSize::Small,
+            SIZE_LARGE => FontSize::Large,
+            _ => FontSize::Medium,
+        };
+        let style = FontStyle {
+            bold: style & STYLE_BOLD != 0,
+            italic: style & STYLE_ITALIC != 0,
+            underline: style & STYLE_UNDERLINED != 0,
+        };
 
-        Ok(canvas::string_width(&string, 10.0) as _)
+        Ok(canvas::Font::new(size, style))
     }
 }
@@ -7,7 +7,7 @@ use jvm::{Array, ClassInstanceRef, JavaChar, JavaValue, Jvm, Result as JvmResult
 use java_class_proto::{JavaFieldProto, JavaMethodProto, TypeConverter};
 use java_runtime::classes::java::lang::String;
 
-use wie_backend::canvas::{Clip, PixelType, Rgb8Pixel, TextAlignment, VecImageBuffer};
+use wie_backend::canvas::{Clip, LoadedFont, PixelType, Rgb8Pixel, TextAlignment, VecImageBuffer};
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
 use crate::classes::javax::microedition::lcdui::{Font, Image};
@@ -35,6 +35,19 @@ impl TypeConverter<Anchor> for Anchor {
     }
 }
 
+impl Anchor {
+    // text is drawn from the top of its line
+    fn text_y(&self, y: i32, font: &LoadedFont) -> i32 {
+        if self.contains(Anchor::BASELINE) {
+            y - font.ascent() as i32
+        } else if self.contains(Anchor::BOTTOM) {
+            y - font.height() as i32
+        } else {
+            y
+        }
+    }
+}
+
 impl From<Anchor> for TextAlignment {
     fn from(anchor: Anchor) -> Self {
         if anchor.contains(Anchor::HCENTER) {
@@ -111,6 +124,7 @@ impl Graphics {
                 JavaFieldProto::new("translateX", "I", Default::default()),
                 JavaFieldProto::new("translateY", "I", Default::default()),
                 JavaFieldProto::new("color", "I", Default::default()),
+                JavaFieldProto::new("font", "Ljavax/microedition/lcdui/Font;", Default::default()),
             ],
             access_flags: Default::default(),
         }
@@ -148,15 +162,18 @@ impl Graphics {
         jvm.put_field(&mut this, "translateY", "I", 0).await?;
         jvm.put_field(&mut this, "color", "I", 0).await?;
 
+        let font: ClassInstanceRef<Font> = jvm
+            .invoke_static("javax/microedition/lcdui/Font", "getDefaultFont", "()Ljavax/microedition/lcdui/Font;", ())
+            .await?;
+        jvm.put_field(&mut this, "font", "Ljavax/microedition/lcdui/Font;", font).await?;
+
         Ok(())
     }
 
     async fn get_font(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Graphics>) -> JvmResult<ClassInstanceRef<Font>> {
-        tracing::warn!("stub javax.microedition.lcdui.Graphics::getFont({this:?})");
-
-        let instance = jvm.new_class("javax/microedition/lcdui/Font", "()V", []).await?;
+        tracing::debug!("javax.microedition.lcdui.Graphics::getFont({this:?})");
 
-        Ok(instance.into())
+        jvm.get_field(&this, "font", "Ljavax/microedition/lcdui/Font;").await
     }
 
     async fn set_color(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, rgb: i32) -> JvmResult<()> {
@@ -177,8 +194,17 @@ impl Graphics {
         Ok(())
     }
 
-    async fn set_font(_jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Graphics>, font: ClassInstanceRef<Font>) -> JvmResult<()> {
-        tracing::warn!("stub javax.microedition.lcdui.Graphics::setFont({this:?}, {font:?})");
+    async fn set_font(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Graphics>, font: ClassInstanceRef<Font>) -> JvmResult<()> {
+        tracing::debug!("javax.microedition.lcdui.Graphics::setFont({this:?}, {font:?})");
+
+        // null selects the default font
+        let font = if font.is_null() {
+            jvm.invoke_static("javax/microedition/lcdui/Font", "getDefaultFont", "()Ljavax/microedition/lcdui/Font;", ())
+                .await?
+        } else {
+            font
+        };
+        jvm.put_field(&mut this, "font", "Ljavax/microedition/lcdui/Font;", font).await?;
 
         Ok(())
     }
@@ -357,7 +383,7 @@ impl Graphics {
 
     async fn draw_char(
         jvm: &Jvm,
-        _: &mut WieJvmContext,
+        context: &mut WieJvmContext,
         mut this: ClassInstanceRef<Self>,
         ch: JavaChar,
         x: i32,
@@ -375,11 +401,14 @@ impl Graphics {
         let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
 
         let color: i32 = jvm.get_field(&this, "color", "I").await?;
+        let font = jvm.get_field(&this, "font", "Ljavax/microedition/lcdui/Font;").await?;
+        let font = context.system().font_pack().get(Font::font(jvm, &font).await?);
 
         canvas.draw_text(
             &string,
             (translate_x + x) as _,
-            (translate_y + y) as _,
+            anchor.text_y(translate_y + y, &font),
+            font,
             anchor.into(),
             Rgb8Pixel::to_color(color as _),
         );
@@ -389,7 +418,7 @@ impl Graphics {
 
     async fn draw_chars(
         jvm: &Jvm,
-        _: &mut WieJvmContext,
+        context: &mut WieJvmContext,
         mut this: ClassInstanceRef<Self>,
         chars: ClassInstanceRef<Array<JavaChar>>,
         offset: i32,
@@ -410,11 +439,14 @@ impl Graphics {
         let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
 
         let color: i32 = jvm.get_field(&this, "color", "I").await?;
+        let font = jvm.get_field(&this, "font", "Ljavax/microedition/lcdui/Font;").await?;
+        let font = context.system().font_pack().get(Font::font(jvm, &font).await?);
 
         canvas.draw_text(
             &string,
             (translate_x + x) as _,
-            (translate_y + y) as _,
+            anchor.text_y(translate_y + y, &font),
+            font,
             anchor.into(),
             Rgb8Pixel::to_color(color as _),
         );
@@ -424,7 +456,7 @@ impl Graphics {
 
     async fn draw_string(
         jvm: &Jvm,
-        _: &mut WieJvmContext,
+        context: &mut WieJvmContext,
         mut this: ClassInstanceRef<Self>,
         string: ClassInstanceRef<String>,
         x: i32,
@@ -445,11 +477,14 @@ impl Graphics {
         let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
 
         let color: i32 = jvm.get_field(&this, "color", "I").await?;
+        let font = jvm.get_field(&this, "font", "Ljavax/microedition/lcdui/Font;").await?;
+        let font = context.system().font_pack().get(Font::font(jvm, &font).await?);
 
         canvas.draw_text(
             &string,
             (translate_x + x) as _,
-            (translate_y + y) as _,
+            anchor.text_y(translate_y + y, &font),
+            font,
             anchor.into(),
             Rgb8Pixel::to_color(color as _),
         );
@@ -458,7 +493,7 @@ impl Graphics {
     }
     async fn draw_substring(
         jvm: &Jvm,
-        _: &mut WieJvmContext,
+        context: &mut WieJvmContext,
         mut this: ClassInstanceRef<Self>,
         string: ClassInstanceRef<String>,
         offset: i32,
@@ -479,11 +514,14 @@ impl Graphics {
         let translate_y: i32 = jvm.get_field(&this, "translateY", "I").await?;
 
         let color: i32 = jvm.get_field(&this, "color", "I").await?;
+        let font = jvm.get_field(&this, "font", "Ljavax/microedition/lcdui/Font;").await?;
+        let font = context.system().font_pack().get(Font::font(jvm, &font).await?);
 
         canvas.draw_text(
             &substring,
             (translate_x + x) as _,
-            (translate_y + y) as _,
+            anchor.text_y(translate_y + y, &font),
+            font,
             anchor.into(),
             Rgb8Pixel::to_color(color as _),
         );
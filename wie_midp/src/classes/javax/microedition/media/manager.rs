@@ -1,9 +1,9 @@
-use alloc::vec;
+use alloc::{vec, vec::Vec};
 
 use java_class_proto::JavaMethodProto;
 use java_constants::MethodAccessFlags;
 use java_runtime::classes::java::{io::InputStream, lang::String};
-use jvm::{ClassInstanceRef, Jvm, Result, runtime::JavaLangString};
+use jvm::{Array, ClassInstanceRef, Jvm, Result, runtime::JavaLangString};
 
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 
@@ -18,12 +18,20 @@ impl Manager {
             name: "javax/microedition/media/Manager",
             parent_class: Some("java/lang/Object"),
             interfaces: vec![],
-            methods: vec![JavaMethodProto::new(
-                "createPlayer",
-                "(Ljava/io/InputStream;Ljava/lang/String;)Ljavax/microedition/media/Player;",
-                Self::create_player,
-                MethodAccessFlags::STATIC,
-            )],
+            methods: vec![
+                JavaMethodProto::new(
+                    "createPlayer",
+                    "(Ljava/io/InputStream;Ljava/lang/String;)Ljavax/microedition/media/Player;",
+                    Self::create_player,
+                    MethodAccessFlags::STATIC,
+                ),
+                JavaMethodProto::new(
+                    "getSupportedContentTypes",
+                    "(Ljava/lang/String;)[Ljava/lang/String;",
+                    Self::get_supported_content_types,
+                    MethodAccessFlags::STATIC,
+                ),
+            ],
             fields: vec![],
             access_flags: Default::default(),
         }
@@ -38,10 +46,37 @@ impl Manager {
         tracing::debug!("javax.microedition.media.Manager::createPlayer({stream:?}, {type:?})");
 
         let type_string = JavaLangString::to_rust_string(jvm, &r#type).await?;
-        if type_string == "application/vnd.smaf" {
-            Ok(jvm.new_class("net/wie/SmafPlayer", "(Ljava/io/InputStream;)V", (stream,)).await?.into())
-        } else {
-            Err(jvm.exception("javax/microedition/media/MediaException", "Unsupported media type").await)
+        let class_name = match type_string.to_ascii_lowercase().as_str() {
+            "application/vnd.smaf" => "net/wie/SmafPlayer",
+            "audio/midi" | "audio/mid" | "audio/x-midi" | "audio/sp-midi" => "net/wie/MidiPlayer",
+            "audio/x-wav" | "audio/wav" => "net/wie/WavePlayer",
+            _ => {
+                tracing::warn!("Unsupported media type {type_string}");
+
+                return Err(jvm.exception("javax/microedition/media/MediaException", "Unsupported media type").await);
+            }
+        };
+
+        Ok(jvm.new_class(class_name, "(Ljava/io/InputStream;)V", (stream,)).await?.into())
+    }
+
+    async fn get_supported_content_types(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        protocol: ClassInstanceRef<String>,
+    ) -> Result<ClassInstanceRef<Array<String>>> {
+        tracing::debug!("javax.microedition.media.Manager::getSupportedContentTypes({protocol:?})");
+
+        let content_types = context.system().handset().content_types.clone();
+
+        let mut strings = Vec::with_capacity(content_types.len());
+        for content_type in &content_types {
+            strings.push(JavaLangString::from_rust_string(jvm, content_type).await?);
         }
+
+        let mut result = jvm.instantiate_array("Ljava/lang/String;", strings.len()).await?;
+        jvm.store_array(&mut result, 0, strings).await?;
+
+        Ok(result.into())
     }
 }
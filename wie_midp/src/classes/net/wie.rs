@@ -1,11 +1,25 @@
+mod audio_player;
 mod event_queue;
+mod http_connection_impl;
 mod launcher;
+mod midi_player;
+mod network_input_stream;
+mod network_output_stream;
 mod smaf_player;
+mod socket_connection_impl;
+mod wave_player;
 mod wie_error;
 
 pub use self::{
-    event_queue::{EventQueue, KeyboardEventType, MIDPKeyCode},
+    audio_player::AudioPlayer,
+    event_queue::{EventQueue, KeyboardEventType, MIDPKeyCode, PointerEventType},
+    http_connection_impl::HttpConnectionImpl,
     launcher::Launcher,
+    midi_player::MidiPlayer,
+    network_input_stream::NetworkInputStream,
+    network_output_stream::NetworkOutputStream,
     smaf_player::SmafPlayer,
+    socket_connection_impl::SocketConnectionImpl,
+    wave_player::WavePlayer,
     wie_error::WieError,
 };
@@ -0,0 +1,78 @@
+use alloc::vec;
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use jvm::{ClassInstanceRef, Jvm, Result};
+
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+// class net.wie.AudioPlayer
+pub struct AudioPlayer;
+
+impl AudioPlayer {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "net/wie/AudioPlayer",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec!["javax/microedition/media/Player"],
+            methods: vec![
+                JavaMethodProto::new("<init>", "(I)V", Self::init, Default::default()),
+                JavaMethodProto::new("start", "()V", Self::start, Default::default()),
+                JavaMethodProto::new("start", "(Z)V", Self::start_with_repeat, Default::default()),
+                JavaMethodProto::new("stop", "()V", Self::stop, Default::default()),
+                JavaMethodProto::new("close", "()V", Self::close, Default::default()),
+            ],
+            fields: vec![JavaFieldProto::new("audioHandle", "I", Default::default())],
+            access_flags: Default::default(),
+        }
+    }
+
+    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, audio_handle: i32) -> Result<()> {
+        tracing::debug!("net.wie.AudioPlayer::<init>({this:?}, {audio_handle})");
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "audioHandle", "I", audio_handle).await?;
+
+        Ok(())
+    }
+
+    async fn start(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> Result<()> {
+        Self::start_with_repeat(jvm, context, this, false).await
+    }
+
+    async fn start_with_repeat(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, repeat: bool) -> Result<()> {
+        tracing::debug!("net.wie.AudioPlayer::start({this:?}, {repeat})");
+
+        let audio_handle: i32 = jvm.get_field(&this, "audioHandle", "I").await?;
+
+        let system = context.system();
+
+        system.audio().play(system, audio_handle as u32, repeat).unwrap();
+
+        Ok(())
+    }
+
+    async fn stop(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> Result<()> {
+        tracing::debug!("net.wie.AudioPlayer::stop({this:?})");
+
+        let audio_handle: i32 = jvm.get_field(&this, "audioHandle", "I").await?;
+
+        let system = context.system();
+
+        system.audio().stop(audio_handle as u32);
+
+        Ok(())
+    }
+
+    async fn close(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> Result<()> {
+        tracing::debug!("net.wie.AudioPlayer::close({this:?})");
+
+        let audio_handle: i32 = jvm.get_field(&this, "audioHandle", "I").await?;
+
+        let system = context.system();
+
+        system.audio().close(audio_handle as u32).unwrap();
+
+        Ok(())
+    }
+}
@@ -16,6 +16,7 @@ use crate::classes::javax::microedition::midlet::MIDlet;
 enum EventQueueEvent {
     // TODO it's wipi event codes
     KeyEvent = 1,
+    PointerEvent = 2,
     RepaintEvent = 41,
     NotifyEvent = 1000,
 }
@@ -24,6 +25,7 @@ impl EventQueueEvent {
     fn from_raw(raw: i32) -> Option<Self> {
         Some(match raw {
             x if x == Self::KeyEvent as i32 => Self::KeyEvent,
+            x if x == Self::PointerEvent as i32 => Self::PointerEvent,
             x if x == Self::RepaintEvent as i32 => Self::RepaintEvent,
             x if x == Self::NotifyEvent as i32 => Self::NotifyEvent,
             _ => return None,
@@ -53,6 +55,26 @@ impl KeyboardEventType {
     }
 }
 
+#[repr(i32)]
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum PointerEventType {
+    PointerPressed = 1,
+    PointerReleased = 2,
+    PointerDragged = 3,
+}
+
+impl PointerEventType {
+    pub fn from_raw(raw: i32) -> Option<Self> {
+        Some(match raw {
+            x if x == Self::PointerPressed as i32 => Self::PointerPressed,
+            x if x == Self::PointerReleased as i32 => Self::PointerReleased,
+            x if x == Self::PointerDragged as i32 => Self::PointerDragged,
+            _ => return None,
+        })
+    }
+}
+
 #[repr(i32)]
 #[allow(clippy::upper_case_acronyms)]
 #[allow(non_camel_case_types)]
@@ -222,6 +244,9 @@ impl EventQueue {
                         MIDPKeyCode::from_key_code(x) as _,
                         0,
                     ],
+                    Event::Pointerdown { x, y } => vec![EventQueueEvent::PointerEvent as _, PointerEventType::PointerPressed as _, x, y],
+                    Event::Pointerdrag { x, y } => vec![EventQueueEvent::PointerEvent as _, PointerEventType::PointerDragged as _, x, y],
+                    Event::Pointerup { x, y } => vec![EventQueueEvent::PointerEvent as _, PointerEventType::PointerReleased as _, x, y],
                     Event::Timer { due, callback } => {
                         // TODO we should wait for timer more efficiently
                         if due < now {
@@ -309,6 +334,19 @@ impl EventQueue {
 
                 let _: () = jvm.invoke_virtual(&display, "handleKeyEvent", "(II)V", (event_type as i32, code)).await?;
             }
+            EventQueueEvent::PointerEvent => {
+                let event_type = if let Some(event_type) = PointerEventType::from_raw(event[1]) {
+                    event_type
+                } else {
+                    return Err(jvm.exception("java/lang/IllegalArgumentException", "Invalid pointer event type").await);
+                };
+                let x = event[2];
+                let y = event[3];
+
+                let _: () = jvm
+                    .invoke_virtual(&display, "handlePointerEvent", "(III)V", (event_type as i32, x, y))
+                    .await?;
+            }
             EventQueueEvent::NotifyEvent => {
                 let r#type = event[1];
                 let param1 = event[2];
@@ -0,0 +1,399 @@
+use alloc::{string::String as RustString, vec};
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use java_runtime::classes::java::{
+    io::{DataInputStream, DataOutputStream, InputStream, OutputStream},
+    lang::String,
+};
+use jvm::{ClassInstanceRef, Jvm, Result as JvmResult, runtime::JavaLangString};
+
+use wie_backend::{HttpResponse, NetworkError};
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use super::network_input_stream::network_exception;
+
+// how often we check on a request another thread has sent
+const RESPONSE_POLL_INTERVAL: u64 = 10;
+
+// class net.wie.HttpConnectionImpl
+pub struct HttpConnectionImpl;
+
+impl HttpConnectionImpl {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "net/wie/HttpConnectionImpl",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec!["javax/microedition/io/HttpConnection"],
+            methods: vec![
+                JavaMethodProto::new("<init>", "(Ljava/lang/String;)V", Self::init, Default::default()),
+                JavaMethodProto::new("getURL", "()Ljava/lang/String;", Self::get_url, Default::default()),
+                JavaMethodProto::new("getRequestMethod", "()Ljava/lang/String;", Self::get_request_method, Default::default()),
+                JavaMethodProto::new("setRequestMethod", "(Ljava/lang/String;)V", Self::set_request_method, Default::default()),
+                JavaMethodProto::new(
+                    "getRequestProperty",
+                    "(Ljava/lang/String;)Ljava/lang/String;",
+                    Self::get_request_property,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "setRequestProperty",
+                    "(Ljava/lang/String;Ljava/lang/String;)V",
+                    Self::set_request_property,
+                    Default::default(),
+                ),
+                JavaMethodProto::new("getResponseCode", "()I", Self::get_response_code, Default::default()),
+                JavaMethodProto::new(
+                    "getResponseMessage",
+                    "()Ljava/lang/String;",
+                    Self::get_response_message,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "getHeaderField",
+                    "(Ljava/lang/String;)Ljava/lang/String;",
+                    Self::get_header_field,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "getHeaderFieldInt",
+                    "(Ljava/lang/String;I)I",
+                    Self::get_header_field_int,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "getHeaderField",
+                    "(I)Ljava/lang/String;",
+                    Self::get_header_field_by_index,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "getHeaderFieldKey",
+                    "(I)Ljava/lang/String;",
+                    Self::get_header_field_key,
+                    Default::default(),
+                ),
+                JavaMethodProto::new("getType", "()Ljava/lang/String;", Self::get_type, Default::default()),
+                JavaMethodProto::new("getEncoding", "()Ljava/lang/String;", Self::get_encoding, Default::default()),
+                JavaMethodProto::new("getLength", "()J", Self::get_length, Default::default()),
+                JavaMethodProto::new("openInputStream", "()Ljava/io/InputStream;", Self::open_input_stream, Default::default()),
+                JavaMethodProto::new(
+                    "openDataInputStream",
+                    "()Ljava/io/DataInputStream;",
+                    Self::open_data_input_stream,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "openOutputStream",
+                    "()Ljava/io/OutputStream;",
+                    Self::open_output_stream,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "openDataOutputStream",
+                    "()Ljava/io/DataOutputStream;",
+                    Self::open_data_output_stream,
+                    Default::default(),
+                ),
+                JavaMethodProto::new("close", "()V", Self::close, Default::default()),
+            ],
+            fields: vec![
+                JavaFieldProto::new("id", "I", Default::default()),
+                JavaFieldProto::new("url", "Ljava/lang/String;", Default::default()),
+            ],
+            access_flags: Default::default(),
+        }
+    }
+
+    async fn init(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, url: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("net.wie.HttpConnectionImpl::<init>({this:?}, {url:?})");
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        let url_string = JavaLangString::to_rust_string(jvm, &url).await?;
+        let id = context.system().sockets().open_http(&url_string);
+
+        jvm.put_field(&mut this, "id", "I", id as i32).await?;
+        jvm.put_field(&mut this, "url", "Ljava/lang/String;", url).await?;
+
+        Ok(())
+    }
+
+    async fn get_url(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getURL({this:?})");
+
+        jvm.get_field(&this, "url", "Ljava/lang/String;").await
+    }
+
+    async fn get_request_method(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getRequestMethod({this:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+        let method = context.system().sockets().http(id as _).map(|x| RustString::from(x.method()));
+
+        match method {
+            Ok(x) => Ok(JavaLangString::from_rust_string(jvm, &x).await?.into()),
+            Err(x) => Err(network_exception(jvm, x).await),
+        }
+    }
+
+    async fn set_request_method(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        method: ClassInstanceRef<String>,
+    ) -> JvmResult<()> {
+        tracing::debug!("net.wie.HttpConnectionImpl::setRequestMethod({this:?}, {method:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+        let method = JavaLangString::to_rust_string(jvm, &method).await?;
+
+        let result = context.system().sockets().http(id as _).map(|x| x.set_method(&method));
+
+        if let Err(x) = result {
+            return Err(network_exception(jvm, x).await);
+        }
+
+        Ok(())
+    }
+
+    async fn get_request_property(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        key: ClassInstanceRef<String>,
+    ) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getRequestProperty({this:?}, {key:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+        let key = JavaLangString::to_rust_string(jvm, &key).await?;
+
+        let value = context
+            .system()
+            .sockets()
+            .http(id as _)
+            .map(|x| x.request_header(&key).map(RustString::from));
+
+        match value {
+            Ok(Some(x)) => Ok(JavaLangString::from_rust_string(jvm, &x).await?.into()),
+            Ok(None) => Ok(None.into()),
+            Err(x) => Err(network_exception(jvm, x).await),
+        }
+    }
+
+    async fn set_request_property(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        key: ClassInstanceRef<String>,
+        value: ClassInstanceRef<String>,
+    ) -> JvmResult<()> {
+        tracing::debug!("net.wie.HttpConnectionImpl::setRequestProperty({this:?}, {key:?}, {value:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+        let key = JavaLangString::to_rust_string(jvm, &key).await?;
+        let value = JavaLangString::to_rust_string(jvm, &value).await?;
+
+        let result = context.system().sockets().http(id as _).map(|x| x.set_request_header(&key, &value));
+
+        if let Err(x) = result {
+            return Err(network_exception(jvm, x).await);
+        }
+
+        Ok(())
+    }
+
+    async fn get_response_code(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getResponseCode({this:?})");
+
+        Self::response(jvm, context, &this, |x| x.status as i32).await
+    }
+
+    async fn get_response_message(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getResponseMessage({this:?})");
+
+        let message = Self::response(jvm, context, &this, |x| x.message.clone()).await?;
+
+        Ok(JavaLangString::from_rust_string(jvm, &message).await?.into())
+    }
+
+    async fn get_header_field(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        name: ClassInstanceRef<String>,
+    ) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getHeaderField({this:?}, {name:?})");
+
+        let name = JavaLangString::to_rust_string(jvm, &name).await?;
+
+        Self::header(jvm, context, &this, &name).await
+    }
+
+    async fn get_header_field_int(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        name: ClassInstanceRef<String>,
+        default: i32,
+    ) -> JvmResult<i32> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getHeaderFieldInt({this:?}, {name:?}, {default})");
+
+        let name = JavaLangString::to_rust_string(jvm, &name).await?;
+        let value = Self::response(jvm, context, &this, |x| x.header(&name).and_then(|x| x.trim().parse().ok())).await?;
+
+        Ok(value.unwrap_or(default))
+    }
+
+    async fn get_header_field_by_index(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        index: i32,
+    ) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getHeaderField({this:?}, {index})");
+
+        let value = Self::response(jvm, context, &this, |x| x.headers.get(index as usize).map(|(_, x)| x.clone())).await?;
+
+        Self::to_java_string(jvm, value).await
+    }
+
+    async fn get_header_field_key(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        index: i32,
+    ) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getHeaderFieldKey({this:?}, {index})");
+
+        let key = Self::response(jvm, context, &this, |x| x.headers.get(index as usize).map(|(x, _)| x.clone())).await?;
+
+        Self::to_java_string(jvm, key).await
+    }
+
+    async fn get_type(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getType({this:?})");
+
+        Self::header(jvm, context, &this, "Content-Type").await
+    }
+
+    async fn get_encoding(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getEncoding({this:?})");
+
+        Self::header(jvm, context, &this, "Content-Encoding").await
+    }
+
+    async fn get_length(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i64> {
+        tracing::debug!("net.wie.HttpConnectionImpl::getLength({this:?})");
+
+        Self::response(jvm, context, &this, |x| {
+            x.header("Content-Length")
+                .and_then(|x| x.trim().parse().ok())
+                .unwrap_or(x.body.len() as _)
+        })
+        .await
+    }
+
+    async fn open_input_stream(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<InputStream>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::openInputStream({this:?})");
+
+        // send the request now so failures surface here rather than on the first read
+        Self::response(jvm, context, &this, |_| ()).await?;
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+
+        Ok(jvm.new_class("net/wie/NetworkInputStream", "(I)V", (id,)).await?.into())
+    }
+
+    async fn open_data_input_stream(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+    ) -> JvmResult<ClassInstanceRef<DataInputStream>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::openDataInputStream({this:?})");
+
+        let input_stream = Self::open_input_stream(jvm, context, this).await?;
+
+        Ok(jvm
+            .new_class("java/io/DataInputStream", "(Ljava/io/InputStream;)V", (input_stream,))
+            .await?
+            .into())
+    }
+
+    async fn open_output_stream(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<OutputStream>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::openOutputStream({this:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+
+        Ok(jvm.new_class("net/wie/NetworkOutputStream", "(I)V", (id,)).await?.into())
+    }
+
+    async fn open_data_output_stream(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+    ) -> JvmResult<ClassInstanceRef<DataOutputStream>> {
+        tracing::debug!("net.wie.HttpConnectionImpl::openDataOutputStream({this:?})");
+
+        let output_stream = Self::open_output_stream(jvm, context, this).await?;
+
+        Ok(jvm
+            .new_class("java/io/DataOutputStream", "(Ljava/io/OutputStream;)V", (output_stream,))
+            .await?
+            .into())
+    }
+
+    async fn close(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("net.wie.HttpConnectionImpl::close({this:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+        let _ = context.system().sockets().close(id as _);
+
+        Ok(())
+    }
+
+    // sends the request on first use, then waits for the response and maps it with `f`
+    async fn response<T>(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>, f: impl Fn(&HttpResponse) -> T) -> JvmResult<T> {
+        let id: i32 = jvm.get_field(this, "id", "I").await?;
+
+        let request = context.system().sockets().http(id as _).and_then(|x| {
+            let idle = matches!(x.response(), Err(NetworkError::NotConnected));
+
+            if idle { x.begin_request().map(Some) } else { Ok(None) }
+        });
+        let request = match request {
+            Ok(x) => x,
+            Err(x) => return Err(network_exception(jvm, x).await),
+        };
+
+        if let Some(request) = request {
+            let system = context.system().clone();
+            let response = system.platform().http().request(request).await;
+
+            if let Ok(x) = context.system().sockets().http(id as _) {
+                x.finish_request(response);
+            }
+        }
+
+        loop {
+            let result = context.system().sockets().http(id as _).and_then(|x| x.response().map(&f));
+            match result {
+                Ok(x) => return Ok(x),
+                Err(NetworkError::WouldBlock) => context.system().sleep(RESPONSE_POLL_INTERVAL).await,
+                Err(x) => return Err(network_exception(jvm, x).await),
+            }
+        }
+    }
+
+    async fn header(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>, name: &str) -> JvmResult<ClassInstanceRef<String>> {
+        let value = Self::response(jvm, context, this, |x| x.header(name).map(RustString::from)).await?;
+
+        Self::to_java_string(jvm, value).await
+    }
+
+    async fn to_java_string(jvm: &Jvm, value: Option<RustString>) -> JvmResult<ClassInstanceRef<String>> {
+        Ok(match value {
+            Some(x) => JavaLangString::from_rust_string(jvm, &x).await?.into(),
+            None => None.into(),
+        })
+    }
+}
@@ -0,0 +1,38 @@
+use alloc::vec;
+
+use java_class_proto::JavaMethodProto;
+use java_runtime::classes::java::io::InputStream;
+use jvm::{ClassInstanceRef, Jvm, Result, runtime::JavaIoInputStream};
+
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+// class net.wie.MidiPlayer
+pub struct MidiPlayer;
+
+impl MidiPlayer {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "net/wie/MidiPlayer",
+            parent_class: Some("net/wie/AudioPlayer"),
+            interfaces: vec![],
+            methods: vec![JavaMethodProto::new("<init>", "(Ljava/io/InputStream;)V", Self::init, Default::default())],
+            fields: vec![],
+            access_flags: Default::default(),
+        }
+    }
+
+    async fn init(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, stream: ClassInstanceRef<InputStream>) -> Result<()> {
+        tracing::debug!("net.wie.MidiPlayer::<init>({this:?}, {stream:?})");
+
+        let data = JavaIoInputStream::read_until_end(jvm, &stream).await?;
+        let Ok(audio_handle) = context.system().audio().load_midi(&data) else {
+            return Err(jvm.exception("javax/microedition/media/MediaException", "Invalid MIDI data").await);
+        };
+
+        let _: () = jvm
+            .invoke_special(&this, "net/wie/AudioPlayer", "<init>", "(I)V", (audio_handle as i32,))
+            .await?;
+
+        Ok(())
+    }
+}
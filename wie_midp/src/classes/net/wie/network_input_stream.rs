@@ -0,0 +1,124 @@
+use alloc::{format, vec, vec::Vec};
+
+use bytemuck::cast_vec;
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use jvm::{Array, ClassInstanceRef, JavaError, Jvm, Result as JvmResult};
+
+use wie_backend::NetworkError;
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+// how often a blocked read checks its connection for incoming data
+const READ_POLL_INTERVAL: u64 = 10;
+
+pub(crate) async fn network_exception(jvm: &Jvm, error: NetworkError) -> JavaError {
+    jvm.exception("java/io/IOException", &format!("Network error: {error:?}")).await
+}
+
+// class net.wie.NetworkInputStream
+pub struct NetworkInputStream;
+
+impl NetworkInputStream {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "net/wie/NetworkInputStream",
+            parent_class: Some("java/io/InputStream"),
+            interfaces: vec![],
+            methods: vec![
+                JavaMethodProto::new("<init>", "(I)V", Self::init, Default::default()),
+                JavaMethodProto::new("available", "()I", Self::available, Default::default()),
+                JavaMethodProto::new("read", "()I", Self::read_byte, Default::default()),
+                JavaMethodProto::new("read", "([BII)I", Self::read_array, Default::default()),
+                JavaMethodProto::new("close", "()V", Self::close, Default::default()),
+            ],
+            fields: vec![
+                JavaFieldProto::new("id", "I", Default::default()),
+                JavaFieldProto::new("ownsConnection", "Z", Default::default()),
+            ],
+            access_flags: Default::default(),
+        }
+    }
+
+    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, id: i32) -> JvmResult<()> {
+        tracing::debug!("net.wie.NetworkInputStream::<init>({this:?}, {id})");
+
+        let _: () = jvm.invoke_special(&this, "java/io/InputStream", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "id", "I", id).await?;
+
+        Ok(())
+    }
+
+    async fn available(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("net.wie.NetworkInputStream::available({this:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+
+        // we don't know how much is buffered on the host, only whether a read would block
+        Ok(if context.system().sockets().readable(id as _) { 1 } else { 0 })
+    }
+
+    async fn read_byte(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("net.wie.NetworkInputStream::read({this:?})");
+
+        let data = Self::recv(jvm, context, &this, 1).await?;
+
+        Ok(data.first().map(|&x| x as i32).unwrap_or(-1))
+    }
+
+    async fn read_array(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        mut buf: ClassInstanceRef<Array<i8>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<i32> {
+        tracing::debug!("net.wie.NetworkInputStream::read({this:?}, {buf:?}, {offset}, {length})");
+
+        if length <= 0 {
+            return Ok(0);
+        }
+
+        let data = Self::recv(jvm, context, &this, length as _).await?;
+        if data.is_empty() {
+            return Ok(-1);
+        }
+
+        let read = data.len();
+        jvm.store_array(&mut buf, offset as _, cast_vec::<u8, i8>(data)).await?;
+
+        Ok(read as _)
+    }
+
+    async fn close(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("net.wie.NetworkInputStream::close({this:?})");
+
+        let owns_connection: bool = jvm.get_field(&this, "ownsConnection", "Z").await?;
+        if owns_connection {
+            let id: i32 = jvm.get_field(&this, "id", "I").await?;
+            let _ = context.system().sockets().close(id as _);
+        }
+
+        Ok(())
+    }
+
+    // blocks the calling java thread until some data or eof (an empty vec) arrives
+    async fn recv(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>, length: usize) -> JvmResult<Vec<u8>> {
+        let id: i32 = jvm.get_field(this, "id", "I").await?;
+
+        let mut buf = vec![0; length];
+        loop {
+            let result = context.system().sockets().recv(id as _, &mut buf);
+            match result {
+                Ok(read) => {
+                    buf.truncate(read);
+
+                    return Ok(buf);
+                }
+                Err(NetworkError::WouldBlock) => context.system().sleep(READ_POLL_INTERVAL).await,
+                Err(x) => return Err(network_exception(jvm, x).await),
+            }
+        }
+    }
+}
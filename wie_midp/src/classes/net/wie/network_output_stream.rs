@@ -0,0 +1,103 @@
+use alloc::{vec, vec::Vec};
+
+use bytemuck::cast_vec;
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use jvm::{Array, ClassInstanceRef, Jvm, Result as JvmResult};
+
+use wie_backend::NetworkError;
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use super::network_input_stream::network_exception;
+
+// how often a blocked write retries a full send buffer
+const WRITE_POLL_INTERVAL: u64 = 10;
+
+// class net.wie.NetworkOutputStream
+pub struct NetworkOutputStream;
+
+impl NetworkOutputStream {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "net/wie/NetworkOutputStream",
+            parent_class: Some("java/io/OutputStream"),
+            interfaces: vec![],
+            methods: vec![
+                JavaMethodProto::new("<init>", "(I)V", Self::init, Default::default()),
+                JavaMethodProto::new("write", "(I)V", Self::write_byte, Default::default()),
+                JavaMethodProto::new("write", "([BII)V", Self::write_array, Default::default()),
+                JavaMethodProto::new("flush", "()V", Self::flush, Default::default()),
+                JavaMethodProto::new("close", "()V", Self::close, Default::default()),
+            ],
+            fields: vec![
+                JavaFieldProto::new("id", "I", Default::default()),
+                JavaFieldProto::new("ownsConnection", "Z", Default::default()),
+            ],
+            access_flags: Default::default(),
+        }
+    }
+
+    async fn init(jvm: &Jvm, _context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, id: i32) -> JvmResult<()> {
+        tracing::debug!("net.wie.NetworkOutputStream::<init>({this:?}, {id})");
+
+        let _: () = jvm.invoke_special(&this, "java/io/OutputStream", "<init>", "()V", ()).await?;
+
+        jvm.put_field(&mut this, "id", "I", id).await?;
+
+        Ok(())
+    }
+
+    async fn write_byte(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, byte: i32) -> JvmResult<()> {
+        tracing::debug!("net.wie.NetworkOutputStream::write({this:?}, {byte})");
+
+        Self::send(jvm, context, &this, &[byte as u8]).await
+    }
+
+    async fn write_array(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+        buf: ClassInstanceRef<Array<i8>>,
+        offset: i32,
+        length: i32,
+    ) -> JvmResult<()> {
+        tracing::debug!("net.wie.NetworkOutputStream::write({this:?}, {buf:?}, {offset}, {length})");
+
+        let data: Vec<i8> = jvm.load_array(&buf, offset as _, length as _).await?;
+
+        Self::send(jvm, context, &this, &cast_vec(data)).await
+    }
+
+    async fn flush(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("net.wie.NetworkOutputStream::flush({this:?})");
+
+        Ok(())
+    }
+
+    async fn close(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("net.wie.NetworkOutputStream::close({this:?})");
+
+        let owns_connection: bool = jvm.get_field(&this, "ownsConnection", "Z").await?;
+        if owns_connection {
+            let id: i32 = jvm.get_field(&this, "id", "I").await?;
+            let _ = context.system().sockets().close(id as _);
+        }
+
+        Ok(())
+    }
+
+    async fn send(jvm: &Jvm, context: &mut WieJvmContext, this: &ClassInstanceRef<Self>, mut data: &[u8]) -> JvmResult<()> {
+        let id: i32 = jvm.get_field(this, "id", "I").await?;
+
+        while !data.is_empty() {
+            let result = context.system().sockets().send(id as _, data);
+            match result {
+                Ok(written) => data = &data[written..],
+                Err(NetworkError::WouldBlock) => context.system().sleep(WRITE_POLL_INTERVAL).await,
+                Err(x) => return Err(network_exception(jvm, x).await),
+            }
+        }
+
+        Ok(())
+    }
+}
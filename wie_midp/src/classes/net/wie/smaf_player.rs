@@ -1,6 +1,6 @@
 use alloc::vec;
 
-use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use java_class_proto::JavaMethodProto;
 use java_runtime::classes::java::io::InputStream;
 use jvm::{ClassInstanceRef, Jvm, Result, runtime::JavaIoInputStream};
 
@@ -13,69 +13,25 @@ impl SmafPlayer {
     pub fn as_proto() -> WieJavaClassProto {
         WieJavaClassProto {
             name: "net/wie/SmafPlayer",
-            parent_class: Some("java/lang/Object"),
-            interfaces: vec!["javax/microedition/media/Player"],
-            methods: vec![
-                JavaMethodProto::new("<init>", "(Ljava/io/InputStream;)V", Self::init, Default::default()),
-                JavaMethodProto::new("start", "()V", Self::start, Default::default()),
-                JavaMethodProto::new("start", "(Z)V", Self::start_with_repeat, Default::default()),
-                JavaMethodProto::new("stop", "()V", Self::stop, Default::default()),
-                JavaMethodProto::new("close", "()V", Self::close, Default::default()),
-            ],
-            fields: vec![JavaFieldProto::new("audioHandle", "I", Default::default())],
+            parent_class: Some("net/wie/AudioPlayer"),
+            interfaces: vec![],
+            methods: vec![JavaMethodProto::new("<init>", "(Ljava/io/InputStream;)V", Self::init, Default::default())],
+            fields: vec![],
             access_flags: Default::default(),
         }
     }
 
-    async fn init(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, stream: ClassInstanceRef<InputStream>) -> Result<()> {
+    async fn init(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, stream: ClassInstanceRef<InputStream>) -> Result<()> {
         tracing::debug!("net.wie.SmafPlayer::<init>({this:?}, {stream:?})");
 
-        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
-
         let data = JavaIoInputStream::read_until_end(jvm, &stream).await?;
-        let audio_handle = context.system().audio().load_smaf(&data).unwrap();
-
-        jvm.put_field(&mut this, "audioHandle", "I", audio_handle as i32).await?;
-
-        Ok(())
-    }
-
-    async fn start(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> Result<()> {
-        Self::start_with_repeat(jvm, context, this, false).await
-    }
-
-    async fn start_with_repeat(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>, repeat: bool) -> Result<()> {
-        tracing::debug!("net.wie.SmafPlayer::start({this:?}, {repeat})");
-
-        let audio_handle: i32 = jvm.get_field(&this, "audioHandle", "I").await?;
-
-        let system = context.system();
-
-        system.audio().play(system, audio_handle as u32, repeat).unwrap();
-
-        Ok(())
-    }
-
-    async fn stop(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> Result<()> {
-        tracing::debug!("net.wie.SmafPlayer::stop({this:?})");
-
-        let audio_handle: i32 = jvm.get_field(&this, "audioHandle", "I").await?;
-
-        let system = context.system();
-
-        system.audio().stop(audio_handle as u32);
-
-        Ok(())
-    }
-
-    async fn close(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> Result<()> {
-        tracing::debug!("net.wie.SmafPlayer::close({this:?})");
-
-        let audio_handle: i32 = jvm.get_field(&this, "audioHandle", "I").await?;
-
-        let system = context.system();
+        let Ok(audio_handle) = context.system().audio().load_smaf(&data) else {
+            return Err(jvm.exception("javax/microedition/media/MediaException", "Invalid SMAF data").await);
+        };
 
-        system.audio().close(audio_handle as u32).unwrap();
+        let _: () = jvm
+            .invoke_special(&this, "net/wie/AudioPlayer", "<init>", "(I)V", (audio_handle as i32,))
+            .await?;
 
         Ok(())
     }
@@ -0,0 +1,188 @@
+use alloc::vec;
+
+use java_class_proto::{JavaFieldProto, JavaMethodProto};
+use java_runtime::classes::java::{
+    io::{DataInputStream, DataOutputStream, InputStream, OutputStream},
+    lang::String,
+};
+use jvm::{ClassInstanceRef, Jvm, Result as JvmResult, runtime::JavaLangString};
+
+use wie_backend::{NetworkError, SocketType};
+use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
+
+use super::network_input_stream::network_exception;
+
+// class net.wie.SocketConnectionImpl
+pub struct SocketConnectionImpl;
+
+impl SocketConnectionImpl {
+    pub fn as_proto() -> WieJavaClassProto {
+        WieJavaClassProto {
+            name: "net/wie/SocketConnectionImpl",
+            parent_class: Some("java/lang/Object"),
+            interfaces: vec!["javax/microedition/io/SocketConnection"],
+            methods: vec![
+                JavaMethodProto::new("<init>", "(Ljava/lang/String;)V", Self::init, Default::default()),
+                JavaMethodProto::new("getAddress", "()Ljava/lang/String;", Self::get_address, Default::default()),
+                JavaMethodProto::new("getPort", "()I", Self::get_port, Default::default()),
+                JavaMethodProto::new("getLocalPort", "()I", Self::get_local_port, Default::default()),
+                JavaMethodProto::new("setSocketOption", "(BI)V", Self::set_socket_option, Default::default()),
+                JavaMethodProto::new("getSocketOption", "(B)I", Self::get_socket_option, Default::default()),
+                JavaMethodProto::new("openInputStream", "()Ljava/io/InputStream;", Self::open_input_stream, Default::default()),
+                JavaMethodProto::new(
+                    "openDataInputStream",
+                    "()Ljava/io/DataInputStream;",
+                    Self::open_data_input_stream,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "openOutputStream",
+                    "()Ljava/io/OutputStream;",
+                    Self::open_output_stream,
+                    Default::default(),
+                ),
+                JavaMethodProto::new(
+                    "openDataOutputStream",
+                    "()Ljava/io/DataOutputStream;",
+                    Self::open_data_output_stream,
+                    Default::default(),
+                ),
+                JavaMethodProto::new("close", "()V", Self::close, Default::default()),
+            ],
+            fields: vec![
+                JavaFieldProto::new("id", "I", Default::default()),
+                JavaFieldProto::new("host", "Ljava/lang/String;", Default::default()),
+                JavaFieldProto::new("port", "I", Default::default()),
+            ],
+            access_flags: Default::default(),
+        }
+    }
+
+    // `address` is the part of a `socket://host:port` url after the scheme
+    async fn init(jvm: &Jvm, context: &mut WieJvmContext, mut this: ClassInstanceRef<Self>, address: ClassInstanceRef<String>) -> JvmResult<()> {
+        tracing::debug!("net.wie.SocketConnectionImpl::<init>({this:?}, {address:?})");
+
+        let _: () = jvm.invoke_special(&this, "java/lang/Object", "<init>", "()V", ()).await?;
+
+        let address = JavaLangString::to_rust_string(jvm, &address).await?;
+        let Some((host, port)) = address.rsplit_once(':').and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?))) else {
+            return Err(jvm.exception("java/lang/IllegalArgumentException", &address).await);
+        };
+
+        let system = context.system().clone();
+        let opened = {
+            let mut sockets = system.sockets();
+
+            sockets.resolve(host).and_then(|addr| Ok((addr, sockets.open(SocketType::Stream)?)))
+        };
+        let result = match opened {
+            Ok((addr, id)) => match system.connect_socket(id, addr, port).await {
+                Ok(()) => Ok(id),
+                Err(x) => {
+                    let _ = system.sockets().close(id);
+
+                    Err(x)
+                }
+            },
+            Err(x) => Err(x),
+        };
+        let id = match result {
+            Ok(x) => x,
+            Err(NetworkError::HostNotFound) => return Err(jvm.exception("javax/microedition/io/ConnectionNotFoundException", host).await),
+            Err(x) => return Err(network_exception(jvm, x).await),
+        };
+
+        let host = JavaLangString::from_rust_string(jvm, host).await?;
+
+        jvm.put_field(&mut this, "id", "I", id as i32).await?;
+        jvm.put_field(&mut this, "host", "Ljava/lang/String;", host).await?;
+        jvm.put_field(&mut this, "port", "I", port as i32).await?;
+
+        Ok(())
+    }
+
+    async fn get_address(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<String>> {
+        tracing::debug!("net.wie.SocketConnectionImpl::getAddress({this:?})");
+
+        jvm.get_field(&this, "host", "Ljava/lang/String;").await
+    }
+
+    async fn get_port(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("net.wie.SocketConnectionImpl::getPort({this:?})");
+
+        jvm.get_field(&this, "port", "I").await
+    }
+
+    async fn get_local_port(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::warn!("stub net.wie.SocketConnectionImpl::getLocalPort({this:?})");
+
+        Ok(0)
+    }
+
+    async fn set_socket_option(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, option: i8, value: i32) -> JvmResult<()> {
+        tracing::warn!("stub net.wie.SocketConnectionImpl::setSocketOption({this:?}, {option}, {value})");
+
+        Ok(())
+    }
+
+    async fn get_socket_option(_jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, option: i8) -> JvmResult<i32> {
+        tracing::warn!("stub net.wie.SocketConnectionImpl::getSocketOption({this:?}, {option})");
+
+        Ok(0)
+    }
+
+    async fn open_input_stream(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<InputStream>> {
+        tracing::debug!("net.wie.SocketConnectionImpl::openInputStream({this:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+
+        Ok(jvm.new_class("net/wie/NetworkInputStream", "(I)V", (id,)).await?.into())
+    }
+
+    async fn open_data_input_stream(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+    ) -> JvmResult<ClassInstanceRef<DataInputStream>> {
+        tracing::debug!("net.wie.SocketConnectionImpl::openDataInputStream({this:?})");
+
+        let input_stream = Self::open_input_stream(jvm, context, this).await?;
+
+        Ok(jvm
+            .new_class("java/io/DataInputStream", "(Ljava/io/InputStream;)V", (input_stream,))
+            .await?
+            .into())
+    }
+
+    async fn open_output_stream(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<ClassInstanceRef<OutputStream>> {
+        tracing::debug!("net.wie.SocketConnectionImpl::openOutputStream({this:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+
+        Ok(jvm.new_class("net/wie/NetworkOutputStream", "(I)V", (id,)).await?.into())
+    }
+
+    async fn open_data_output_stream(
+        jvm: &Jvm,
+        context: &mut WieJvmContext,
+        this: ClassInstanceRef<Self>,
+    ) -> JvmResult<ClassInstanceRef<DataOutputStream>> {
+        tracing::debug!("net.wie.SocketConnectionImpl::openDataOutputStream({this:?})");
+
+        let output_stream = Self::open_output_stream(jvm, context, this).await?;
+
+        Ok(jvm
+            .new_class("java/io/DataOutputStream", "(Ljava/io/OutputStream;)V", (output_stream,))
+            .await?
+            .into())
+    }
+
+    async fn close(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<()> {
+        tracing::debug!("net.wie.SocketConnectionImpl::close({this:?})");
+
+        let id: i32 = jvm.get_field(&this, "id", "I").await?;
+        let _ = context.system().sockets().close(id as _);
+
+        Ok(())
+    }
+}
@@ -5,8 +5,17 @@ pub mod classes;
 
 use wie_jvm_support::WieJavaClassProto;
 
-pub fn get_protos() -> [WieJavaClassProto; 26] {
+pub fn get_protos() -> [WieJavaClassProto; 42] {
     [
+        classes::javax::microedition::io::Connection::as_proto(),
+        classes::javax::microedition::io::ConnectionNotFoundException::as_proto(),
+        classes::javax::microedition::io::Connector::as_proto(),
+        classes::javax::microedition::io::ContentConnection::as_proto(),
+        classes::javax::microedition::io::HttpConnection::as_proto(),
+        classes::javax::microedition::io::InputConnection::as_proto(),
+        classes::javax::microedition::io::OutputConnection::as_proto(),
+        classes::javax::microedition::io::SocketConnection::as_proto(),
+        classes::javax::microedition::io::StreamConnection::as_proto(),
         classes::javax::microedition::lcdui::Alert::as_proto(),
         classes::javax::microedition::lcdui::AlertType::as_proto(),
         classes::javax::microedition::lcdui::Canvas::as_proto(),
@@ -29,9 +38,16 @@ pub fn get_protos() -> [WieJavaClassProto; 26] {
         classes::javax::microedition::rms::InvalidRecordIDException::as_proto(),
         classes::javax::microedition::rms::RecordStore::as_proto(),
         classes::javax::microedition::rms::RecordStoreException::as_proto(),
+        classes::net::wie::AudioPlayer::as_proto(),
         classes::net::wie::EventQueue::as_proto(),
+        classes::net::wie::HttpConnectionImpl::as_proto(),
         classes::net::wie::Launcher::as_proto(),
+        classes::net::wie::MidiPlayer::as_proto(),
+        classes::net::wie::NetworkInputStream::as_proto(),
+        classes::net::wie::NetworkOutputStream::as_proto(),
         classes::net::wie::SmafPlayer::as_proto(),
+        classes::net::wie::SocketConnectionImpl::as_proto(),
+        classes::net::wie::WavePlayer::as_proto(),
         classes::net::wie::WieError::as_proto(),
     ]
 }
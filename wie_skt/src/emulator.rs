@@ -10,7 +10,7 @@ use alloc::{
 
 use jvm::{Result as JvmResult, runtime::JavaLangString};
 
-use wie_backend::{DefaultTaskRunner, Emulator, Event, Platform, System};
+use wie_backend::{DefaultTaskRunner, Emulator, Event, HandsetProfile, Platform, System};
 use wie_jvm_support::{JvmSupport, RustJavaJvmImplementation};
 use wie_util::{Result, WieError};
 
@@ -19,7 +19,7 @@ pub struct SktEmulator {
 }
 
 impl SktEmulator {
-    pub fn from_archive(platform: Box<dyn Platform>, files: BTreeMap<String, Vec<u8>>) -> Result<Self> {
+    pub fn from_archive(platform: Box<dyn Platform>, handset: HandsetProfile, files: BTreeMap<String, Vec<u8>>) -> Result<Self> {
         let msd_file = files.iter().find(|x| x.0.ends_with(".msd")).unwrap();
         let msd = SktMsd::parse(msd_file.0, msd_file.1);
 
@@ -27,13 +27,20 @@ impl SktEmulator {
 
         let jar_filename = msd_file.0.replace(".msd", ".jar");
 
-        Self::load(platform, &jar_filename, &msd.id, Some(msd.main_class), msd.properties, &files)
+        Self::load(platform, handset, &jar_filename, &msd.id, Some(msd.main_class), msd.properties, &files)
     }
 
-    pub fn from_jar(platform: Box<dyn Platform>, jar_filename: &str, jar: Vec<u8>, id: &str, main_class_name: Option<String>) -> Result<Self> {
+    pub fn from_jar(
+        platform: Box<dyn Platform>,
+        handset: HandsetProfile,
+        jar_filename: &str,
+        jar: Vec<u8>,
+        id: &str,
+        main_class_name: Option<String>,
+    ) -> Result<Self> {
         let files = [(jar_filename.to_owned(), jar)].into_iter().collect();
 
-        Self::load(platform, jar_filename, id, main_class_name, BTreeMap::new(), &files)
+        Self::load(platform, handset, jar_filename, id, main_class_name, BTreeMap::new(), &files)
     }
 
     pub fn loadable_archive(files: &BTreeMap<String, Vec<u8>>) -> bool {
@@ -46,13 +53,16 @@ impl SktEmulator {
 
     fn load(
         platform: Box<dyn Platform>,
+        handset: HandsetProfile,
         jar_filename: &str,
         id: &str,
         main_class_name: Option<String>,
         properties: BTreeMap<String, String>,
         files: &BTreeMap<String, Vec<u8>>,
     ) -> Result<Self> {
-        let system = System::new(platform, id, id, DefaultTaskRunner);
+        let mut system = System::new(platform, handset, id, id, DefaultTaskRunner);
+        system.load_game_config(files)?;
+        let main_class_name = system.game_config().main_class.clone().or(main_class_name);
 
         for (filename, data) in files {
             system.filesystem().add_virtual(filename, data.clone())
@@ -73,12 +83,13 @@ impl SktEmulator {
         properties: BTreeMap<String, String>,
         main_class_name: Option<String>,
     ) -> Result<()> {
+        let handset = system.handset().clone();
         let system_properties = [
-            ("MIN", "01000000000"),
-            ("m.MIN", "01000000000"),
+            ("MIN", handset.phone_number.as_str()),
+            ("m.MIN", handset.phone_number.as_str()),
             ("m.COLOR", "7"),
             ("m.VENDER", "vender"),
-            ("m.CARRIER", "SKT"),
+            ("m.CARRIER", handset.carrier.as_deref().unwrap_or("SKT")),
             ("m.SK_VM", "10"),
             ("com.xce.wipi.version", ""),
         ];
@@ -127,7 +138,7 @@ impl SktEmulator {
 
 impl Emulator for SktEmulator {
     fn handle_event(&mut self, event: Event) {
-        self.system.event_queue().push(event)
+        self.system.handle_event(event)
     }
 
     fn tick(&mut self) -> Result<()> {
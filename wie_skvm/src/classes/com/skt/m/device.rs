@@ -58,8 +58,10 @@ impl Device {
         Ok(())
     }
 
-    async fn set_key_repeat_time(_jvm: &Jvm, _context: &mut WieJvmContext, delay: i32, interval: i32) -> JvmResult<()> {
-        tracing::warn!("stub com.skt.m.Device::setKeyRepeatTime({delay}, {interval})");
+    async fn set_key_repeat_time(_jvm: &Jvm, context: &mut WieJvmContext, delay: i32, interval: i32) -> JvmResult<()> {
+        tracing::debug!("com.skt.m.Device::setKeyRepeatTime({delay}, {interval})");
+
+        context.system().platform().set_key_repeat(delay.max(0) as u64, interval.max(0) as u64);
 
         Ok(())
     }
@@ -617,7 +617,7 @@ mod tests {
     use alloc::boxed::Box;
 
     use test_utils::TestPlatform;
-    use wie_backend::{DefaultTaskRunner, System};
+    use wie_backend::{DefaultTaskRunner, HandsetProfile, System};
     use wie_util::{ByteRead, ByteWrite};
 
     use crate::context::test::TestContext;
@@ -696,7 +696,13 @@ mod tests {
     }
 
     fn database_test_context() -> TestContext {
-        let system = System::new(Box::new(TestPlatform::new()), "test-pid", "test-aid", DefaultTaskRunner);
+        let system = System::new(
+            Box::new(TestPlatform::new()),
+            HandsetProfile::default(),
+            "test-pid",
+            "test-aid",
+            DefaultTaskRunner,
+        );
         TestContext::with_system(system)
     }
 
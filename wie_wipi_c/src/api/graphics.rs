@@ -1,3 +1,4 @@
+mod font;
 mod framebuffer;
 mod grp_context;
 mod image;
@@ -8,7 +9,7 @@ use alloc::{string::String, vec, vec::Vec};
 
 use wie_backend::{
     Event,
-    canvas::{Clip, Color, PixelType, Rgb8Pixel, Rgb565Pixel, TextAlignment, string_width},
+    canvas::{Clip, Color, PixelType, Rgb8Pixel, Rgb565Pixel, TextAlignment},
 };
 use wie_util::{Result, read_generic, read_null_terminated_string_bytes, write_generic};
 
@@ -16,7 +17,12 @@ use wipi_types::wipic::{WIPICDisplayInfo, WIPICFramebuffer, WIPICGraphicsContext
 
 use crate::context::WIPICContext;
 
-use self::{framebuffer::FrameBuffer, grp_context::WIPICGraphicsContextIdx, image::create_wipi_image};
+use self::{
+    font::{font_from_handle, font_handle},
+    framebuffer::FrameBuffer,
+    grp_context::WIPICGraphicsContextIdx,
+    image::create_wipi_image,
+};
 
 const FRAMEBUFFER_DEPTH: u32 = 16; // XXX hardcode to 16bpp as some game requires 16bpp framebuffer
 const SCREEN_FRAMEBUFFER_PTR: u32 = 0x7fff1000;
@@ -330,12 +336,13 @@ pub async fn get_display_info(context: &mut dyn WIPICContext, reserved: WIPICWor
 
     assert_eq!(reserved, 0);
 
+    let depth = context.system().handset().color_depth;
     let platform = context.system().platform();
     let screen = platform.screen();
 
     let info = WIPICDisplayInfo {
         bpp: FRAMEBUFFER_DEPTH,
-        depth: 16,
+        depth,
         width: screen.width(),
         height: screen.height(),
         bpl: 2 * screen.width(),
@@ -443,27 +450,27 @@ pub async fn copy_frame_buffer(
 }
 
 pub async fn get_font(_: &mut dyn WIPICContext, face: i32, size: i32, style: i32) -> Result<i32> {
-    tracing::warn!("stub MC_grpGetFont({face}, {size}, {style})");
+    tracing::debug!("MC_grpGetFont({face}, {size}, {style})");
 
-    Ok(0)
+    Ok(font_handle(face, size, style))
 }
 
-pub async fn get_font_height(_: &mut dyn WIPICContext, font: i32) -> Result<i32> {
-    tracing::warn!("stub MC_grpGetFontHeight({font})");
+pub async fn get_font_height(context: &mut dyn WIPICContext, font: i32) -> Result<i32> {
+    tracing::debug!("MC_grpGetFontHeight({font})");
 
-    Ok(12)
+    Ok(context.system().font_pack().get(font_from_handle(font)).height() as _)
 }
 
-pub async fn get_font_ascent(_: &mut dyn WIPICContext, font: i32) -> Result<i32> {
-    tracing::warn!("stub MC_grpGetFontAscent({font})");
+pub async fn get_font_ascent(context: &mut dyn WIPICContext, font: i32) -> Result<i32> {
+    tracing::debug!("MC_grpGetFontAscent({font})");
 
-    Ok(10)
+    Ok(context.system().font_pack().get(font_from_handle(font)).ascent() as _)
 }
 
-pub async fn get_font_descent(_: &mut dyn WIPICContext, font: i32) -> Result<i32> {
-    tracing::warn!("stub MC_grpGetFontDescent({font})");
+pub async fn get_font_descent(context: &mut dyn WIPICContext, font: i32) -> Result<i32> {
+    tracing::debug!("MC_grpGetFontDescent({font})");
 
-    Ok(2)
+    Ok(context.system().font_pack().get(font_from_handle(font)).descent() as _)
 }
 
 pub async fn get_string_width(context: &mut dyn WIPICContext, font: i32, ptr_string: WIPICWord, length: i32) -> Result<i32> {
@@ -475,7 +482,7 @@ pub async fn get_string_width(context: &mut dyn WIPICContext, font: i32, ptr_str
     }
     let s = String::from_utf8_lossy(&bytes);
 
-    Ok(string_width(&s, 10.0) as i32)
+    Ok(context.system().font_pack().get(font_from_handle(font)).string_width(&s) as i32)
 }
 
 pub async fn draw_string(
@@ -499,9 +506,12 @@ pub async fn draw_string(
 
     let string = String::from_utf8_lossy(&string_bytes);
 
+    let system = context.system().clone();
+    let font = system.font_pack().get(font_from_handle(gctx.font as _));
+
     let mut canvas = framebuffer.canvas(context)?;
     let color = framebuffer.pixel_to_color(gctx.fgpxl);
-    canvas.draw_text(&string, x, y, TextAlignment::Left, color);
+    canvas.draw_text(&string, x, y, font, TextAlignment::Left, color);
 
     Ok(())
 }
@@ -0,0 +1,36 @@
+use wie_backend::canvas::{Font, FontSize, FontStyle};
+
+// MC_GRP_FONT_* values, the same as midp's
+const STYLE_BOLD: i32 = 1;
+const STYLE_ITALIC: i32 = 2;
+const STYLE_UNDERLINED: i32 = 4;
+const SIZE_SMALL: i32 = 8;
+const SIZE_LARGE: i32 = 16;
+
+const STYLE_MASK: i32 = STYLE_BOLD | STYLE_ITALIC | STYLE_UNDERLINED;
+const SIZE_MASK: i32 = SIZE_SMALL | SIZE_LARGE;
+const FACE_MASK: i32 = 0x60;
+
+/// Font handles are the face, size and style flags or'ed together, so there is nothing to allocate or free.
+pub fn font_handle(face: i32, size: i32, style: i32) -> i32 {
+    (face & FACE_MASK) | (size & SIZE_MASK) | (style & STYLE_MASK)
+}
+
+pub fn font_from_handle(handle: i32) -> Font {
+    let size = if handle & SIZE_SMALL != 0 {
+        FontSize::Small
+    } else if handle & SIZE_LARGE != 0 {
+        FontSize::Large
+    } else {
+        FontSize::Medium
+    };
+
+    Font::new(
+        size,
+        FontStyle {
+            bold: handle & STYLE_BOLD != 0,
+            italic: handle & STYLE_ITALIC != 0,
+            underline: handle & STYLE_UNDERLINED != 0,
+        },
+    )
+}
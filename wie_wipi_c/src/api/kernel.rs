@@ -31,25 +31,9 @@ pub async fn get_system_property(context: &mut dyn WIPICContext, ptr_id: WIPICWo
 
     let id = String::from_utf8(read_null_terminated_string_bytes(context, ptr_id)?).unwrap();
 
-    let value = match id.as_ref() {
-        "RSSILEVEL" => "30",
-        "BATTERYLEVEL" => "100",
-        "PHONEMODEL" => "Emulator",
-        "PHONENUMBER" => "", // putting this cause some game to fail authentication
-        "MIN" => "01000000000",
-        "ANNUN_CALL" => "0",
-        "ANNUN_SMS" => "0",
-        "ANNUN_SILENT" => "0",
-        "ANNUN_ALARM" => "0",
-        "ANNUN_SECURITY" => "0",
-        "CURRENTCH" => "0",
-        "AIRPLANE_MODE" => "0",
-        "ROAMING_AREA" => "0",
-        "DS_LOCK" => "0",
-        _ => {
-            tracing::warn!("unknown system property id: {id}");
-            return Ok(-9); // M_E_INVALID
-        }
+    let Some(value) = context.system().handset().system_property(&id) else {
+        tracing::warn!("unknown system property id: {id}");
+        return Ok(-9); // M_E_INVALID
     };
 
     let bytes = value.as_bytes();
@@ -251,16 +235,16 @@ pub async fn sprintk(
     Ok(result.len() as _)
 }
 
-pub async fn get_total_memory(_context: &mut dyn WIPICContext) -> Result<i32> {
-    tracing::warn!("stub MC_knlGetTotalMemory()");
+pub async fn get_total_memory(context: &mut dyn WIPICContext) -> Result<i32> {
+    tracing::debug!("MC_knlGetTotalMemory()");
 
-    Ok(0x100000) // TODO hardcoded
+    Ok(context.system().handset().total_memory as _)
 }
 
-pub async fn get_free_memory(_context: &mut dyn WIPICContext) -> Result<i32> {
-    tracing::warn!("stub MC_knlGetFreeMemory()");
+pub async fn get_free_memory(context: &mut dyn WIPICContext) -> Result<i32> {
+    tracing::debug!("MC_knlGetFreeMemory()");
 
-    Ok(0x100000) // TODO hardcoded
+    Ok(context.system().handset().free_memory as _)
 }
 
 fn sprintf(context: &mut dyn WIPICContext, format: &str, args: &[u32]) -> Result<String> {
@@ -371,6 +355,8 @@ pub async fn get_program_name(context: &mut dyn WIPICContext, name_buf: WIPICWor
 mod test {
     use alloc::{boxed::Box, string::String};
 
+    use test_utils::TestPlatform;
+    use wie_backend::{DefaultTaskRunner, HandsetProfile, System};
     use wie_util::{ByteRead, ByteWrite, Result, read_null_terminated_string_bytes, write_null_terminated_string_bytes};
 
     use crate::{WIPICContext, context::test::TestContext, method::MethodImpl};
@@ -407,7 +393,11 @@ mod test {
 
     #[futures_test::test]
     async fn test_get_system_property_min() -> Result<()> {
-        let mut context = TestContext::new();
+        let handset = HandsetProfile {
+            phone_number: "01012345678".into(),
+            ..Default::default()
+        };
+        let mut context = TestContext::with_system(System::new(Box::new(TestPlatform::new()), handset, "", "", DefaultTaskRunner));
         let id = context.alloc_raw(16).unwrap();
         let out = context.alloc_raw(16).unwrap();
 
@@ -415,7 +405,12 @@ mod test {
 
         assert_eq!(get_system_property(&mut context, id, out, 16).await.unwrap(), 0);
         let result = read_null_terminated_string_bytes(&context, out).unwrap();
-        assert_eq!(String::from_utf8(result).unwrap(), "01000000000");
+        assert_eq!(String::from_utf8(result).unwrap(), "01012345678");
+
+        assert_eq!(get_system_property(&mut context, id, out, 11).await.unwrap(), -18);
+
+        write_null_terminated_string_bytes(&mut context, id, b"UNKNOWN").unwrap();
+        assert_eq!(get_system_property(&mut context, id, out, 16).await.unwrap(), -9);
 
         Ok(())
     }
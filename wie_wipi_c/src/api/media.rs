@@ -104,20 +104,23 @@ pub async fn clip_get_type(_context: &mut dyn WIPICContext, clip: WIPICWord, buf
     Ok(0)
 }
 
-pub async fn get_mute_state(_context: &mut dyn WIPICContext, source: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaGetMuteState({source:#x})");
+pub async fn get_mute_state(context: &mut dyn WIPICContext, source: WIPICWord) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaGetMuteState({source:#x})");
 
-    Ok(0)
+    // there's a single output, so every source shares the mute state
+    Ok(context.system().audio().is_muted() as _)
 }
 
+// OEMC_mdaClipGetInfo command codes aren't in the public WIPI headers, so this stays a logged stub until they're
+// confirmed; the log shows which commands apps ask for.
 pub async fn clip_get_info(
     _context: &mut dyn WIPICContext,
-    clip: WIPICWord,
+    ptr_clip: WIPICWord,
     command: WIPICWord,
     buf: WIPICWord,
     buf_size: WIPICWord,
-) -> Result<WIPICWord> {
-    tracing::warn!("stub OEMC_mdaClipGetInfo({clip:#x}, {command:#x}, {buf:#x}, {buf_size:#x})");
+) -> Result<i32> {
+    tracing::warn!("stub OEMC_mdaClipGetInfo({ptr_clip:#x}, {command:#x}, {buf:#x}, {buf_size:#x})");
 
     Ok(0)
 }
@@ -153,20 +156,54 @@ pub async fn clip_get_data(_context: &mut dyn WIPICContext, clip: WIPICWord, buf
     Ok(0)
 }
 
-pub async fn clip_set_position(_context: &mut dyn WIPICContext, clip: WIPICWord, ms: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaClipSetPosition({clip:#x}, {ms:#x})");
+pub async fn clip_set_position(context: &mut dyn WIPICContext, ptr_clip: WIPICWord, ms: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_mdaClipSetPosition({ptr_clip:#x}, {ms})");
+
+    if ptr_clip == 0 {
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
+
+    let mut clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    let system = context.system();
+    if let Err(x) = system.audio().set_position(system, clip.handle, ms as _) {
+        tracing::error!("Failed to seek audio: {x:?}");
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
+
+    clip.position = ms as _;
+    write_generic(context, ptr_clip, clip)?;
 
     Ok(0)
 }
 
-pub async fn clip_get_volume(_context: &mut dyn WIPICContext, clip: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaClipGetVolume({clip:#x})");
+pub async fn clip_get_volume(context: &mut dyn WIPICContext, ptr_clip: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_mdaClipGetVolume({ptr_clip:#x})");
 
-    Ok(0)
+    if ptr_clip == 0 {
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    match context.system().audio().volume(clip.handle) {
+        Ok(x) => Ok(x as _),
+        Err(_) => Ok(-25), // M_E_INVALIDHANDLE
+    }
 }
 
-pub async fn clip_set_volume(_context: &mut dyn WIPICContext, clip: WIPICWord, volume: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaClipSetVolume({clip:#x}, {volume:#x})");
+pub async fn clip_set_volume(context: &mut dyn WIPICContext, ptr_clip: WIPICWord, volume: i32) -> Result<i32> {
+    tracing::debug!("MC_mdaClipSetVolume({ptr_clip:#x}, {volume})");
+
+    if ptr_clip == 0 {
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    if context.system().audio().set_volume(clip.handle, volume.clamp(0, 100) as _).is_err() {
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
 
     Ok(0)
 }
@@ -197,8 +234,19 @@ pub async fn play(context: &mut dyn WIPICContext, ptr_clip: WIPICWord, repeat: W
     Ok(0)
 }
 
-pub async fn clip_alloc_player(_context: &mut dyn WIPICContext, clip: WIPICWord, param: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaClipAllocPlayer({clip:#x}, {param:#x})");
+// players are created per play by the backend, so allocating one only has to check the clip has data to play
+pub async fn clip_alloc_player(context: &mut dyn WIPICContext, ptr_clip: WIPICWord, param: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_mdaClipAllocPlayer({ptr_clip:#x}, {param:#x})");
+
+    if ptr_clip == 0 {
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    if !context.system().audio().contains(clip.handle) {
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
 
     Ok(0)
 }
@@ -219,20 +267,44 @@ pub async fn vibrator(context: &mut dyn WIPICContext, level: i32, timeout: i32)
     Ok(0)
 }
 
-pub async fn set_mute_state(_context: &mut dyn WIPICContext, source: i32, b_mute: i32) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaSetMuteState({source:#x}, {b_mute})");
+pub async fn set_mute_state(context: &mut dyn WIPICContext, source: i32, b_mute: i32) -> Result<WIPICWord> {
+    tracing::debug!("MC_mdaSetMuteState({source:#x}, {b_mute})");
+
+    context.system().audio().set_muted(b_mute != 0);
 
     Ok(0)
 }
 
-pub async fn pause(_context: &mut dyn WIPICContext, clip: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaPause({clip:#x})");
+pub async fn pause(context: &mut dyn WIPICContext, ptr_clip: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_mdaPause({ptr_clip:#x})");
+
+    if ptr_clip == 0 {
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    if let Err(x) = context.system().audio().pause(clip.handle) {
+        tracing::warn!("Failed to pause audio: {x:?}");
+        return Ok(-1); // M_E_ERROR
+    }
 
     Ok(0)
 }
 
-pub async fn resume(_context: &mut dyn WIPICContext, clip: WIPICWord) -> Result<WIPICWord> {
-    tracing::warn!("stub MC_mdaResume({clip:#x})");
+pub async fn resume(context: &mut dyn WIPICContext, ptr_clip: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_mdaResume({ptr_clip:#x})");
+
+    if ptr_clip == 0 {
+        return Ok(-25); // M_E_INVALIDHANDLE
+    }
+
+    let clip: MdaClip = read_generic(context, ptr_clip)?;
+
+    if let Err(x) = context.system().audio().resume(clip.handle) {
+        tracing::warn!("Failed to resume audio: {x:?}");
+        return Ok(-1); // M_E_ERROR
+    }
 
     Ok(0)
 }
@@ -1,13 +1,54 @@
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
 
 use wipi_types::wipic::WIPICWord;
 
-use wie_util::{Result, WieError};
+use wie_backend::{HttpRequest, HttpResponse, NetworkError, SocketType};
+use wie_util::{Result, WieError, read_null_terminated_string_bytes, write_null_terminated_string_bytes};
 
 use crate::{WIPICResult, context::WIPICContext, method::MethodBody};
 
+// how often a read callback checks its socket for incoming data
+const READ_POLL_INTERVAL: u64 = 10;
+
+fn error_code(error: NetworkError) -> i32 {
+    match error {
+        NetworkError::WouldBlock => -19,   // M_E_WOULDBLOCK
+        NetworkError::InvalidSocket => -2, // M_E_BADFD
+        _ => -1,                           // M_E_ERROR
+    }
+}
+
+// addresses and ports are passed in network byte order, as produced by MC_utilHtons and friends. guest words are
+// little endian arm words, so the first byte in network order is the word's lowest byte whatever the host is.
+fn addr_from_word(addr: WIPICWord) -> [u8; 4] {
+    [addr as u8, (addr >> 8) as u8, (addr >> 16) as u8, (addr >> 24) as u8]
+}
+
+fn addr_to_word(addr: [u8; 4]) -> WIPICWord {
+    addr.iter().rev().fold(0, |word, &x| (word << 8) | x as WIPICWord)
+}
+
+fn port_from_word(port: WIPICWord) -> u16 {
+    u16::from_be_bytes([port as u8, (port >> 8) as u8])
+}
+
+fn read_string(context: &mut dyn WIPICContext, ptr: WIPICWord) -> Result<String> {
+    String::from_utf8(read_null_terminated_string_bytes(context, ptr)?).map_err(|_| WieError::FatalError("Invalid string".into()))
+}
+
+// copies `value` into a guest buffer of `len` bytes, returning its length or M_E_SHORTBUF
+fn write_string(context: &mut dyn WIPICContext, buf: WIPICWord, len: i32, value: &str) -> Result<i32> {
+    if value.len() + 1 > len.max(0) as usize {
+        return Ok(-18); // M_E_SHORTBUF
+    }
+
+    write_null_terminated_string_bytes(context, buf, value.as_bytes())?;
+
+    Ok(value.len() as _)
+}
+
 pub async fn connect(context: &mut dyn WIPICContext, cb: WIPICWord, param: WIPICWord) -> Result<i32> {
-    tracing::warn!("stub MC_netConnect({cb:#x}, {param:#x})");
+    tracing::debug!("MC_netConnect({cb:#x}, {param:#x})");
 
     struct ConnectCallback {
         cb: WIPICWord,
@@ -20,7 +61,7 @@ pub async fn connect(context: &mut dyn WIPICContext, cb: WIPICWord, param: WIPIC
         async fn call(&self, context: &mut dyn WIPICContext, _: Box<[WIPICWord]>) -> Result<WIPICResult> {
             context.system().sleep(1).await; // simulate some delay
 
-            context.call_function(self.cb, &[u32::MAX, self.param]).await?; // callback with M_E_ERROR
+            context.call_function(self.cb, &[0, self.param]).await?; // callback with M_E_SUCCESS
 
             Ok(WIPICResult { results: Vec::new() })
         }
@@ -31,14 +72,534 @@ pub async fn connect(context: &mut dyn WIPICContext, cb: WIPICWord, param: WIPIC
     Ok(0)
 }
 
-pub async fn close(_context: &mut dyn WIPICContext) -> Result<()> {
-    tracing::warn!("stub MC_netClose()");
+pub async fn close(context: &mut dyn WIPICContext) -> Result<()> {
+    tracing::debug!("MC_netClose()");
+
+    context.system().sockets().close_all();
 
     Ok(())
 }
 
-pub async fn socket_close(_context: &mut dyn WIPICContext, fd: i32) -> Result<i32> {
-    tracing::warn!("stub MC_netSocketClose({fd})");
+pub async fn socket(context: &mut dyn WIPICContext, domain: i32, r#type: i32) -> Result<i32> {
+    tracing::debug!("MC_netSocket({domain}, {type})");
+
+    let socket_type = if r#type == 2 { SocketType::Datagram } else { SocketType::Stream };
+
+    Ok(match context.system().sockets().open(socket_type) {
+        Ok(x) => x as _,
+        Err(x) => error_code(x),
+    })
+}
+
+pub async fn socket_connect(
+    context: &mut dyn WIPICContext,
+    fd: i32,
+    addr: WIPICWord,
+    port: WIPICWord,
+    cb: WIPICWord,
+    param: WIPICWord,
+) -> Result<i32> {
+    tracing::debug!("MC_netSocketConnect({fd}, {addr:#x}, {port:#x}, {cb:#x}, {param:#x})");
+
+    struct SocketConnectCallback {
+        fd: i32,
+        addr: [u8; 4],
+        port: u16,
+        cb: WIPICWord,
+        param: WIPICWord,
+    }
+
+    #[async_trait::async_trait]
+    impl MethodBody<WieError> for SocketConnectCallback {
+        #[tracing::instrument(name = "net", skip_all)]
+        async fn call(&self, context: &mut dyn WIPICContext, _: Box<[WIPICWord]>) -> Result<WIPICResult> {
+            let system = context.system().clone();
+            let result = match system.connect_socket(self.fd as _, self.addr, self.port).await {
+                Ok(()) => 0,
+                Err(x) => error_code(x),
+            };
+
+            context.call_function(self.cb, &[self.fd as _, result as _, self.param]).await?;
+
+            Ok(WIPICResult { results: Vec::new() })
+        }
+    }
+
+    let addr = addr_from_word(addr);
+    let port = port_from_word(port);
+
+    // with a callback the connect completes in the background and its result is only reported through the callback
+    if cb != 0 {
+        context.spawn(Box::new(SocketConnectCallback { fd, addr, port, cb, param }))?;
+
+        return Ok(-19); // M_E_WOULDBLOCK
+    }
+
+    let system = context.system().clone();
+    Ok(match system.connect_socket(fd as _, addr, port).await {
+        Ok(()) => 0,
+        Err(x) => error_code(x),
+    })
+}
+
+pub async fn socket_write(context: &mut dyn WIPICContext, fd: i32, buf: WIPICWord, len: i32) -> Result<i32> {
+    tracing::debug!("MC_netSocketWrite({fd}, {buf:#x}, {len})");
+
+    let mut data = vec![0; len.max(0) as usize];
+    context.read_bytes(buf, &mut data)?;
+
+    Ok(match context.system().sockets().send(fd as _, &data) {
+        Ok(x) => x as _,
+        Err(x) => error_code(x),
+    })
+}
+
+pub async fn socket_read(context: &mut dyn WIPICContext, fd: i32, buf: WIPICWord, len: i32) -> Result<i32> {
+    tracing::debug!("MC_netSocketRead({fd}, {buf:#x}, {len})");
+
+    let mut data = vec![0; len.max(0) as usize];
+    let result = context.system().sockets().recv(fd as _, &mut data);
+
+    Ok(match result {
+        Ok(x) => {
+            context.write_bytes(buf, &data[..x])?;
+
+            x as _
+        }
+        Err(x) => error_code(x),
+    })
+}
+
+pub async fn socket_close(context: &mut dyn WIPICContext, fd: i32) -> Result<i32> {
+    tracing::debug!("MC_netSocketClose({fd})");
+
+    Ok(match context.system().sockets().close(fd as _) {
+        Ok(()) => 0,
+        Err(x) => error_code(x),
+    })
+}
+
+/// Resolves `name` on the host and reports the address (or a negative error) through `cb(addr, param)`.
+pub async fn get_host_addr(context: &mut dyn WIPICContext, name: WIPICWord, cb: WIPICWord, param: WIPICWord) -> Result<i32> {
+    let name = String::from_utf8(read_null_terminated_string_bytes(context, name)?).map_err(|_| WieError::FatalError("Invalid host name".into()))?;
+
+    tracing::debug!("MC_netGetHostAddr({name}, {cb:#x}, {param:#x})");
+
+    struct GetHostAddrCallback {
+        result: WIPICWord,
+        cb: WIPICWord,
+        param: WIPICWord,
+    }
+
+    #[async_trait::async_trait]
+    impl MethodBody<WieError> for GetHostAddrCallback {
+        #[tracing::instrument(name = "net", skip_all)]
+        async fn call(&self, context: &mut dyn WIPICContext, _: Box<[WIPICWord]>) -> Result<WIPICResult> {
+            context.call_function(self.cb, &[self.result, self.param]).await?;
+
+            Ok(WIPICResult { results: Vec::new() })
+        }
+    }
+
+    let result = match context.system().sockets().resolve(&name) {
+        Ok(x) => addr_to_word(x),
+        Err(x) => error_code(x) as _,
+    };
+
+    context.spawn(Box::new(GetHostAddrCallback { result, cb, param }))?;
+
+    Ok(-19) // M_E_WOULDBLOCK
+}
+
+/// Calls `cb(fd, M_E_SUCCESS, param)` whenever `fd` has data to read, until the callback is replaced or cleared with a null `cb`.
+pub async fn set_read_cb(context: &mut dyn WIPICContext, fd: i32, cb: WIPICWord, param: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_netSetReadCB({fd}, {cb:#x}, {param:#x})");
+
+    struct ReadCallback {
+        fd: i32,
+        watch_id: u64,
+        cb: WIPICWord,
+        param: WIPICWord,
+    }
+
+    #[async_trait::async_trait]
+    impl MethodBody<WieError> for ReadCallback {
+        #[tracing::instrument(name = "net", skip_all)]
+        async fn call(&self, context: &mut dyn WIPICContext, _: Box<[WIPICWord]>) -> Result<WIPICResult> {
+            loop {
+                let readable = {
+                    let sockets = context.system().sockets();
+                    if !sockets.is_watching(self.fd as _, self.watch_id) {
+                        break;
+                    }
+
+                    sockets.readable(self.fd as _)
+                };
+
+                if readable {
+                    context.call_function(self.cb, &[self.fd as _, 0, self.param]).await?;
+                }
+
+                context.system().sleep(READ_POLL_INTERVAL).await;
+            }
+
+            Ok(WIPICResult { results: Vec::new() })
+        }
+    }
+
+    if cb == 0 {
+        return Ok(match context.system().sockets().unwatch_read(fd as _) {
+            Ok(()) => 0,
+            Err(x) => error_code(x),
+        });
+    }
+
+    let watch_id = match context.system().sockets().watch_read(fd as _) {
+        Ok(x) => x,
+        Err(x) => return Ok(error_code(x)),
+    };
+
+    context.spawn(Box::new(ReadCallback { fd, watch_id, cb, param }))?;
+
+    Ok(0)
+}
+
+/// Creates an HTTP connection to `url`. The request body is written with `MC_netSocketWrite` before
+/// `MC_netHttpConnect`, and the response body is read with `MC_netSocketRead` afterwards.
+pub async fn http_open(context: &mut dyn WIPICContext, url: WIPICWord) -> Result<i32> {
+    let url = read_string(context, url)?;
+
+    tracing::debug!("MC_netHttpOpen({url})");
+
+    Ok(context.system().sockets().open_http(&url) as _)
+}
+
+/// Sends the request and reports the outcome through `cb(fd, result, param)` once the response has arrived.
+pub async fn http_connect(context: &mut dyn WIPICContext, fd: i32, cb: WIPICWord, param: WIPICWord) -> Result<i32> {
+    tracing::debug!("MC_netHttpConnect({fd}, {cb:#x}, {param:#x})");
+
+    struct HttpConnectCallback {
+        fd: i32,
+        request: HttpRequest,
+        cb: WIPICWord,
+        param: WIPICWord,
+    }
+
+    #[async_trait::async_trait]
+    impl MethodBody<WieError> for HttpConnectCallback {
+        #[tracing::instrument(name = "net", skip_all)]
+        async fn call(&self, context: &mut dyn WIPICContext, _: Box<[WIPICWord]>) -> Result<WIPICResult> {
+            let system = context.system().clone();
+            let response = system.platform().http().request(self.request.clone()).await;
+
+            let result = match &response {
+                Ok(x) => {
+                    tracing::debug!("HTTP {} {} -> {}", self.request.method, self.request.url, x.status);
+
+                    0
+                }
+                Err(x) => error_code(*x),
+            };
+
+            // the app may have closed the connection while the request was in flight
+            match context.system().sockets().http(self.fd as _) {
+                Ok(x) => x.finish_request(response),
+                Err(_) => return Ok(WIPICResult { results: Vec::new() }),
+            }
+
+            if self.cb != 0 {
+                context.call_function(self.cb, &[self.fd as _, result as _, self.param]).await?;
+            }
+
+            Ok(WIPICResult { results: Vec::new() })
+        }
+    }
+
+    let request = match context.system().sockets().http(fd as _).and_then(|x| x.begin_request()) {
+        Ok(x) => x,
+        Err(x) => return Ok(error_code(x)),
+    };
+
+    context.spawn(Box::new(HttpConnectCallback { fd, request, cb, param }))?;
+
+    Ok(0)
+}
+
+pub async fn http_set_request_method(context: &mut dyn WIPICContext, fd: i32, method: WIPICWord) -> Result<i32> {
+    let method = read_string(context, method)?;
+
+    tracing::debug!("MC_netHttpSetRequestMethod({fd}, {method})");
+
+    Ok(match context.system().sockets().http(fd as _) {
+        Ok(x) => {
+            x.set_method(&method);
+
+            0
+        }
+        Err(x) => error_code(x),
+    })
+}
+
+pub async fn http_get_request_method(context: &mut dyn WIPICContext, fd: i32, buf: WIPICWord, len: i32) -> Result<i32> {
+    tracing::debug!("MC_netHttpGetRequestMethod({fd}, {buf:#x}, {len})");
+
+    let method = match context.system().sockets().http(fd as _) {
+        Ok(x) => String::from(x.method()),
+        Err(x) => return Ok(error_code(x)),
+    };
+
+    write_string(context, buf, len, &method)
+}
+
+pub async fn http_set_request_property(context: &mut dyn WIPICContext, fd: i32, field: WIPICWord, value: WIPICWord) -> Result<i32> {
+    let field = read_string(context, field)?;
+    let value = read_string(context, value)?;
+
+    tracing::debug!("MC_netHttpSetRequestProperty({fd}, {field}, {value})");
+
+    Ok(match context.system().sockets().http(fd as _) {
+        Ok(x) => {
+            x.set_request_header(&field, &value);
+
+            0
+        }
+        Err(x) => error_code(x),
+    })
+}
+
+pub async fn http_get_request_property(context: &mut dyn WIPICContext, fd: i32, field: WIPICWord, buf: WIPICWord, len: i32) -> Result<i32> {
+    let field = read_string(context, field)?;
+
+    tracing::debug!("MC_netHttpGetRequestProperty({fd}, {field}, {buf:#x}, {len})");
+
+    let value = match context.system().sockets().http(fd as _) {
+        Ok(x) => x.request_header(&field).map(String::from),
+        Err(x) => return Ok(error_code(x)),
+    };
+
+    match value {
+        Some(x) => write_string(context, buf, len, &x),
+        None => Ok(-12), // M_E_NOENT
+    }
+}
+
+// runs `f` on the response of `fd`, mapping a missing or pending response to its error code
+fn with_response<T>(context: &mut dyn WIPICContext, fd: i32, f: impl FnOnce(&HttpResponse) -> T) -> core::result::Result<T, i32> {
+    let mut sockets = context.system().sockets();
+    let connection = sockets.http(fd as _).map_err(error_code)?;
+
+    connection.response().map(f).map_err(error_code)
+}
+
+pub async fn http_get_response_code(context: &mut dyn WIPICContext, fd: i32) -> Result<i32> {
+    tracing::debug!("MC_netHttpGetResponseCode({fd})");
+
+    Ok(with_response(context, fd, |x| x.status as i32).unwrap_or_else(|x| x))
+}
+
+pub async fn http_get_response_message(context: &mut dyn WIPICContext, fd: i32, buf: WIPICWord, len: i32) -> Result<i32> {
+    tracing::debug!("MC_netHttpGetResponseMessage({fd}, {buf:#x}, {len})");
+
+    match with_response(context, fd, |x| x.message.clone()) {
+        Ok(x) => write_string(context, buf, len, &x),
+        Err(x) => Ok(x),
+    }
+}
+
+fn get_response_header(context: &mut dyn WIPICContext, fd: i32, field: &str, buf: WIPICWord, len: i32) -> Result<i32> {
+    match with_response(context, fd, |x| x.header(field).map(String::from)) {
+        Ok(Some(x)) => write_string(context, buf, len, &x),
+        Ok(None) => Ok(-12), // M_E_NOENT
+        Err(x) => Ok(x),
+    }
+}
+
+pub async fn http_get_header_field(context: &mut dyn WIPICContext, fd: i32, field: WIPICWord, buf: WIPICWord, len: i32) -> Result<i32> {
+    let field = read_string(context, field)?;
+
+    tracing::debug!("MC_netHttpGetHeaderField({fd}, {field}, {buf:#x}, {len})");
+
+    get_response_header(context, fd, &field, buf, len)
+}
+
+/// Returns the response body length, from `Content-Length` when the server sent one.
+pub async fn http_get_length(context: &mut dyn WIPICContext, fd: i32) -> Result<i32> {
+    tracing::debug!("MC_netHttpGetLength({fd})");
+
+    let length = with_response(context, fd, |x| {
+        x.header("Content-Length")
+            .and_then(|x| x.trim().parse::<i32>().ok())
+            .unwrap_or(x.body.len() as _)
+    });
+
+    Ok(length.unwrap_or_else(|x| x))
+}
+
+pub async fn http_get_type(context: &mut dyn WIPICContext, fd: i32, buf: WIPICWord, len: i32) -> Result<i32> {
+    tracing::debug!("MC_netHttpGetType({fd}, {buf:#x}, {len})");
+
+    get_response_header(context, fd, "Content-Type", buf, len)
+}
+
+pub async fn http_get_encoding(context: &mut dyn WIPICContext, fd: i32, buf: WIPICWord, len: i32) -> Result<i32> {
+    tracing::debug!("MC_netHttpGetEncoding({fd}, {buf:#x}, {len})");
+
+    get_response_header(context, fd, "Content-Encoding", buf, len)
+}
+
+pub async fn http_close(context: &mut dyn WIPICContext, fd: i32) -> Result<i32> {
+    tracing::debug!("MC_netHttpClose({fd})");
+
+    Ok(match context.system().sockets().close(fd as _) {
+        Ok(()) => 0,
+        Err(x) => error_code(x),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, vec};
+
+    use test_utils::TestPlatform;
+    use wie_backend::{DefaultTaskRunner, HandsetProfile, Instant, System, VirtualClock};
+    use wie_util::{ByteRead, ByteWrite, read_null_terminated_string_bytes, write_null_terminated_string_bytes};
+
+    use crate::context::test::TestContext;
+
+    use super::{
+        addr_from_word, addr_to_word, http_close, http_connect, http_get_header_field, http_get_length, http_get_response_code, http_get_type,
+        http_open, http_set_request_method, http_set_request_property, port_from_word, socket, socket_close, socket_connect, socket_read,
+        socket_write,
+    };
+
+    #[test]
+    fn network_byte_order_words() {
+        // 127.0.0.1:80 as stored by MC_utilHtonl/MC_utilHtons on the little endian guest
+        assert_eq!(addr_from_word(0x0100007f), [127, 0, 0, 1]);
+        assert_eq!(addr_to_word([127, 0, 0, 1]), 0x0100007f);
+        assert_eq!(port_from_word(0x5000), 80);
+    }
 
-    Ok(-1) // M_E_ERROR
+    #[futures_test::test]
+    async fn socket_roundtrip_over_loopback() {
+        let system = System::new(
+            Box::new(TestPlatform::new()),
+            HandsetProfile::default(),
+            "test-pid",
+            "test-aid",
+            DefaultTaskRunner,
+        );
+        let mut context = TestContext::with_system(system);
+
+        let fd = socket(&mut context, 1, 1).await.unwrap();
+        assert!(fd > 0);
+
+        assert_eq!(socket_read(&mut context, fd, 0x2000, 4).await.unwrap(), -1); // not connected
+        assert_eq!(socket_connect(&mut context, fd, 0x0100007f, 0x5000, 0, 0).await.unwrap(), 0);
+        assert_eq!(socket_read(&mut context, fd, 0x2000, 4).await.unwrap(), -19);
+
+        context.write_bytes(0x1000, b"ping").unwrap();
+        assert_eq!(socket_write(&mut context, fd, 0x1000, 4).await.unwrap(), 4);
+        assert_eq!(socket_read(&mut context, fd, 0x2000, 16).await.unwrap(), 4);
+
+        let mut data = [0; 4];
+        context.read_bytes(0x2000, &mut data).unwrap();
+        assert_eq!(&data, b"ping");
+
+        assert_eq!(socket_close(&mut context, fd).await.unwrap(), 0);
+        assert_eq!(socket_close(&mut context, fd).await.unwrap(), -2);
+        assert_eq!(socket_write(&mut context, fd, 0x1000, 4).await.unwrap(), -2);
+    }
+
+    #[futures_test::test]
+    async fn socket_connect_reports_refused_connection() {
+        let system = System::new(
+            Box::new(TestPlatform::new()),
+            HandsetProfile::default(),
+            "test-pid",
+            "test-aid",
+            DefaultTaskRunner,
+        );
+        let mut context = TestContext::with_system(system);
+
+        let fd = socket(&mut context, 1, 1).await.unwrap();
+        assert_eq!(socket_connect(&mut context, fd, 0x0100007f, 0, 0, 0).await.unwrap(), -1);
+    }
+
+    #[futures_test::test]
+    async fn socket_connect_reports_through_callback() {
+        let platform = TestPlatform::new().with_virtual_clock(VirtualClock::new(Instant::from_epoch_millis(0)));
+        let mut system = System::new(Box::new(platform), HandsetProfile::default(), "test-pid", "test-aid", DefaultTaskRunner);
+        let mut context = TestContext::with_system(system.clone());
+
+        let connected = socket(&mut context, 1, 1).await.unwrap();
+        let refused = socket(&mut context, 1, 1).await.unwrap();
+
+        assert_eq!(
+            socket_connect(&mut context, connected, 0x0100007f, 0x5000, 0x100, 0x11).await.unwrap(),
+            -19
+        );
+        assert_eq!(socket_connect(&mut context, refused, 0x0100007f, 0, 0x200, 0x22).await.unwrap(), -19);
+        assert!(context.calls().is_empty());
+
+        system.tick().unwrap();
+
+        let mut calls = context.calls();
+        calls.sort();
+        assert_eq!(
+            calls,
+            [
+                (0x100, vec![connected as u32, 0, 0x11]),
+                (0x200, vec![refused as u32, -1i32 as u32, 0x22])
+            ]
+        );
+    }
+
+    #[futures_test::test]
+    async fn http_exchange_over_loopback() {
+        // TestPlatform answers HTTP requests with LoopbackHttp
+        let platform = TestPlatform::new().with_virtual_clock(VirtualClock::new(Instant::from_epoch_millis(0)));
+        let mut system = System::new(Box::new(platform), HandsetProfile::default(), "test-pid", "test-aid", DefaultTaskRunner);
+        let mut context = TestContext::with_system(system.clone());
+
+        write_null_terminated_string_bytes(&mut context, 0x1000, b"http://localhost/ranking").unwrap();
+        write_null_terminated_string_bytes(&mut context, 0x1100, b"post").unwrap();
+        write_null_terminated_string_bytes(&mut context, 0x1200, b"X-Score").unwrap();
+        write_null_terminated_string_bytes(&mut context, 0x1300, b"1234").unwrap();
+        write_null_terminated_string_bytes(&mut context, 0x1400, b"x-method").unwrap();
+        context.write_bytes(0x1500, b"score=1234").unwrap();
+
+        let fd = http_open(&mut context, 0x1000).await.unwrap();
+        assert!(fd > 0);
+        assert_eq!(http_set_request_method(&mut context, fd, 0x1100).await.unwrap(), 0);
+        assert_eq!(http_set_request_property(&mut context, fd, 0x1200, 0x1300).await.unwrap(), 0);
+        assert_eq!(socket_write(&mut context, fd, 0x1500, 10).await.unwrap(), 10);
+        assert_eq!(http_get_response_code(&mut context, fd).await.unwrap(), -1); // not connected yet
+
+        assert_eq!(http_connect(&mut context, fd, 0x100, 0x11).await.unwrap(), 0);
+        assert_eq!(http_get_response_code(&mut context, fd).await.unwrap(), -19);
+        assert!(context.calls().is_empty());
+
+        system.tick().unwrap();
+
+        assert_eq!(context.calls(), [(0x100, vec![fd as u32, 0, 0x11])]);
+        assert_eq!(http_get_response_code(&mut context, fd).await.unwrap(), 200);
+        assert_eq!(http_get_length(&mut context, fd).await.unwrap(), 10);
+        assert_eq!(http_get_type(&mut context, fd, 0x2000, 64).await.unwrap(), -12);
+
+        assert_eq!(http_get_header_field(&mut context, fd, 0x1400, 0x2000, 4).await.unwrap(), -18);
+        assert_eq!(http_get_header_field(&mut context, fd, 0x1400, 0x2000, 64).await.unwrap(), 4);
+        assert_eq!(read_null_terminated_string_bytes(&context, 0x2000).unwrap(), b"POST");
+        assert_eq!(http_get_header_field(&mut context, fd, 0x1200, 0x2000, 64).await.unwrap(), 4);
+        assert_eq!(read_null_terminated_string_bytes(&context, 0x2000).unwrap(), b"1234");
+
+        assert_eq!(socket_read(&mut context, fd, 0x3000, 64).await.unwrap(), 10);
+        let mut data = [0; 10];
+        context.read_bytes(0x3000, &mut data).unwrap();
+        assert_eq!(&data, b"score=1234");
+        assert_eq!(socket_read(&mut context, fd, 0x3000, 64).await.unwrap(), 0);
+
+        assert_eq!(http_close(&mut context, fd).await.unwrap(), 0);
+        assert_eq!(http_close(&mut context, fd).await.unwrap(), -2);
+    }
 }
@@ -81,11 +81,13 @@ impl ResultConverter<()> for () {
 
 #[cfg(test)]
 pub mod test {
-    use alloc::{boxed::Box, format, string::String, vec::Vec};
+    use alloc::{boxed::Box, format, string::String, sync::Arc, vec, vec::Vec};
+
+    use spin::Mutex;
 
     use wipi_types::wipic::{WIPICIndirectPtr, WIPICWord};
 
-    use wie_backend::{Instant, System};
+    use wie_backend::{AsyncCallable, Instant, System};
     use wie_util::{ByteRead, ByteWrite, Result, WieError};
 
     use super::{WIPICContext, WIPICMethodBody};
@@ -93,9 +95,16 @@ pub mod test {
     const TEST_MEMORY_SIZE: usize = 0x20000;
     const TEST_ALLOC_START: usize = 0x10000;
 
-    pub struct TestContext {
-        memory: [u8; TEST_MEMORY_SIZE],
+    struct TestState {
+        memory: Vec<u8>,
         last_alloc: usize,
+        calls: Vec<(WIPICWord, Vec<WIPICWord>)>,
+    }
+
+    /// Clones share memory and the calls made, so callbacks spawned on the system's executor run on the same state.
+    #[derive(Clone)]
+    pub struct TestContext {
+        state: Arc<Mutex<TestState>>,
         system: Option<System>,
         resources: Vec<(String, Vec<u8>)>,
     }
@@ -104,8 +113,11 @@ pub mod test {
         #[allow(clippy::new_without_default)]
         pub fn new() -> Self {
             Self {
-                memory: [0; TEST_MEMORY_SIZE],
-                last_alloc: TEST_ALLOC_START,
+                state: Arc::new(Mutex::new(TestState {
+                    memory: vec![0; TEST_MEMORY_SIZE],
+                    last_alloc: TEST_ALLOC_START,
+                    calls: Vec::new(),
+                })),
                 system: None,
                 resources: Vec::new(),
             }
@@ -113,10 +125,8 @@ pub mod test {
 
         pub fn with_system(system: System) -> Self {
             Self {
-                memory: [0; TEST_MEMORY_SIZE],
-                last_alloc: TEST_ALLOC_START,
                 system: Some(system),
-                resources: Vec::new(),
+                ..Self::new()
             }
         }
 
@@ -124,13 +134,19 @@ pub mod test {
             self.resources.push((String::from(name), data.to_vec()));
             self
         }
+
+        /// Guest functions called so far, with their arguments. Calls return 0.
+        pub fn calls(&self) -> Vec<(WIPICWord, Vec<WIPICWord>)> {
+            self.state.lock().calls.clone()
+        }
     }
 
     #[async_trait::async_trait]
     impl WIPICContext for TestContext {
         fn alloc_raw(&mut self, size: WIPICWord) -> Result<WIPICWord> {
-            let address = self.last_alloc;
-            self.last_alloc += size as usize;
+            let mut state = self.state.lock();
+            let address = state.last_alloc;
+            state.last_alloc += size as usize;
 
             Ok(address as WIPICWord)
         }
@@ -151,16 +167,34 @@ pub mod test {
             Ok(memory.0)
         }
 
-        async fn call_function(&mut self, _address: WIPICWord, _args: &[WIPICWord]) -> Result<WIPICWord> {
-            todo!()
+        async fn call_function(&mut self, address: WIPICWord, args: &[WIPICWord]) -> Result<WIPICWord> {
+            self.state.lock().calls.push((address, args.to_vec()));
+
+            Ok(0)
         }
 
         fn system(&mut self) -> &mut System {
             self.system.as_mut().unwrap()
         }
 
-        fn spawn(&mut self, _callback: WIPICMethodBody) -> Result<()> {
-            todo!()
+        fn spawn(&mut self, callback: WIPICMethodBody) -> Result<()> {
+            struct SpawnProxy {
+                context: TestContext,
+                callback: WIPICMethodBody,
+            }
+
+            impl AsyncCallable<Result<()>> for SpawnProxy {
+                async fn call(mut self) -> Result<()> {
+                    self.callback.call(&mut self.context, Box::new([])).await?;
+
+                    Ok(())
+                }
+            }
+
+            let context = self.clone();
+            self.system().spawn(SpawnProxy { context, callback });
+
+            Ok(())
         }
 
         async fn get_resource_size(&self, name: &str) -> Result<Option<usize>> {
@@ -182,7 +216,7 @@ pub mod test {
 
     impl ByteWrite for TestContext {
         fn write_bytes(&mut self, address: u32, data: &[u8]) -> wie_util::Result<()> {
-            self.memory[address as usize..(address + data.len() as u32) as usize].copy_from_slice(data);
+            self.state.lock().memory[address as usize..(address + data.len() as u32) as usize].copy_from_slice(data);
 
             Ok(())
         }
@@ -190,7 +224,7 @@ pub mod test {
 
     impl ByteRead for TestContext {
         fn read_bytes(&self, address: u32, result: &mut [u8]) -> wie_util::Result<usize> {
-            result.copy_from_slice(&self.memory[address as usize..(address as usize + result.len())]);
+            result.copy_from_slice(&self.state.lock().memory[address as usize..(address as usize + result.len())]);
 
             Ok(result.len())
         }
@@ -40,6 +40,12 @@ macro_rules! __impl_method_body {
 
                 Ok(R::convert(context, result))
             }
+
+            fn param_count(&self) -> usize {
+                let params: &[&str] = &[$(stringify!($arg)),*];
+
+                params.len()
+            }
         }
     };
 }
@@ -72,6 +78,11 @@ macro_rules! methods {
         #[async_trait::async_trait]
         pub trait MethodBody<E>: Sync + Send {
             async fn call(&self, context: &mut dyn $context, args: Box<[$raw_type]>) -> Result<WIPICResult, E>;
+
+            /// Number of arguments `call` takes from `args`.
+            fn param_count(&self) -> usize {
+                0
+            }
         }
 
         trait FnHelper<'a, E, R, P> {
@@ -7,7 +7,7 @@ use jvm::{ClassInstanceRef, Jvm, Result as JvmResult, runtime::JavaLangString};
 use wie_jvm_support::{WieJavaClassProto, WieJvmContext};
 use wie_midp::classes::{
     javax::microedition::lcdui::{Display as MidpDisplay, Graphics as MidpGraphics},
-    net::wie::MIDPKeyCode,
+    net::wie::{MIDPKeyCode, PointerEventType},
 };
 
 use crate::classes::org::kwis::msp::lcdui::{Card, Display};
@@ -120,6 +120,9 @@ impl CardCanvas {
                 JavaMethodProto::new("keyPressed", "(I)V", Self::key_pressed, Default::default()),
                 JavaMethodProto::new("keyRepeated", "(I)V", Self::key_repeated, Default::default()),
                 JavaMethodProto::new("keyReleased", "(I)V", Self::key_released, Default::default()),
+                JavaMethodProto::new("pointerPressed", "(II)V", Self::pointer_pressed, Default::default()),
+                JavaMethodProto::new("pointerDragged", "(II)V", Self::pointer_dragged, Default::default()),
+                JavaMethodProto::new("pointerReleased", "(II)V", Self::pointer_released, Default::default()),
                 JavaMethodProto::new("pushCard", "(Lorg/kwis/msp/lcdui/Card;)V", Self::push_card, Default::default()),
                 JavaMethodProto::new("removeAllCards", "()V", Self::remove_all_cards, Default::default()),
                 // wie private
@@ -223,6 +226,40 @@ impl CardCanvas {
         Ok(())
     }
 
+    async fn pointer_pressed(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, x: i32, y: i32) -> JvmResult<()> {
+        tracing::debug!("net.wie.CardCanvas::pointerPressed({this:?}, {x}, {y})");
+
+        Self::pointer_notify(jvm, this, PointerEventType::PointerPressed, x, y).await
+    }
+
+    async fn pointer_dragged(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, x: i32, y: i32) -> JvmResult<()> {
+        tracing::debug!("net.wie.CardCanvas::pointerDragged({this:?}, {x}, {y})");
+
+        Self::pointer_notify(jvm, this, PointerEventType::PointerDragged, x, y).await
+    }
+
+    async fn pointer_released(jvm: &Jvm, _context: &mut WieJvmContext, this: ClassInstanceRef<Self>, x: i32, y: i32) -> JvmResult<()> {
+        tracing::debug!("net.wie.CardCanvas::pointerReleased({this:?}, {x}, {y})");
+
+        Self::pointer_notify(jvm, this, PointerEventType::PointerReleased, x, y).await
+    }
+
+    async fn pointer_notify(jvm: &Jvm, this: ClassInstanceRef<Self>, r#type: PointerEventType, x: i32, y: i32) -> JvmResult<()> {
+        let cards = jvm.get_field(&this, "cards", "Ljava/util/Vector;").await?;
+        let length = jvm.invoke_virtual(&cards, "size", "()I", ()).await?;
+
+        for i in 0..length {
+            let card = jvm.invoke_virtual(&cards, "elementAt", "(I)Ljava/lang/Object;", (i,)).await?;
+            let propagate: bool = jvm.invoke_virtual(&card, "pointerNotify", "(III)Z", (r#type as i32, x, y)).await?;
+
+            if !propagate {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn push_card(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>, c: ClassInstanceRef<Card>) -> JvmResult<()> {
         tracing::debug!("net.wie.CardCanvas::pushCard({this:?}, {c:?})");
 
@@ -29,14 +29,17 @@ impl Network {
         }
     }
 
+    // the host network is always up, there's no data session to bring up
     async fn connect(_: &Jvm, _: &mut WieJvmContext) -> JvmResult<i32> {
-        tracing::warn!("stub org.kwis.msf.io.Network::connect()");
+        tracing::debug!("org.kwis.msf.io.Network::connect()");
 
-        Ok(-1)
+        Ok(0)
     }
 
-    async fn disconnect(_: &Jvm, _: &mut WieJvmContext) -> JvmResult<()> {
-        tracing::warn!("stub org.kwis.msf.io.Network::disconnect()");
+    async fn disconnect(_: &Jvm, context: &mut WieJvmContext) -> JvmResult<()> {
+        tracing::debug!("org.kwis.msf.io.Network::disconnect()");
+
+        context.system().sockets().close_all();
 
         Ok(())
     }
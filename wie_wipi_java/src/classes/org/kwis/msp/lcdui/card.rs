@@ -30,6 +30,7 @@ impl Card {
                 JavaMethodProto::new("serviceRepaints", "()V", Self::service_repaints, Default::default()),
                 JavaMethodProto::new("showNotify", "(Z)V", Self::show_notify, Default::default()),
                 JavaMethodProto::new("keyNotify", "(II)Z", Self::key_notify, Default::default()),
+                JavaMethodProto::new("pointerNotify", "(III)Z", Self::pointer_notify, Default::default()),
                 JavaMethodProto::new_abstract("paint", "(Lorg/kwis/msp/lcdui/Graphics;)V", Default::default()),
                 // wie private
                 JavaMethodProto::new("setCanvas", "(Ljavax/microedition/lcdui/Canvas;)V", Self::set_canvas, Default::default()),
@@ -167,6 +168,12 @@ impl Card {
         Ok(false)
     }
 
+    async fn pointer_notify(_: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Card>, r#type: i32, x: i32, y: i32) -> JvmResult<bool> {
+        tracing::debug!("org.kwis.msp.lcdui.Card::pointerNotify({this:?}, {type}, {x}, {y})");
+
+        Ok(false)
+    }
+
     async fn set_canvas(jvm: &Jvm, _: &mut WieJvmContext, mut this: ClassInstanceRef<Card>, canvas: ClassInstanceRef<Canvas>) -> JvmResult<()> {
         tracing::debug!("org.kwis.msp.lcdui.Card::setCanvas({this:?}, {canvas:?})");
 
@@ -21,6 +21,12 @@ impl Font {
                 JavaMethodProto::new("<clinit>", "()V", Self::cl_init, MethodAccessFlags::STATIC),
                 JavaMethodProto::new("<init>", "(Ljavax/microedition/lcdui/Font;)V", Self::init, Default::default()),
                 JavaMethodProto::new("getHeight", "()I", Self::get_height, Default::default()),
+                JavaMethodProto::new("getAscent", "()I", Self::get_ascent, Default::default()),
+                JavaMethodProto::new("getDescent", "()I", Self::get_descent, Default::default()),
+                JavaMethodProto::new("getBaselinePosition", "()I", Self::get_baseline_position, Default::default()),
+                JavaMethodProto::new("getFace", "()I", Self::get_face, Default::default()),
+                JavaMethodProto::new("getStyle", "()I", Self::get_style, Default::default()),
+                JavaMethodProto::new("getSize", "()I", Self::get_size, Default::default()),
                 JavaMethodProto::new(
                     "getDefaultFont",
                     "()Lorg/kwis/msp/lcdui/Font;",
@@ -96,6 +102,54 @@ impl Font {
         jvm.invoke_virtual(&midp_font, "getHeight", "()I", ()).await
     }
 
+    async fn get_ascent(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("org.kwis.msp.lcdui.Font::getAscent");
+
+        let midp_font = Self::midp_font(jvm, &this).await?;
+
+        let font = MidpFont::font(jvm, &midp_font).await?;
+
+        Ok(context.system().font_pack().get(font).ascent() as _)
+    }
+
+    async fn get_descent(jvm: &Jvm, context: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("org.kwis.msp.lcdui.Font::getDescent");
+
+        let midp_font = Self::midp_font(jvm, &this).await?;
+
+        let font = MidpFont::font(jvm, &midp_font).await?;
+
+        Ok(context.system().font_pack().get(font).descent() as _)
+    }
+
+    async fn get_baseline_position(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("org.kwis.msp.lcdui.Font::getBaselinePosition");
+
+        let midp_font = jvm.get_field(&this, "midpFont", "Ljavax/microedition/lcdui/Font;").await?;
+        jvm.invoke_virtual(&midp_font, "getBaselinePosition", "()I", ()).await
+    }
+
+    async fn get_face(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("org.kwis.msp.lcdui.Font::getFace");
+
+        let midp_font = jvm.get_field(&this, "midpFont", "Ljavax/microedition/lcdui/Font;").await?;
+        jvm.invoke_virtual(&midp_font, "getFace", "()I", ()).await
+    }
+
+    async fn get_style(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("org.kwis.msp.lcdui.Font::getStyle");
+
+        let midp_font = jvm.get_field(&this, "midpFont", "Ljavax/microedition/lcdui/Font;").await?;
+        jvm.invoke_virtual(&midp_font, "getStyle", "()I", ()).await
+    }
+
+    async fn get_size(jvm: &Jvm, _: &mut WieJvmContext, this: ClassInstanceRef<Self>) -> JvmResult<i32> {
+        tracing::debug!("org.kwis.msp.lcdui.Font::getSize");
+
+        let midp_font = jvm.get_field(&this, "midpFont", "Ljavax/microedition/lcdui/Font;").await?;
+        jvm.invoke_virtual(&midp_font, "getSize", "()I", ()).await
+    }
+
     async fn get_default_font(jvm: &Jvm, _: &mut WieJvmContext) -> JvmResult<ClassInstanceRef<Self>> {
         tracing::debug!("org.kwis.msp.lcdui.Font::getDefaultFont");
 
@@ -152,7 +152,12 @@ impl Graphics {
         tracing::debug!("org.kwis.msp.lcdui.Graphics::setFont({this:?}, {font:?})");
 
         let midp_graphics = jvm.get_field(&this, "midpGraphics", "Ljavax/microedition/lcdui/Graphics;").await?;
-        let midp_font = Font::midp_font(jvm, &font).await?;
+        let midp_font: ClassInstanceRef<MidpFont> = if font.is_null() {
+            jvm.invoke_static("javax/microedition/lcdui/Font", "getDefaultFont", "()Ljavax/microedition/lcdui/Font;", ())
+                .await?
+        } else {
+            Font::midp_font(jvm, &font).await?
+        };
 
         jvm.invoke_virtual(&midp_graphics, "setFont", "(Ljavax/microedition/lcdui/Font;)V", (midp_font,))
             .await